@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::{fuzz_mutator, fuzz_target, fuzzer_mutate};
+
+fuzz_target!(|data: &[u8]| {
+    rkyv_fuzz::check(data);
+});
+
+fuzz_mutator!(|data: &mut [u8], size: usize, max_size: usize, seed: u32| {
+    match rkyv_fuzz::mutate(data, size, max_size, seed) {
+        Some(size) => size,
+        None => fuzzer_mutate(data, size, max_size),
+    }
+});
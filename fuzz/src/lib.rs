@@ -0,0 +1,291 @@
+//! Archived types and structure-aware mutations for the rkyv fuzz targets.
+//!
+//! Every fuzz input starts with a selector byte which picks the type to
+//! access, followed by the bytes of the archive. Most random bytes fail the
+//! first bounds check of the root, so [`mutate`] starts from valid archives
+//! and corrupts the parts of them that validation has to reject: relative
+//! pointer offsets, lengths, and the control bytes of swiss tables.
+
+use std::{collections::HashMap, num::NonZeroU32, rc::Rc};
+
+use rkyv::{
+    bytecheck::CheckBytes,
+    de::Unify,
+    from_bytes,
+    rancor::{Failure, Strategy},
+    ser::AllocSerializer,
+    to_bytes,
+    util::AlignedVec,
+    validation::validators::DefaultValidator,
+    with::Niche,
+    Archive, Deserialize, Serialize,
+};
+
+/// `Option`s which are archived with niches.
+#[derive(Archive, Serialize, Deserialize)]
+#[archive(check_bytes)]
+pub struct Niches {
+    #[with(Niche)]
+    pub boxed: Option<Box<String>>,
+    #[with(Niche)]
+    pub nonzero: Option<NonZeroU32>,
+}
+
+/// A value which contains every other fuzzed type.
+#[derive(Archive, Serialize, Deserialize)]
+#[archive(check_bytes)]
+pub struct Composite {
+    pub strings: Vec<String>,
+    pub map: HashMap<String, Vec<u32>>,
+    pub shared: Vec<Rc<String>>,
+    pub niches: Vec<Niches>,
+}
+
+// `BTreeMap` is not fuzzed yet because `ArchivedBTreeMap` does not implement
+// `CheckBytes` while its validation is disabled.
+
+/// The number of types which can be selected by the first byte of an input.
+pub const TYPE_COUNT: u8 = 6;
+
+/// The size of the relative pointer offsets and lengths in an archive.
+///
+/// The fuzz targets always use 32-bit pointers.
+const WORD_SIZE: usize = 4;
+
+/// Accesses the archive in `data` as the type picked by its selector byte.
+///
+/// If the archive passes validation, it is also deserialized. Deserializing
+/// reads every part of the archived value, so any memory which validation
+/// accepted without checking is touched and caught by the sanitizers.
+pub fn check(data: &[u8]) {
+    let (selector, archive) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+
+    let mut bytes = AlignedVec::with_capacity(archive.len());
+    bytes.extend_from_slice(archive);
+
+    match *selector % TYPE_COUNT {
+        0 => check_as::<String>(&bytes),
+        1 => check_as::<Vec<String>>(&bytes),
+        2 => check_as::<HashMap<String, u32>>(&bytes),
+        3 => check_as::<Vec<Rc<String>>>(&bytes),
+        4 => check_as::<Niches>(&bytes),
+        _ => check_as::<Composite>(&bytes),
+    }
+}
+
+fn check_as<T>(bytes: &[u8])
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<DefaultValidator, Failure>>
+        + Deserialize<T, Strategy<Unify, Failure>>,
+{
+    let _ = from_bytes::<T, Failure>(bytes);
+}
+
+/// A small, deterministic random number generator.
+///
+/// libFuzzer passes a seed to each mutation so that mutations can be
+/// reproduced.
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    /// Creates a new random number generator from a seed.
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed | 1 }
+    }
+
+    /// Returns the next random number.
+    pub fn next_u32(&mut self) -> u32 {
+        // xorshift32
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+
+    /// Returns a random number less than `n`, or zero if `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            self.next_u32() as usize % n
+        }
+    }
+}
+
+fn gen_string(rng: &mut Rng) -> String {
+    // Strings up to eight bytes long are stored inline, so generate both short
+    // and long strings.
+    let len = if rng.below(2) == 0 {
+        rng.below(9)
+    } else {
+        rng.below(64)
+    };
+    (0..len)
+        .map(|_| match rng.below(16) {
+            0 => 'é',
+            1 => '🦀',
+            _ => (b'a' + rng.below(26) as u8) as char,
+        })
+        .collect()
+}
+
+fn gen_vec<T>(rng: &mut Rng, max_len: usize, f: fn(&mut Rng) -> T) -> Vec<T> {
+    let len = rng.below(max_len + 1);
+    (0..len).map(|_| f(rng)).collect()
+}
+
+fn gen_map(rng: &mut Rng) -> HashMap<String, u32> {
+    // More than 16 entries makes the table use more than one control group.
+    let len = rng.below(40);
+    (0..len)
+        .map(|_| (gen_string(rng), rng.next_u32()))
+        .collect()
+}
+
+fn gen_shared(rng: &mut Rng) -> Vec<Rc<String>> {
+    let pool = gen_vec(rng, 3, gen_string)
+        .into_iter()
+        .map(Rc::new)
+        .collect::<Vec<_>>();
+    if pool.is_empty() {
+        return Vec::new();
+    }
+    let len = rng.below(9);
+    (0..len)
+        .map(|_| pool[rng.below(pool.len())].clone())
+        .collect()
+}
+
+fn gen_niches(rng: &mut Rng) -> Niches {
+    Niches {
+        boxed: if rng.below(2) == 0 {
+            None
+        } else {
+            Some(Box::new(gen_string(rng)))
+        },
+        nonzero: NonZeroU32::new(rng.next_u32() & 0xff),
+    }
+}
+
+fn gen_composite(rng: &mut Rng) -> Composite {
+    Composite {
+        strings: gen_vec(rng, 8, gen_string),
+        map: (0..rng.below(20))
+            .map(|_| (gen_string(rng), gen_vec(rng, 4, Rng::next_u32)))
+            .collect(),
+        shared: gen_shared(rng),
+        niches: gen_vec(rng, 4, gen_niches),
+    }
+}
+
+fn serialize<T>(value: &T) -> AlignedVec
+where
+    T: Serialize<Strategy<AllocSerializer<256>, Failure>>,
+{
+    to_bytes::<_, 256, Failure>(value).expect("failed to serialize value")
+}
+
+/// Generates a valid archive of the type picked by `selector`.
+pub fn generate(selector: u8, rng: &mut Rng) -> AlignedVec {
+    match selector % TYPE_COUNT {
+        0 => serialize(&gen_string(rng)),
+        1 => serialize(&gen_vec(rng, 8, gen_string)),
+        2 => serialize(&gen_map(rng)),
+        3 => serialize(&gen_shared(rng)),
+        4 => serialize(&gen_niches(rng)),
+        _ => serialize(&gen_composite(rng)),
+    }
+}
+
+/// Points the word at a random aligned position to another position.
+///
+/// The target is usually inside the archive, but may also be the word itself
+/// (a null pointer), the end of the archive, or somewhere outside of it.
+fn retarget_offset(archive: &mut [u8], rng: &mut Rng) {
+    let pos = rng.below(archive.len() / WORD_SIZE) * WORD_SIZE;
+    let target = match rng.below(8) {
+        0 => pos as i64,
+        1 => archive.len() as i64,
+        2 => -(rng.below(64) as i64),
+        3 => (archive.len() + rng.below(64)) as i64,
+        _ => rng.below(archive.len()) as i64,
+    };
+    let offset = (target - pos as i64) as i32;
+    archive[pos..pos + WORD_SIZE].copy_from_slice(&offset.to_le_bytes());
+}
+
+/// Replaces the word at a random aligned position with a length near its
+/// current value or at a boundary.
+fn tweak_length(archive: &mut [u8], rng: &mut Rng) {
+    let pos = rng.below(archive.len() / WORD_SIZE) * WORD_SIZE;
+    let mut word = [0; WORD_SIZE];
+    word.copy_from_slice(&archive[pos..pos + WORD_SIZE]);
+    let len = u32::from_le_bytes(word);
+    let new_len = match rng.below(9) {
+        0 => len.wrapping_add(1),
+        1 => len.wrapping_sub(1),
+        2 => len.wrapping_mul(2),
+        3 => len / 2,
+        4 => 0,
+        5 => u32::MAX,
+        6 => i32::MAX as u32,
+        7 => 1 << 31,
+        _ => archive.len() as u32,
+    };
+    archive[pos..pos + WORD_SIZE].copy_from_slice(&new_len.to_le_bytes());
+}
+
+/// Replaces a random byte with a swiss table control byte.
+///
+/// Control bytes mark buckets as empty (`0xff`), deleted (`0x80`), or full
+/// with the top seven bits of the hash of their key.
+fn flip_control_byte(archive: &mut [u8], rng: &mut Rng) {
+    let pos = rng.below(archive.len());
+    archive[pos] = match rng.below(4) {
+        0 => 0xff,
+        1 => 0x80,
+        2 => archive[pos] ^ 0x80,
+        _ => rng.next_u32() as u8 & 0x7f,
+    };
+}
+
+/// Mutates the input in `data[..size]`, which may grow up to `max_size`.
+///
+/// Returns the new size of the input, or `None` if the input should be
+/// mutated by libFuzzer instead.
+pub fn mutate(
+    data: &mut [u8],
+    size: usize,
+    max_size: usize,
+    seed: u32,
+) -> Option<usize> {
+    let mut rng = Rng::new(seed);
+
+    if size <= WORD_SIZE || rng.below(16) == 0 {
+        // Start over from a valid archive
+        let selector = rng.next_u32() as u8 % TYPE_COUNT;
+        let archive = generate(selector, &mut rng);
+        let new_size = archive.len() + 1;
+        if new_size > max_size {
+            return None;
+        }
+        data[0] = selector;
+        data[1..new_size].copy_from_slice(&archive);
+        return Some(new_size);
+    }
+
+    let archive = &mut data[1..size];
+    match rng.below(4) {
+        0 => retarget_offset(archive, &mut rng),
+        1 => tweak_length(archive, &mut rng),
+        2 => flip_control_byte(archive, &mut rng),
+        _ => return None,
+    }
+    Some(size)
+}
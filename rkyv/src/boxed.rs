@@ -243,15 +243,21 @@ impl BoxResolver {
 
 #[cfg(feature = "bytecheck")]
 mod verify {
+    use core::ptr::addr_of;
+
     use bytecheck::{
-        rancor::{Error, Fallible},
+        rancor::{Error, Fallible, ResultExt as _, Strategy},
         CheckBytes, Verify,
     };
 
     use crate::{
         boxed::ArchivedBox,
-        validation::{ArchiveContext, ArchiveContextExt, LayoutRaw},
-        ArchivePointee,
+        lazy::Checked,
+        validation::{
+            path::PointeeCheckContext, validators::DefaultValidator,
+            ArchiveContext, ArchiveContextExt, LayoutRaw,
+        },
+        ArchivePointee, RelPtr,
     };
 
     unsafe impl<T, C> Verify<C> for ArchivedBox<T>
@@ -265,10 +271,15 @@ mod verify {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
             let ptr =
                 unsafe { context.bounds_check_subtree_rel_ptr(&self.ptr)? };
+            context.check_pointee_collection(ptr)?;
 
             let range = unsafe { context.push_prefix_subtree(ptr)? };
             unsafe {
-                T::check_bytes(ptr, context)?;
+                T::check_bytes(ptr, context).with_trace(|| {
+                    PointeeCheckContext {
+                        address: ptr as *const u8 as usize,
+                    }
+                })?;
             }
             unsafe {
                 context.pop_subtree_range(range)?;
@@ -277,4 +288,28 @@ mod verify {
             Ok(())
         }
     }
+
+    impl<'a, T> Checked<'a, ArchivedBox<T>>
+    where
+        T: ArchivePointee + LayoutRaw + ?Sized,
+    {
+        /// Returns the boxed value.
+        ///
+        /// Only the pointer to the value is checked.
+        pub fn get<E>(self) -> Result<Checked<'a, T>, E>
+        where
+            T::ArchivedMetadata: CheckBytes<Strategy<DefaultValidator, E>>,
+            E: Error,
+        {
+            let access = self.access();
+            let boxed = self.as_ptr();
+            let ptr = access.with_context(|validator| unsafe {
+                let context = Strategy::<_, E>::wrap(validator);
+                RelPtr::check_bytes(addr_of!((*boxed).ptr), context)?;
+                context.bounds_check_subtree_rel_ptr(&(*boxed).ptr)
+            })?;
+
+            Ok(unsafe { Checked::new(access, ptr) })
+        }
+    }
 }
@@ -10,7 +10,7 @@ use core::{
     hash::{Hash, Hasher},
     iter::FusedIterator,
     marker::PhantomData,
-    ops::Index,
+    ops::{Bound, Index, RangeBounds},
     ptr::NonNull,
 };
 
@@ -92,6 +92,17 @@ impl NodeHeader {
     fn len(&self) -> usize {
         split_meta(self.meta.to_native()).1
     }
+
+    /// Returns the next leaf node, or `None` if this is the last leaf node.
+    #[inline]
+    fn next_leaf(&self) -> Option<&NodeHeader> {
+        debug_assert!(self.is_leaf());
+        if self.ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { &*self.ptr.as_ptr() })
+        }
+    }
 }
 
 #[inline]
@@ -219,6 +230,36 @@ impl NodeHeader {
         debug_assert!(self.is_leaf());
         unsafe { &*self.classify_leaf_ptr() }
     }
+
+    /// Finds the first entry in the subtree rooted at this node for which
+    /// `pred` returns `false`.
+    ///
+    /// `pred` must return `true` for every key before some key and `false` for
+    /// every key after it. Returns the leaf node which the entry would be
+    /// found in and its index in that leaf. The index is the length of the
+    /// leaf if the entry is in the next leaf node or there is no such entry.
+    fn partition_point<K, V>(
+        &self,
+        pred: impl Fn(&K) -> bool,
+    ) -> (&NodeHeader, usize) {
+        let mut current = self;
+        loop {
+            match current.classify::<K, V>() {
+                ClassifiedNode::Inner(node) => {
+                    let i = node.tail.partition_point(|entry| pred(&entry.key));
+                    current = if i == 0 {
+                        unsafe { &*node.header.ptr.as_ptr() }
+                    } else {
+                        unsafe { &*node.tail[i - 1].ptr.as_ptr() }
+                    };
+                }
+                ClassifiedNode::Leaf(node) => {
+                    let i = node.tail.partition_point(|entry| pred(&entry.key));
+                    break (current, i);
+                }
+            }
+        }
+    }
 }
 
 /// An archived [`BTreeMap`](std::collections::BTreeMap).
@@ -280,6 +321,83 @@ impl<K, V> ArchivedBTreeMap<K, V> {
         }
     }
 
+    #[inline]
+    fn last(&self) -> NonNull<NodeHeader> {
+        if let Some(mut node) = self.root() {
+            while let ClassifiedNode::Inner(inner) = node {
+                let next = match inner.tail.last() {
+                    Some(entry) => unsafe { &*entry.ptr.as_ptr() },
+                    None => unsafe { &*inner.header.ptr.as_ptr() },
+                };
+                node = next.classify();
+            }
+            match node {
+                ClassifiedNode::Leaf(leaf) => NonNull::from(&leaf.header),
+                ClassifiedNode::Inner(_) => unsafe {
+                    core::hint::unreachable_unchecked()
+                },
+            }
+        } else {
+            NonNull::dangling()
+        }
+    }
+
+    /// Returns the position of the end of the map.
+    #[inline]
+    fn end(&self) -> (NonNull<NodeHeader>, usize) {
+        let last = self.last();
+        if self.is_empty() {
+            (last, 0)
+        } else {
+            (last, unsafe { last.as_ref().len() })
+        }
+    }
+
+    /// Returns the position of the first entry for which `pred` returns
+    /// `false`, or the end of the map if there is no such entry.
+    ///
+    /// Positions at the end of a leaf node are moved to the start of the next
+    /// leaf node, so each entry has exactly one position.
+    fn position(
+        &self,
+        pred: impl Fn(&K) -> bool,
+    ) -> (NonNull<NodeHeader>, usize) {
+        if self.is_empty() {
+            return (NonNull::dangling(), 0);
+        }
+
+        let root = unsafe { &*self.root.as_ptr() };
+        let (leaf, index) = root.partition_point::<K, V>(pred);
+        if index == leaf.len() {
+            if let Some(next) = leaf.next_leaf() {
+                return (NonNull::from(next), 0);
+            }
+        }
+        (NonNull::from(leaf), index)
+    }
+
+    #[inline]
+    fn raw_iter(
+        &self,
+        (front, front_index): (NonNull<NodeHeader>, usize),
+        (back, back_index): (NonNull<NodeHeader>, usize),
+    ) -> RawIter<'_, K, V> {
+        let root = if self.is_empty() {
+            NonNull::dangling()
+        } else {
+            unsafe { NonNull::new_unchecked(self.root.as_ptr()) }
+        };
+        RawIter {
+            root,
+            front,
+            front_index,
+            back,
+            back_index,
+            remaining: self.len(),
+            _phantom: PhantomData,
+        }
+    }
+
     /// Returns `true` if the map contains a value for the specified key.
     ///
     /// The key may be any borrowed form of the map's key type, but the ordering
@@ -352,6 +470,132 @@ impl<K, V> ArchivedBTreeMap<K, V> {
         }
     }
 
+    /// Returns the first key-value pair in the map. The key in this pair is
+    /// the minimum key in the map.
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        if self.is_empty() {
+            None
+        } else {
+            let leaf = unsafe { self.first().as_ref().classify_leaf::<K, V>() };
+            let entry = &leaf.tail[0];
+            Some((&entry.key, &entry.value))
+        }
+    }
+
+    /// Returns the last key-value pair in the map. The key in this pair is the
+    /// maximum key in the map.
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        if self.is_empty() {
+            None
+        } else {
+            let leaf = unsafe { self.last().as_ref().classify_leaf::<K, V>() };
+            let entry = &leaf.tail[leaf.tail.len() - 1];
+            Some((&entry.key, &entry.value))
+        }
+    }
+
+    /// Returns the first key-value pair in the map whose key is above the
+    /// given bound.
+    ///
+    /// For `Included(x)`, this is the first entry with a key greater than or
+    /// equal to `x`. For `Excluded(x)`, this is the first entry with a key
+    /// greater than `x`. For `Unbounded`, this is the first entry in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but the ordering
+    /// on the borrowed form _must_ match the ordering on the key type.
+    pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+    {
+        let front = match bound {
+            Bound::Included(key) => self.position(|k| k.borrow() < key),
+            Bound::Excluded(key) => self.position(|k| k.borrow() <= key),
+            Bound::Unbounded => (self.first(), 0),
+        };
+        self.raw_iter(front, self.end()).next()
+    }
+
+    /// Returns the last key-value pair in the map whose key is below the given
+    /// bound.
+    ///
+    /// For `Included(x)`, this is the last entry with a key less than or equal
+    /// to `x`. For `Excluded(x)`, this is the last entry with a key less than
+    /// `x`. For `Unbounded`, this is the last entry in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but the ordering
+    /// on the borrowed form _must_ match the ordering on the key type.
+    pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+    {
+        let back = match bound {
+            Bound::Included(key) => self.position(|k| k.borrow() <= key),
+            Bound::Excluded(key) => self.position(|k| k.borrow() < key),
+            Bound::Unbounded => self.end(),
+        };
+        self.raw_iter((self.first(), 0), back).next_back()
+    }
+
+    /// Gets an iterator over a sub-range of the entries of the map, sorted by
+    /// key.
+    ///
+    /// The simplest way is to use the range syntax `min..max`, thus
+    /// `range(min..max)` will yield entries from `min` (inclusive) to `max`
+    /// (exclusive). The range may also be entered as `(Bound<T>, Bound<T>)`,
+    /// so for example `range((Excluded(4), Included(10)))` will yield a
+    /// left-exclusive, right-inclusive range from 4 to 10.
+    ///
+    /// The key may be any borrowed form of the map's key type, but the ordering
+    /// on the borrowed form _must_ match the ordering on the key type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`. Panics if range `start == end` and both
+    /// bounds are `Excluded`.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(start), Bound::Excluded(end)) if start == end => {
+                panic!(
+                    "range start and end are equal and excluded in \
+                     ArchivedBTreeMap"
+                )
+            }
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Included(end) | Bound::Excluded(end),
+            ) if start > end => {
+                panic!(
+                    "range start is greater than range end in ArchivedBTreeMap"
+                )
+            }
+            _ => (),
+        }
+
+        let front = match range.start_bound() {
+            Bound::Included(start) => self.position(|k| k.borrow() < start),
+            Bound::Excluded(start) => self.position(|k| k.borrow() <= start),
+            Bound::Unbounded => (self.first(), 0),
+        };
+        let back = match range.end_bound() {
+            Bound::Included(end) => self.position(|k| k.borrow() <= end),
+            Bound::Excluded(end) => self.position(|k| k.borrow() < end),
+            Bound::Unbounded => self.end(),
+        };
+
+        Range {
+            inner: self.raw_iter(front, back),
+        }
+    }
+
     /// Returns `true` if the map contains no elements.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -362,7 +606,7 @@ impl<K, V> ArchivedBTreeMap<K, V> {
     #[inline]
     pub fn iter(&self) -> Iter<'_, K, V> {
         Iter {
-            inner: RawIter::new(self.first(), 0, self.len()),
+            inner: self.raw_iter((self.first(), 0), self.end()),
         }
     }
 
@@ -370,7 +614,7 @@ impl<K, V> ArchivedBTreeMap<K, V> {
     #[inline]
     pub fn keys(&self) -> Keys<'_, K, V> {
         Keys {
-            inner: RawIter::new(self.first(), 0, self.len()),
+            inner: self.raw_iter((self.first(), 0), self.end()),
         }
     }
 
@@ -384,7 +628,7 @@ impl<K, V> ArchivedBTreeMap<K, V> {
     #[inline]
     pub fn values(&self) -> Values<'_, K, V> {
         Values {
-            inner: RawIter::new(self.first(), 0, self.len()),
+            inner: self.raw_iter((self.first(), 0), self.end()),
         }
     }
 
@@ -703,21 +947,28 @@ impl<K: PartialOrd, V: PartialOrd> PartialOrd for ArchivedBTreeMap<K, V> {
 
 // RawIter
 
+// Leaf nodes only point to the next leaf node. To iterate from the back, the
+// previous leaf node is found by searching the tree for the key before the
+// first key of the current leaf node. This is why the iterators are only
+// double-ended when `K: Ord`.
 struct RawIter<'a, K, V> {
-    leaf: NonNull<NodeHeader>,
-    index: usize,
+    root: NonNull<NodeHeader>,
+    // The position of the next entry to yield from the front
+    front: NonNull<NodeHeader>,
+    front_index: usize,
+    // The position after the next entry to yield from the back
+    back: NonNull<NodeHeader>,
+    back_index: usize,
+    // The number of entries left when iterating the whole map, and an upper
+    // bound when iterating a range
     remaining: usize,
     _phantom: PhantomData<(&'a K, &'a V)>,
 }
 
 impl<'a, K, V> RawIter<'a, K, V> {
-    fn new(leaf: NonNull<NodeHeader>, index: usize, remaining: usize) -> Self {
-        Self {
-            leaf,
-            index,
-            remaining,
-            _phantom: PhantomData,
-        }
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.front == self.back && self.front_index == self.back_index
     }
 }
 
@@ -726,25 +977,24 @@ impl<'a, K, V> Iterator for RawIter<'a, K, V> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.remaining == 0 {
+        if self.is_empty() {
             None
         } else {
             unsafe {
-                // SAFETY: self.leaf is valid when self.remaining > 0
-                // SAFETY: self.leaf always points to a leaf node header
-                let leaf = self.leaf.as_ref().classify_leaf::<K, V>();
-                if self.index == leaf.tail.len() {
-                    self.index = 0;
-                    // SAFETY: when self.remaining > 0 this is guaranteed to
-                    // point to a leaf node
-                    self.leaf = NonNull::new_unchecked(
+                // SAFETY: self.front always points to a leaf node header, and
+                // self.front_index is in bounds when the iterator is not empty
+                let leaf = self.front.as_ref().classify_leaf::<K, V>();
+                let result = &leaf.tail[self.front_index];
+                self.front_index += 1;
+                self.remaining -= 1;
+                if self.front_index == leaf.tail.len() && !self.is_empty() {
+                    // SAFETY: the back of the iterator is after this leaf node,
+                    // so this is guaranteed to point to a leaf node
+                    self.front = NonNull::new_unchecked(
                         leaf.header.ptr.as_ptr() as *mut _,
                     );
+                    self.front_index = 0;
                 }
-                let result =
-                    &self.leaf.as_ref().classify_leaf().tail[self.index];
-                self.index += 1;
-                self.remaining -= 1;
                 Some((&result.key, &result.value))
             }
         }
@@ -756,10 +1006,41 @@ impl<'a, K, V> Iterator for RawIter<'a, K, V> {
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for RawIter<'a, K, V> {}
+impl<'a, K: Ord, V> DoubleEndedIterator for RawIter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            None
+        } else {
+            unsafe {
+                if self.back_index == 0 {
+                    // SAFETY: the front of the iterator is before this leaf
+                    // node, so the map is not empty and there is a previous
+                    // leaf node
+                    let first =
+                        &self.back.as_ref().classify_leaf::<K, V>().tail[0].key;
+                    let (previous, len) = self
+                        .root
+                        .as_ref()
+                        .partition_point::<K, V>(|k| k < first);
+                    self.back = NonNull::from(previous);
+                    self.back_index = len;
+                }
+                self.back_index -= 1;
+                self.remaining -= 1;
+                let leaf = self.back.as_ref().classify_leaf::<K, V>();
+                let result = &leaf.tail[self.back_index];
+                Some((&result.key, &result.value))
+            }
+        }
+    }
+}
+
 impl<'a, K, V> FusedIterator for RawIter<'a, K, V> {}
 
 /// An iterator over the key-value pairs of an archived B-tree map.
+///
+/// Iterating from the back requires `K: Ord`.
 pub struct Iter<'a, K, V> {
     inner: RawIter<'a, K, V>,
 }
@@ -778,10 +1059,19 @@ impl<'a, K, V> Iterator for Iter<'a, K, V> {
     }
 }
 
+impl<'a, K: Ord, V> DoubleEndedIterator for Iter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
 impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
 impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
 
 /// An iterator over the keys of an archived B-tree map.
+///
+/// Iterating from the back requires `K: Ord`.
 pub struct Keys<'a, K, V> {
     inner: RawIter<'a, K, V>,
 }
@@ -800,10 +1090,19 @@ impl<'a, K, V> Iterator for Keys<'a, K, V> {
     }
 }
 
+impl<'a, K: Ord, V> DoubleEndedIterator for Keys<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
 impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
 impl<'a, K, V> FusedIterator for Keys<'a, K, V> {}
 
 /// An iterator over the values of an archived B-tree map.
+///
+/// Iterating from the back requires `K: Ord`.
 pub struct Values<'a, K, V> {
     inner: RawIter<'a, K, V>,
 }
@@ -822,5 +1121,46 @@ impl<'a, K, V> Iterator for Values<'a, K, V> {
     }
 }
 
+impl<'a, K: Ord, V> DoubleEndedIterator for Values<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
 impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
 impl<'a, K, V> FusedIterator for Values<'a, K, V> {}
+
+/// An iterator over a sub-range of the entries of an archived B-tree map.
+///
+/// This is created by [`ArchivedBTreeMap::range`].
+pub struct Range<'a, K, V> {
+    inner: RawIter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.inner.is_empty() {
+            (0, Some(0))
+        } else {
+            (1, Some(self.inner.remaining))
+        }
+    }
+}
+
+impl<'a, K: Ord, V> DoubleEndedIterator for Range<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, K, V> FusedIterator for Range<'a, K, V> {}
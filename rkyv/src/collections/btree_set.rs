@@ -1,9 +1,14 @@
 //! [`Archive`](crate::Archive) implementation for B-tree sets.
 
-use core::{borrow::Borrow, fmt};
+use core::{
+    borrow::Borrow,
+    fmt,
+    iter::FusedIterator,
+    ops::{Bound, RangeBounds},
+};
 
 use crate::{
-    collections::btree_map::{ArchivedBTreeMap, BTreeMapResolver, Keys},
+    collections::btree_map::{self, ArchivedBTreeMap, BTreeMapResolver, Keys},
     Portable,
 };
 
@@ -42,6 +47,18 @@ impl<K> ArchivedBTreeSet<K> {
         self.0.get_key_value(value).map(|(key, _)| key)
     }
 
+    /// Returns the first value in the set, which is the minimum value.
+    #[inline]
+    pub fn first(&self) -> Option<&K> {
+        self.0.first_key_value().map(|(key, _)| key)
+    }
+
+    /// Returns the last value in the set, which is the maximum value.
+    #[inline]
+    pub fn last(&self) -> Option<&K> {
+        self.0.last_key_value().map(|(key, _)| key)
+    }
+
     /// Returns `true` if the set contains no elements.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -60,6 +77,53 @@ impl<K> ArchivedBTreeSet<K> {
         self.0.len()
     }
 
+    /// Returns the first value in the set which is above the given bound.
+    ///
+    /// See [`ArchivedBTreeMap::lower_bound`] for more details.
+    #[inline]
+    pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<&K>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+    {
+        self.0.lower_bound(bound).map(|(key, _)| key)
+    }
+
+    /// Returns the last value in the set which is below the given bound.
+    ///
+    /// See [`ArchivedBTreeMap::upper_bound`] for more details.
+    #[inline]
+    pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<&K>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+    {
+        self.0.upper_bound(bound).map(|(key, _)| key)
+    }
+
+    /// Gets an iterator over a sub-range of the values of the set, in sorted
+    /// order.
+    ///
+    /// The value may be any borrowed form of the set's value type, but the
+    /// ordering on the borrowed form _must_ match the ordering on the value
+    /// type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`. Panics if range `start == end` and both
+    /// bounds are `Excluded`.
+    #[inline]
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K>
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            inner: self.0.range(range),
+        }
+    }
+
     /// Resolves a B-tree set from its length.
     ///
     /// # Safety
@@ -128,3 +192,33 @@ impl<'a, K> IntoIterator for &'a ArchivedBTreeSet<K> {
 
 /// The resolver for archived B-tree sets.
 pub struct BTreeSetResolver(BTreeMapResolver);
+
+/// An iterator over a sub-range of the values of an archived B-tree set.
+///
+/// This is created by [`ArchivedBTreeSet::range`].
+pub struct Range<'a, K> {
+    inner: btree_map::Range<'a, K, ()>,
+}
+
+impl<'a, K> Iterator for Range<'a, K> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K: Ord> DoubleEndedIterator for Range<'a, K> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<'a, K> FusedIterator for Range<'a, K> {}
@@ -2,5 +2,7 @@
 
 pub mod btree_map;
 pub mod btree_set;
+pub mod perfect_hash_map;
+pub mod sorted_vec_map;
 pub mod swiss_table;
 pub mod util;
@@ -0,0 +1,779 @@
+//! An archived hash map which uses a minimal perfect hash function.
+//!
+//! The perfect hash function is built with the "hash, displace, and compress"
+//! (CHD) algorithm. Keys are split into buckets of about two keys each, and
+//! each bucket is assigned a pair of displacements which place all of its keys
+//! into empty slots. Buckets with a single key are moved directly into the
+//! slots which are left over. Looking up a key takes one hash, one
+//! displacement lookup, and one key comparison.
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::Index,
+    slice,
+};
+
+use crate::{
+    collections::util::Entry,
+    hash::{hash_value, FxHasher64},
+    primitive::{ArchivedU32, ArchivedU64},
+    vec::{ArchivedVec, VecResolver},
+    Archive as _, Portable,
+};
+
+/// The average number of keys in each bucket.
+///
+/// Larger buckets make the displacement table smaller, but the last buckets
+/// with more than one key have to be placed into fewer empty slots. With two
+/// keys per bucket, the bounded displacement search almost always succeeds on
+/// the first seed.
+const KEYS_PER_BUCKET: usize = 2;
+
+#[inline]
+fn bucket_count(len: usize) -> usize {
+    len.div_ceil(KEYS_PER_BUCKET)
+}
+
+// The finalizer of splitmix64. This is a bijection, so different hashes always
+// mix to different values.
+#[inline]
+fn mix(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf_58_47_6d_1c_e4_e5_b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94_d0_49_bb_13_31_11_eb);
+    x ^ (x >> 31)
+}
+
+/// Splits the hash of a key into its bucket and two displacement factors.
+#[inline]
+fn split_hash(hash: u64, seed: u64) -> (u32, u32, u32) {
+    let a = mix(hash ^ seed);
+    let b = mix(a);
+    ((a >> 32) as u32, a as u32, b as u32)
+}
+
+#[inline]
+fn displace(f1: u32, f2: u32, d1: u32, d2: u32, len: usize) -> usize {
+    let index = (f1 as u64)
+        .wrapping_mul(d1 as u64)
+        .wrapping_add(f2 as u64)
+        .wrapping_add(d2 as u64);
+    (index % len as u64) as usize
+}
+
+/// An archived hash map which uses a minimal perfect hash function.
+///
+/// The entries of the map are stored in a single array with no empty slots.
+/// Looking up a key takes exactly one hash and one key comparison, which
+/// makes this map well-suited for lookup tables which are written once and
+/// read many times. Building the perfect hash function makes serializing
+/// slower than serializing an [`ArchivedHashMap`].
+///
+/// This is produced by serializing a map with
+/// [`AsPerfectHash`](crate::with::AsPerfectHash).
+///
+/// Validation checks that the displacement table has the right length and
+/// that every displacement is in range. It does not hash the keys, so a
+/// malformed map may fail to find some of its keys, but it can never read out
+/// of bounds.
+///
+/// [`ArchivedHashMap`]: crate::collections::swiss_table::ArchivedHashMap
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+pub struct ArchivedPerfectHashMap<K, V, H = FxHasher64> {
+    seed: ArchivedU64,
+    displacements: ArchivedVec<[ArchivedU32; 2]>,
+    entries: ArchivedVec<Entry<K, V>>,
+    _phantom: PhantomData<H>,
+}
+
+impl<K, V, H> ArchivedPerfectHashMap<K, V, H> {
+    /// Returns whether the hash map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of elements in the hash map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns an iterator over the key-value entries in the hash map.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys in the hash map.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the values in the hash map.
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Resolves an archived perfect hash map from a given length.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a perfect hash map
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: PerfectHashMapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.seed);
+        resolver.seed.resolve(pos + fp, (), fo);
+
+        let (fp, fo) = out_field!(out.displacements);
+        ArchivedVec::resolve_from_len(
+            bucket_count(len),
+            pos + fp,
+            resolver.displacements,
+            fo,
+        );
+
+        let (fp, fo) = out_field!(out.entries);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.entries, fo);
+
+        // PhantomData doesn't need to be initialized
+    }
+}
+
+impl<K, V, H: Hasher + Default> ArchivedPerfectHashMap<K, V, H> {
+    /// Returns the key-value pair corresponding to the supplied key using the
+    /// given comparison function.
+    #[inline]
+    pub fn get_key_value_with<Q, C>(&self, key: &Q, cmp: C) -> Option<(&K, &V)>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+
+        let (bucket, f1, f2) =
+            split_hash(hash_value::<Q, H>(key), self.seed.to_native());
+        let [d1, d2] =
+            &self.displacements[bucket as usize % self.displacements.len()];
+        let entry = &self.entries
+            [displace(f1, f2, d1.to_native(), d2.to_native(), len)];
+
+        if cmp(key, &entry.key) {
+            Some((&entry.key, &entry.value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value_with(key, |q, k| q == k.borrow())
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key using
+    /// the given comparison function.
+    #[inline]
+    pub fn get_with<Q, C>(&self, key: &Q, cmp: C) -> Option<&V>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        Some(self.get_key_value_with(key, cmp)?.1)
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Some(self.get_key_value(key)?.1)
+    }
+
+    /// Returns whether the hash map contains the given key.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+const _: () = {
+    use rancor::{fail, Error, Fallible};
+
+    use crate::{
+        ser::{Allocator, Writer},
+        util::ScratchVec,
+        Serialize,
+    };
+
+    /// The number of seeds to try before giving up on building a perfect hash
+    /// function.
+    const MAX_ATTEMPTS: u64 = 32;
+
+    /// The number of values tried for each displacement of a bucket with more
+    /// than one key. If a bucket can't be placed with any of them, the current
+    /// seed fails and the next seed is tried.
+    const MAX_DISPLACEMENT: u32 = 32;
+
+    #[derive(Debug)]
+    enum BuildError {
+        DuplicateHash,
+        TooManyAttempts { len: usize },
+    }
+
+    impl fmt::Display for BuildError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                BuildError::DuplicateHash => write!(
+                    f,
+                    "two keys have the same hash, so no perfect hash function can be built for them",
+                ),
+                BuildError::TooManyAttempts { len } => write!(
+                    f,
+                    "failed to build a perfect hash function for {} keys after {} attempts",
+                    len, MAX_ATTEMPTS,
+                ),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for BuildError {}
+
+    /// The scratch space used to build a perfect hash function.
+    struct Builder {
+        split: ScratchVec<(u32, u32, u32)>,
+        // The indices of the keys, grouped by bucket
+        keys: ScratchVec<usize>,
+        // The start of each bucket in `keys`, followed by the number of keys
+        starts: ScratchVec<usize>,
+        // The buckets in the order they are placed
+        order: ScratchVec<usize>,
+        // Slots claimed while trying a displacement are marked with the current
+        // generation so they don't have to be cleared after each try.
+        generations: ScratchVec<u64>,
+        generation: u64,
+        placed: ScratchVec<(usize, usize)>,
+    }
+
+    impl Builder {
+        /// # Safety
+        ///
+        /// The builder must be freed with the same allocator before any scratch
+        /// space allocated before it is freed.
+        unsafe fn new<A: Allocator<E> + ?Sized, E>(
+            allocator: &mut A,
+            len: usize,
+        ) -> Result<Self, E> {
+            let count = bucket_count(len);
+            unsafe {
+                let split = ScratchVec::new(allocator, len)?;
+                let keys = ScratchVec::new(allocator, len)?;
+                let starts = ScratchVec::new(allocator, count + 1)?;
+                let order = ScratchVec::new(allocator, count)?;
+                let mut generations = ScratchVec::new(allocator, len)?;
+                for _ in 0..len {
+                    generations.push(0);
+                }
+                let placed = ScratchVec::new(allocator, len)?;
+
+                Ok(Self {
+                    split,
+                    keys,
+                    starts,
+                    order,
+                    generations,
+                    generation: 0,
+                    placed,
+                })
+            }
+        }
+
+        /// # Safety
+        ///
+        /// The allocator must be the one the builder was created with.
+        unsafe fn free<A: Allocator<E> + ?Sized, E>(
+            self,
+            allocator: &mut A,
+        ) -> Result<(), E> {
+            unsafe {
+                self.placed.free(allocator)?;
+                self.generations.free(allocator)?;
+                self.order.free(allocator)?;
+                self.starts.free(allocator)?;
+                self.keys.free(allocator)?;
+                self.split.free(allocator)?;
+            }
+            Ok(())
+        }
+
+        /// Tries to build a perfect hash function with the given seed, and
+        /// returns whether it succeeded.
+        ///
+        /// On success, `displacements` contains the displacements of each
+        /// bucket and `slots` contains the index of the key in each slot.
+        fn try_build(
+            &mut self,
+            hashes: &[u64],
+            seed: u64,
+            displacements: &mut ScratchVec<[u32; 2]>,
+            slots: &mut ScratchVec<usize>,
+        ) -> bool {
+            let len = hashes.len();
+            let count = bucket_count(len);
+
+            self.split.clear();
+            for &hash in hashes {
+                self.split.push(split_hash(hash, seed));
+            }
+
+            // Group the keys by bucket with a counting sort
+            self.starts.clear();
+            for _ in 0..=count {
+                self.starts.push(0);
+            }
+            for &(bucket, _, _) in self.split.iter() {
+                self.starts[bucket as usize % count] += 1;
+            }
+            // Turn the counts into the ends of the buckets, then move each end
+            // back to the start of its bucket while placing its keys
+            let mut end = 0;
+            for start in self.starts[..count].iter_mut() {
+                end += *start;
+                *start = end;
+            }
+            self.starts[count] = len;
+            self.keys.clear();
+            for _ in 0..len {
+                self.keys.push(0);
+            }
+            for (key, &(bucket, _, _)) in self.split.iter().enumerate() {
+                let bucket = bucket as usize % count;
+                self.starts[bucket] -= 1;
+                self.keys[self.starts[bucket]] = key;
+            }
+
+            // Place the largest buckets first while there are the most empty
+            // slots
+            let starts = &self.starts;
+            self.order.clear();
+            for bucket in 0..count {
+                self.order.push(bucket);
+            }
+            self.order.sort_unstable_by(|&a, &b| {
+                let a_len = starts[a + 1] - starts[a];
+                let b_len = starts[b + 1] - starts[b];
+                b_len.cmp(&a_len).then(a.cmp(&b))
+            });
+
+            displacements.clear();
+            for _ in 0..count {
+                displacements.push([0; 2]);
+            }
+            slots.clear();
+            for _ in 0..len {
+                slots.push(usize::MAX);
+            }
+
+            let max_displacement = len.min(MAX_DISPLACEMENT as usize) as u32;
+            // Every slot before this one is full once the single-key buckets
+            // are reached
+            let mut free = 0;
+
+            'buckets: for &bucket in self.order.iter() {
+                let keys = &self.keys[starts[bucket]..starts[bucket + 1]];
+                match keys.len() {
+                    // Empty buckets are sorted last and need no displacement
+                    0 => break,
+                    // Single keys can be moved directly into an empty slot.
+                    // With a first displacement of 0, the key is placed at
+                    // `(f2 + d2) % len`.
+                    1 => {
+                        while slots[free] != usize::MAX {
+                            free += 1;
+                        }
+                        let (_, _, f2) = self.split[keys[0]];
+                        let d2 = (free + len - f2 as usize % len) % len;
+                        slots[free] = keys[0];
+                        displacements[bucket] = [0, d2 as u32];
+                        continue 'buckets;
+                    }
+                    _ => (),
+                }
+
+                for d1 in 0..max_displacement {
+                    'displacements: for d2 in 0..max_displacement {
+                        self.generation += 1;
+                        self.placed.clear();
+                        for &key in keys {
+                            let (_, f1, f2) = self.split[key];
+                            let slot = displace(f1, f2, d1, d2, len);
+                            if slots[slot] != usize::MAX
+                                || self.generations[slot] == self.generation
+                            {
+                                continue 'displacements;
+                            }
+                            self.generations[slot] = self.generation;
+                            self.placed.push((slot, key));
+                        }
+
+                        for &(slot, key) in self.placed.iter() {
+                            slots[slot] = key;
+                        }
+                        displacements[bucket] = [d1, d2];
+                        continue 'buckets;
+                    }
+                }
+
+                // This bucket's keys can't be separated with small
+                // displacements, so try the next seed instead.
+                return false;
+            }
+
+            true
+        }
+    }
+
+    /// Builds a perfect hash function for the given hashes and returns its
+    /// seed.
+    fn build<A, E>(
+        hashes: &[u64],
+        displacements: &mut ScratchVec<[u32; 2]>,
+        slots: &mut ScratchVec<usize>,
+        allocator: &mut A,
+    ) -> Result<u64, E>
+    where
+        A: Allocator<E> + ?Sized,
+        E: Error,
+    {
+        // Keys with the same hash can never be separated
+        let mut sorted = unsafe { ScratchVec::new(allocator, hashes.len())? };
+        sorted.extend_from_slice(hashes);
+        sorted.sort_unstable();
+        let duplicate = sorted.windows(2).any(|pair| pair[0] == pair[1]);
+        unsafe {
+            sorted.free(allocator)?;
+        }
+        if duplicate {
+            fail!(BuildError::DuplicateHash);
+        }
+
+        let mut builder = unsafe { Builder::new(allocator, hashes.len())? };
+        let mut result = None;
+        for attempt in 0..MAX_ATTEMPTS {
+            // Seeds are deterministic so that serializing the same map always
+            // produces the same archive.
+            let seed = attempt.wrapping_mul(0x9e_37_79_b9_7f_4a_7c_15);
+            if builder.try_build(hashes, seed, displacements, slots) {
+                result = Some(seed);
+                break;
+            }
+        }
+        unsafe {
+            builder.free(allocator)?;
+        }
+
+        match result {
+            Some(seed) => Ok(seed),
+            None => fail!(BuildError::TooManyAttempts { len: hashes.len() }),
+        }
+    }
+
+    impl<K, V, H: Hasher + Default> ArchivedPerfectHashMap<K, V, H> {
+        /// Serializes an iterator of key-value pairs as a perfect hash map.
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub fn serialize_from_iter<'a, I, KU, VU, S>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<PerfectHashMapResolver, S::Error>
+        where
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Fallible + Writer + Allocator + ?Sized,
+            S::Error: Error,
+        {
+            let len = iter.len();
+            let mut items = unsafe { ScratchVec::new(serializer, len)? };
+            for item in iter {
+                items.push(item);
+            }
+            let mut hashes = unsafe { ScratchVec::new(serializer, len)? };
+            for (key, _) in items.iter() {
+                hashes.push(hash_value::<KU, H>(key));
+            }
+
+            let mut displacements =
+                unsafe { ScratchVec::new(serializer, bucket_count(len))? };
+            let mut slots = unsafe { ScratchVec::new(serializer, len)? };
+            let seed =
+                build(&hashes, &mut displacements, &mut slots, serializer)?;
+
+            let displacements_resolver =
+                ArchivedVec::serialize_from_slice(&displacements, serializer)?;
+            let entries = ArchivedVec::serialize_from_iter(
+                slots.iter().map(|&i| Entry {
+                    key: items[i].0,
+                    value: items[i].1,
+                }),
+                serializer,
+            )?;
+
+            unsafe {
+                slots.free(serializer)?;
+                displacements.free(serializer)?;
+                hashes.free(serializer)?;
+                items.free(serializer)?;
+            }
+
+            Ok(PerfectHashMapResolver {
+                seed,
+                displacements: displacements_resolver,
+                entries,
+            })
+        }
+    }
+};
+
+impl<K, V, H> fmt::Debug for ArchivedPerfectHashMap<K, V, H>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, H> Eq for ArchivedPerfectHashMap<K, V, H>
+where
+    K: Hash + Eq,
+    V: Eq,
+    H: Default + Hasher,
+{
+}
+
+impl<K, V, H> PartialEq for ArchivedPerfectHashMap<K, V, H>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    H: Default + Hasher,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            false
+        } else {
+            self.iter().all(|(key, value)| {
+                other.get(key).map_or(false, |v| *value == *v)
+            })
+        }
+    }
+}
+
+impl<K, Q, V, H> Index<&'_ Q> for ArchivedPerfectHashMap<K, V, H>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    H: Default + Hasher,
+{
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).unwrap()
+    }
+}
+
+/// The resolver for [`ArchivedPerfectHashMap`].
+pub struct PerfectHashMapResolver {
+    seed: u64,
+    displacements: VecResolver,
+    entries: VecResolver,
+}
+
+/// An iterator over the key-value pairs of an [`ArchivedPerfectHashMap`].
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// An iterator over the keys of an [`ArchivedPerfectHashMap`].
+pub struct Keys<'a, K, V> {
+    inner: slice::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| &entry.key)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of an [`ArchivedPerfectHashMap`].
+pub struct Values<'a, K, V> {
+    inner: slice::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| &entry.value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::fmt;
+
+    use bytecheck::Verify;
+    use rancor::{fail, Error, Fallible};
+
+    use super::{bucket_count, ArchivedPerfectHashMap};
+
+    #[derive(Debug)]
+    struct InvalidDisplacementCount {
+        expected: usize,
+        actual: usize,
+    }
+
+    impl fmt::Display for InvalidDisplacementCount {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "perfect hash map has the wrong number of displacements (expected: {}, actual: {})",
+                self.expected, self.actual,
+            )
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for InvalidDisplacementCount {}
+
+    #[derive(Debug)]
+    struct InvalidDisplacement {
+        bucket: usize,
+        displacement: [u32; 2],
+        len: usize,
+    }
+
+    impl fmt::Display for InvalidDisplacement {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "displacement {:?} of bucket {} is out of range for a perfect hash map of length {}",
+                self.displacement, self.bucket, self.len,
+            )
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for InvalidDisplacement {}
+
+    unsafe impl<C, K, V, H> Verify<C> for ArchivedPerfectHashMap<K, V, H>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Error,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let len = self.entries.len();
+            let expected = bucket_count(len);
+            if self.displacements.len() != expected {
+                fail!(InvalidDisplacementCount {
+                    expected,
+                    actual: self.displacements.len(),
+                });
+            }
+
+            for (bucket, [d1, d2]) in self.displacements.iter().enumerate() {
+                let displacement = [d1.to_native(), d2.to_native()];
+                if displacement.iter().any(|&d| d as usize >= len) {
+                    fail!(InvalidDisplacement {
+                        bucket,
+                        displacement,
+                        len,
+                    });
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
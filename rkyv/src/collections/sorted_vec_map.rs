@@ -0,0 +1,490 @@
+//! An archived map which stores its keys and values in sorted vectors.
+//!
+//! Keys are stored in ascending order in one contiguous vector, and their
+//! values are stored in the same order in another. Looking up a key is a
+//! binary search over the keys, or an interpolation search for keys which can
+//! be mapped to integers.
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    iter::FusedIterator,
+    ops::{Bound, Index, RangeBounds},
+    slice,
+};
+
+use rancor::Fallible;
+
+use crate::{
+    ser::{Allocator, Writer},
+    util::ScratchVec,
+    vec::{ArchivedVec, VecResolver},
+    Portable, Serialize,
+};
+
+/// An archived map which stores its keys and values in sorted vectors.
+///
+/// Unlike an [`ArchivedBTreeMap`] or [`ArchivedHashMap`], this map has no
+/// nodes or control bytes: it takes up exactly as much space as its keys and
+/// values. Lookups are binary searches, and ranges of keys are contiguous
+/// subslices. This makes it well-suited for small to medium maps which are
+/// never modified.
+///
+/// This is produced by serializing a map with
+/// [`AsSortedVec`](crate::with::AsSortedVec). The archived keys must be
+/// ordered the same way as the unarchived keys they were serialized from.
+///
+/// Validation checks that there are as many values as keys, and that the keys
+/// are strictly increasing.
+///
+/// [`ArchivedBTreeMap`]: crate::collections::btree_map::ArchivedBTreeMap
+/// [`ArchivedHashMap`]: crate::collections::swiss_table::ArchivedHashMap
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+pub struct ArchivedSortedVecMap<K, V> {
+    keys: ArchivedVec<K>,
+    values: ArchivedVec<V>,
+}
+
+impl<K, V> ArchivedSortedVecMap<K, V> {
+    /// Returns whether the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the keys of the map in ascending order.
+    #[inline]
+    pub fn keys(&self) -> &[K] {
+        self.keys.as_slice()
+    }
+
+    /// Returns the values of the map in the order of their keys.
+    #[inline]
+    pub fn values(&self) -> &[V] {
+        self.values.as_slice()
+    }
+
+    /// Returns an iterator over the key-value entries of the map, in ascending
+    /// order of keys.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            keys: self.keys.iter(),
+            values: self.values.iter(),
+        }
+    }
+
+    /// Returns the key-value pair at the given index in the map.
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        Some((self.keys.get(index)?, self.values.get(index)?))
+    }
+
+    /// Returns the first key-value pair in the map.
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    /// Returns the last key-value pair in the map.
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.get_index(self.len().checked_sub(1)?)
+    }
+
+    /// Searches the keys of the map for the given key using interpolation
+    /// search.
+    ///
+    /// `position` maps each key to an integer, and must be monotonic: if one
+    /// key is less than another, its position must not be greater. Like
+    /// [`binary_search`](ArchivedSortedVecMap::binary_search), this returns
+    /// `Ok` with the index of a key with the given position, or `Err` with the
+    /// index where such a key would be inserted. If multiple keys have the
+    /// given position, any one of them may be returned.
+    ///
+    /// When the positions of the keys are evenly distributed, this takes
+    /// `O(log log n)` steps on average instead of `O(log n)`. Whenever a step
+    /// fails to halve the search range, the next step bisects it instead, so
+    /// it never takes more than about twice as many steps as a binary search.
+    pub fn interpolation_search_by<F>(
+        &self,
+        position: u64,
+        key_position: F,
+    ) -> Result<usize, usize>
+    where
+        F: Fn(&K) -> u64,
+    {
+        let keys = self.keys();
+        let (mut low, mut high) = (0, keys.len());
+        let mut bisect = false;
+
+        while low < high {
+            let low_position = key_position(&keys[low]);
+            let high_position = key_position(&keys[high - 1]);
+            if position < low_position {
+                return Err(low);
+            } else if position > high_position {
+                return Err(high);
+            } else if low_position == high_position {
+                return Ok(low);
+            }
+
+            let len = high - low;
+            let mid = if bisect {
+                low + len / 2
+            } else {
+                // `low_position <= position <= high_position`, so the offset
+                // is always in `0..len`.
+                let offset = (position - low_position) as u128
+                    * (len - 1) as u128
+                    / (high_position - low_position) as u128;
+                low + offset as usize
+            };
+
+            let mid_position = key_position(&keys[mid]);
+            if mid_position < position {
+                low = mid + 1;
+            } else if mid_position > position {
+                high = mid;
+            } else {
+                return Ok(mid);
+            }
+
+            bisect = high - low > len / 2;
+        }
+
+        Err(low)
+    }
+
+    /// Resolves an archived sorted vec map from a given length.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a sorted vec map
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: SortedVecMapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.keys);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.keys, fo);
+
+        let (fp, fo) = out_field!(out.values);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.values, fo);
+    }
+
+    /// Serializes an iterator of key-value pairs as a sorted vec map.
+    ///
+    /// The keys returned by the iterator must be unique and in ascending
+    /// order.
+    pub fn serialize_from_sorted_iter<'a, I, KU, VU, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<SortedVecMapResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        KU: 'a + Serialize<S, Archived = K>,
+        VU: 'a + Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+    {
+        let keys = ArchivedVec::serialize_from_iter::<KU, _, _>(
+            iter.clone().map(|(key, _)| key),
+            serializer,
+        )?;
+        let values = ArchivedVec::serialize_from_iter::<VU, _, _>(
+            iter.map(|(_, value)| value),
+            serializer,
+        )?;
+
+        Ok(SortedVecMapResolver { keys, values })
+    }
+}
+
+impl<K: Ord, V> ArchivedSortedVecMap<K, V> {
+    /// Searches the keys of the map for the given key using binary search.
+    ///
+    /// Returns `Ok` with the index of the key if it is in the map, or `Err`
+    /// with the index where it would be inserted otherwise.
+    #[inline]
+    pub fn binary_search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.keys.binary_search_by(|k| k.borrow().cmp(key))
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_index(self.binary_search(key).ok()?)
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Some(self.get_key_value(key)?.1)
+    }
+
+    /// Returns whether the map contains the given key.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(key).is_ok()
+    }
+
+    /// Returns an iterator over the key-value entries in the given range of
+    /// keys, in ascending order of keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end of the range,
+    /// or if the start and end of the range are equal and both excluded.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(start), Bound::Excluded(end)) if start == end => {
+                panic!(
+                    "range start and end are equal and excluded in \
+                     ArchivedSortedVecMap"
+                )
+            }
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Included(end) | Bound::Excluded(end),
+            ) if start > end => {
+                panic!(
+                    "range start is greater than range end in \
+                     ArchivedSortedVecMap"
+                )
+            }
+            _ => (),
+        }
+
+        let keys = self.keys();
+        let start = match range.start_bound() {
+            Bound::Included(start) => {
+                keys.partition_point(|k| k.borrow() < start)
+            }
+            Bound::Excluded(start) => {
+                keys.partition_point(|k| k.borrow() <= start)
+            }
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => keys.partition_point(|k| k.borrow() <= end),
+            Bound::Excluded(end) => keys.partition_point(|k| k.borrow() < end),
+            Bound::Unbounded => keys.len(),
+        };
+
+        Iter {
+            keys: keys[start..end].iter(),
+            values: self.values()[start..end].iter(),
+        }
+    }
+}
+
+impl<K, V> ArchivedSortedVecMap<K, V> {
+    /// Serializes an iterator of key-value pairs as a sorted vec map.
+    ///
+    /// The keys returned by the iterator must be unique. They are sorted in
+    /// scratch space before serializing.
+    pub fn serialize_from_iter<'a, I, KU, VU, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<SortedVecMapResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        KU: 'a + Serialize<S, Archived = K> + Ord,
+        VU: 'a + Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+    {
+        let mut entries = unsafe { ScratchVec::new(serializer, iter.len())? };
+        for entry in iter {
+            entries.push(entry);
+        }
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        let resolver = Self::serialize_from_sorted_iter(
+            entries.iter().copied(),
+            serializer,
+        )?;
+
+        unsafe {
+            entries.free(serializer)?;
+        }
+
+        Ok(resolver)
+    }
+}
+
+impl<K, V> fmt::Debug for ArchivedSortedVecMap<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ArchivedSortedVecMap<K, V> {}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ArchivedSortedVecMap<K, V> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.keys() == other.keys() && self.values() == other.values()
+    }
+}
+
+impl<K, Q, V> Index<&'_ Q> for ArchivedSortedVecMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).unwrap()
+    }
+}
+
+/// The resolver for [`ArchivedSortedVecMap`].
+pub struct SortedVecMapResolver {
+    keys: VecResolver,
+    values: VecResolver,
+}
+
+/// An iterator over the key-value pairs of an [`ArchivedSortedVecMap`].
+pub struct Iter<'a, K, V> {
+    keys: slice::Iter<'a, K>,
+    values: slice::Iter<'a, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.keys.next()?, self.values.next()?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some((self.keys.next_back()?, self.values.next_back()?))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::fmt;
+
+    use bytecheck::Verify;
+    use rancor::{fail, Error, Fallible};
+
+    use super::ArchivedSortedVecMap;
+
+    #[derive(Debug)]
+    struct LengthMismatch {
+        keys: usize,
+        values: usize,
+    }
+
+    impl fmt::Display for LengthMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "sorted vec map has {} keys but {} values",
+                self.keys, self.values,
+            )
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for LengthMismatch {}
+
+    #[derive(Debug)]
+    struct UnsortedKeys {
+        index: usize,
+    }
+
+    impl fmt::Display for UnsortedKeys {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "key {} of sorted vec map is not greater than the key before it",
+                self.index,
+            )
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for UnsortedKeys {}
+
+    unsafe impl<C, K, V> Verify<C> for ArchivedSortedVecMap<K, V>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Error,
+        K: Ord,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            if self.keys.len() != self.values.len() {
+                fail!(LengthMismatch {
+                    keys: self.keys.len(),
+                    values: self.values.len(),
+                });
+            }
+
+            for (i, pair) in self.keys.windows(2).enumerate() {
+                if pair[0] >= pair[1] {
+                    fail!(UnsortedKeys { index: i + 1 });
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
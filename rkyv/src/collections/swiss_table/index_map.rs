@@ -527,13 +527,17 @@ pub struct IndexMapResolver {
 
 #[cfg(feature = "bytecheck")]
 mod verify {
+    use core::{mem::size_of, ops::Range};
+
     use bytecheck::{CheckBytes, Verify};
-    use rancor::{Error, Fallible};
+    use rancor::{Error, Fallible, ResultExt as _};
 
     use super::ArchivedIndexMap;
     use crate::{
         collections::swiss_table::Entry,
-        validation::{ArchiveContext, ArchiveContextExt},
+        validation::{
+            path::IndexCheckContext, ArchiveContext, ArchiveContextExt,
+        },
     };
 
     unsafe impl<C, K, V, H> Verify<C> for ArchivedIndexMap<K, V, H>
@@ -547,18 +551,41 @@ mod verify {
             &self,
             context: &mut C,
         ) -> Result<(), <C as Fallible>::Error> {
+            let len = self.table.len();
+            context.check_collection(
+                len,
+                size_of::<Entry<K, V>>().saturating_mul(len),
+            )?;
+
             let ptr = unsafe {
                 context.bounds_check_subtree_base_offset::<[Entry<K, V>]>(
                     self.entries.base(),
                     self.entries.offset(),
-                    self.table.len(),
+                    len,
                 )?
             };
 
             let range = unsafe { context.push_prefix_subtree(ptr)? };
-            unsafe {
-                <[Entry<K, V>]>::check_bytes(ptr, context)?;
-            }
+            // The base address is captured as an integer so that entries can
+            // be checked from multiple threads.
+            let base = ptr.cast::<Entry<K, V>>() as usize;
+            context.check_elements(
+                len,
+                &|context: &mut C, indices: Range<usize>| {
+                    for index in indices {
+                        let entry =
+                            unsafe { (base as *const Entry<K, V>).add(index) };
+                        unsafe {
+                            <Entry<K, V>>::check_bytes(entry, context)
+                                .with_trace(|| IndexCheckContext {
+                                    index,
+                                    address: entry as usize,
+                                })?;
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
             unsafe {
                 context.pop_subtree_range(range)?;
             }
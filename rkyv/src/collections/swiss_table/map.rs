@@ -1,13 +1,8 @@
 //! Archived hash map implementation using an archived SwissTable.
 
 use core::{
-    borrow::Borrow,
-    fmt,
-    hash::{Hash, Hasher},
-    iter::FusedIterator,
-    marker::PhantomData,
-    ops::Index,
-    pin::Pin,
+    borrow::Borrow, fmt, hash::Hash, iter::FusedIterator, marker::PhantomData,
+    ops::Index, pin::Pin,
 };
 
 use rancor::{Error, Fallible};
@@ -17,22 +12,31 @@ use crate::{
         table::{ArchivedHashTable, HashTableResolver, RawIter},
         Entry, EntryAdapter,
     },
-    hash::{hash_value, FxHasher64},
+    hash::{hash_value_with_seed, ArchivedHasher, FxHasher64},
     ser::{Allocator, Writer},
     Portable, Serialize,
 };
 
 /// An archived SwissTable hash map.
+///
+/// Keys are hashed with `H`, which is seeded with a seed stored next to the
+/// table. Unseeded hashers like the default [`FxHasher64`] add nothing to the
+/// size of the map.
+///
+/// Because the type of the seed depends on the hasher, `H` must implement
+/// [`ArchivedHasher`] wherever `ArchivedHashMap` is named. Generic code which
+/// names `ArchivedHashMap<K, V, H>` needs an `H: ArchivedHasher` bound.
 #[derive(Portable)]
 #[archive(crate)]
-#[repr(transparent)]
+#[repr(C)]
 #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
-pub struct ArchivedHashMap<K, V, H = FxHasher64> {
+pub struct ArchivedHashMap<K, V, H: ArchivedHasher = FxHasher64> {
     table: ArchivedHashTable<Entry<K, V>>,
+    seed: H::Seed,
     _phantom: PhantomData<H>,
 }
 
-impl<K, V, H> ArchivedHashMap<K, V, H> {
+impl<K, V, H: ArchivedHasher> ArchivedHashMap<K, V, H> {
     /// Returns whether the hash map is empty.
     #[inline]
     pub const fn is_empty(&self) -> bool {
@@ -97,7 +101,12 @@ impl<K, V, H> ArchivedHashMap<K, V, H> {
     }
 }
 
-impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
+impl<K, V, H: ArchivedHasher> ArchivedHashMap<K, V, H> {
+    #[inline]
+    fn hash_key<Q: Hash + ?Sized>(&self, key: &Q) -> u64 {
+        hash_value_with_seed::<Q, H>(key, &self.seed)
+    }
+
     /// Returns the key-value pair corresponding to the supplied key using the
     /// given comparison function.
     #[inline]
@@ -108,7 +117,7 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
     {
         let entry = self
             .table
-            .get_with(hash_value::<Q, H>(key), |e| cmp(key, &e.key))?;
+            .get_with(self.hash_key(key), |e| cmp(key, &e.key))?;
         Some((&entry.key, &entry.value))
     }
 
@@ -156,9 +165,9 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         Q: Hash + Eq + ?Sized,
         C: Fn(&Q, &K) -> bool,
     {
+        let hash = self.hash_key(key);
         let table = unsafe { Pin::map_unchecked_mut(self, |s| &mut s.table) };
-        let entry = table
-            .get_with_mut(hash_value::<Q, H>(key), |e| cmp(key, &e.key))?;
+        let entry = table.get_with_mut(hash, |e| cmp(key, &e.key))?;
         let entry = unsafe { Pin::into_inner_unchecked(entry) };
         let key = &entry.key;
         let value = unsafe { Pin::new_unchecked(&mut entry.value) };
@@ -216,11 +225,15 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
     }
 
     /// Serializes an iterator of key-value pairs as a hash map.
+    ///
+    /// The map is hashed with the [default seed](ArchivedHasher::default_seed)
+    /// of `H`, so serializing the same entries always produces the same bytes.
+    #[inline]
     pub fn serialize_from_iter<'a, I, KU, VU, S>(
         iter: I,
         load_factor: (usize, usize),
         serializer: &mut S,
-    ) -> Result<HashMapResolver, S::Error>
+    ) -> Result<HashMapResolver<H::Seed>, S::Error>
     where
         I: Clone + ExactSizeIterator<Item = (&'a KU, &'a VU)>,
         KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
@@ -228,13 +241,36 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         S: Fallible + Writer + Allocator + ?Sized,
         S::Error: Error,
     {
-        ArchivedHashTable::<Entry<K, V>>::serialize_from_iter(
-            iter.clone().map(|(key, value)| EntryAdapter { key, value }),
-            iter.map(|(key, _)| hash_value::<KU, H>(key)),
+        Self::serialize_from_iter_with_seed(
+            iter,
             load_factor,
+            H::default_seed(),
             serializer,
         )
-        .map(HashMapResolver)
+    }
+
+    /// Serializes an iterator of key-value pairs as a hash map which is hashed
+    /// with the given seed.
+    pub fn serialize_from_iter_with_seed<'a, I, KU, VU, S>(
+        iter: I,
+        load_factor: (usize, usize),
+        seed: H::Seed,
+        serializer: &mut S,
+    ) -> Result<HashMapResolver<H::Seed>, S::Error>
+    where
+        I: Clone + ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+        VU: 'a + Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Error,
+    {
+        let table = ArchivedHashTable::<Entry<K, V>>::serialize_from_iter(
+            iter.clone().map(|(key, value)| EntryAdapter { key, value }),
+            iter.map(|(key, _)| hash_value_with_seed::<KU, H>(key, &seed)),
+            load_factor,
+            serializer,
+        )?;
+        Ok(HashMapResolver { table, seed })
     }
 
     /// Resolves an archived hash map from a given length and parameters.
@@ -246,16 +282,22 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         len: usize,
         load_factor: (usize, usize),
         pos: usize,
-        resolver: HashMapResolver,
+        resolver: HashMapResolver<H::Seed>,
         out: *mut Self,
     ) {
+        let (fp, fo) = out_field!(out.table);
         ArchivedHashTable::<Entry<K, V>>::resolve_from_len(
             len,
             load_factor,
-            pos,
-            resolver.0,
-            out.cast(),
-        )
+            pos + fp,
+            resolver.table,
+            fo,
+        );
+
+        let (_, fo) = out_field!(out.seed);
+        fo.write(resolver.seed);
+
+        // PhantomData doesn't need to be initialized
     }
 }
 
@@ -263,6 +305,7 @@ impl<K, V, H> fmt::Debug for ArchivedHashMap<K, V, H>
 where
     K: fmt::Debug,
     V: fmt::Debug,
+    H: ArchivedHasher,
 {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -274,7 +317,7 @@ impl<K, V, H> Eq for ArchivedHashMap<K, V, H>
 where
     K: Hash + Eq,
     V: Eq,
-    H: Default + Hasher,
+    H: ArchivedHasher,
 {
 }
 
@@ -282,7 +325,7 @@ impl<K, V, H> PartialEq for ArchivedHashMap<K, V, H>
 where
     K: Hash + Eq,
     V: PartialEq,
-    H: Default + Hasher,
+    H: ArchivedHasher,
 {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -300,7 +343,7 @@ impl<K, Q, V, H> Index<&'_ Q> for ArchivedHashMap<K, V, H>
 where
     K: Eq + Hash + Borrow<Q>,
     Q: Eq + Hash + ?Sized,
-    H: Default + Hasher,
+    H: ArchivedHasher,
 {
     type Output = V;
 
@@ -311,12 +354,17 @@ where
 }
 
 /// The resolver for [`ArchivedHashMap`].
-pub struct HashMapResolver(HashTableResolver);
+///
+/// `S` is the seed of the map's hasher.
+pub struct HashMapResolver<S = ()> {
+    table: HashTableResolver,
+    seed: S,
+}
 
 /// An iterator over the key-value pairs of an [`ArchivedHashMap`].
 pub struct Iter<'a, K, V, H> {
     raw: RawIter<Entry<K, V>>,
-    _phantom: PhantomData<&'a ArchivedHashMap<K, V, H>>,
+    _phantom: PhantomData<&'a (K, V, H)>,
 }
 
 impl<'a, K, V, H> Iterator for Iter<'a, K, V, H> {
@@ -343,7 +391,7 @@ impl<K, V, H> FusedIterator for Iter<'_, K, V, H> {}
 /// An iterator over the mutable key-value pairs of an [`ArchivedHashMap`].
 pub struct IterMut<'a, K, V, H> {
     raw: RawIter<Entry<K, V>>,
-    _phantom: PhantomData<&'a ArchivedHashMap<K, V, H>>,
+    _phantom: PhantomData<&'a (K, V, H)>,
 }
 
 impl<'a, K, V, H> Iterator for IterMut<'a, K, V, H> {
@@ -370,7 +418,7 @@ impl<K, V, H> FusedIterator for IterMut<'_, K, V, H> {}
 /// An iterator over the keys of an [`ArchivedHashMap`].
 pub struct Keys<'a, K, V, H> {
     raw: RawIter<Entry<K, V>>,
-    _phantom: PhantomData<&'a ArchivedHashMap<K, V, H>>,
+    _phantom: PhantomData<&'a (K, V, H)>,
 }
 
 impl<'a, K, V, H> Iterator for Keys<'a, K, V, H> {
@@ -396,7 +444,7 @@ impl<K, V, H> FusedIterator for Keys<'_, K, V, H> {}
 /// An iterator over the values of an [`ArchivedHashMap`].
 pub struct Values<'a, K, V, H> {
     raw: RawIter<Entry<K, V>>,
-    _phantom: PhantomData<&'a ArchivedHashMap<K, V, H>>,
+    _phantom: PhantomData<&'a (K, V, H)>,
 }
 
 impl<'a, K, V, H> Iterator for Values<'a, K, V, H> {
@@ -422,7 +470,7 @@ impl<K, V, H> FusedIterator for Values<'_, K, V, H> {}
 /// An iterator over the mutable values of an [`ArchivedHashMap`].
 pub struct ValuesMut<'a, K, V, H> {
     raw: RawIter<Entry<K, V>>,
-    _phantom: PhantomData<&'a ArchivedHashMap<K, V, H>>,
+    _phantom: PhantomData<&'a (K, V, H)>,
 }
 
 impl<'a, K, V, H> Iterator for ValuesMut<'a, K, V, H> {
@@ -444,3 +492,57 @@ impl<K, V, H> ExactSizeIterator for ValuesMut<'_, K, V, H> {
 }
 
 impl<K, V, H> FusedIterator for ValuesMut<'_, K, V, H> {}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{borrow::Borrow, hash::Hash, ptr::addr_of};
+
+    use bytecheck::CheckBytes;
+    use rancor::{Error, Strategy};
+
+    use super::ArchivedHashMap;
+    use crate::{
+        hash::ArchivedHasher, lazy::Checked,
+        validation::validators::DefaultValidator,
+    };
+
+    impl<'a, K, V, H: ArchivedHasher> Checked<'a, ArchivedHashMap<K, V, H>> {
+        /// Returns the value corresponding to the supplied key.
+        ///
+        /// Only the seed of the map and the keys compared while probing for
+        /// the key are checked.
+        pub fn get<Q, E>(self, key: &Q) -> Result<Option<Checked<'a, V>>, E>
+        where
+            K: Borrow<Q> + CheckBytes<Strategy<DefaultValidator, E>>,
+            Q: Hash + Eq + ?Sized,
+            H::Seed: CheckBytes<Strategy<DefaultValidator, E>>,
+            E: Error,
+        {
+            let access = self.access();
+            let map = self.as_ptr();
+            access.with_context(|validator| unsafe {
+                let context = Strategy::<_, E>::wrap(validator);
+                H::Seed::check_bytes(addr_of!((*map).seed), context)?;
+                (*map).table.check_layout(context)?;
+                Ok(())
+            })?;
+
+            // SAFETY: The seed was just checked, and the other fields of the
+            // map are valid for any bytes.
+            let map = unsafe { &*map };
+            let entry = unsafe {
+                map.table.probe_checked(map.hash_key(key), |entry| {
+                    access.with_context(|validator| {
+                        let entry_key = addr_of!((*entry).key);
+                        K::check_bytes(entry_key, Strategy::wrap(validator))?;
+                        Ok((*entry_key).borrow() == key)
+                    })
+                })?
+            };
+
+            Ok(entry.map(|entry| unsafe {
+                Checked::new(access, addr_of!((*entry).value))
+            }))
+        }
+    }
+}
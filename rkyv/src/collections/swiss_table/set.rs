@@ -1,6 +1,5 @@
 //! Archived hash set implementation using an archived SwissTable.
 
-use core::hash::Hasher;
 use core::{borrow::Borrow, fmt, hash::Hash};
 
 use rancor::{Error, Fallible};
@@ -8,7 +7,7 @@ use rancor::{Error, Fallible};
 use crate::collections::swiss_table::map::{
     ArchivedHashMap, HashMapResolver, Keys,
 };
-use crate::hash::FxHasher64;
+use crate::hash::{ArchivedHasher, FxHasher64};
 use crate::{
     ser::{Allocator, Writer},
     Portable, Serialize,
@@ -16,15 +15,18 @@ use crate::{
 
 /// An archived `HashSet`. This is a wrapper around a hash map with the same key
 /// and unit value.
+///
+/// Like [`ArchivedHashMap`], generic code which names `ArchivedHashSet<K, H>`
+/// needs an `H: ArchivedHasher` bound.
 #[derive(Portable)]
 #[archive(crate)]
 #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
 #[repr(transparent)]
-pub struct ArchivedHashSet<K, H = FxHasher64> {
+pub struct ArchivedHashSet<K, H: ArchivedHasher = FxHasher64> {
     inner: ArchivedHashMap<K, (), H>,
 }
 
-impl<K, H> ArchivedHashSet<K, H> {
+impl<K, H: ArchivedHasher> ArchivedHashSet<K, H> {
     /// Gets the number of items in the hash set.
     #[inline]
     pub const fn len(&self) -> usize {
@@ -44,7 +46,7 @@ impl<K, H> ArchivedHashSet<K, H> {
     }
 }
 
-impl<K, H: Hasher + Default> ArchivedHashSet<K, H> {
+impl<K, H: ArchivedHasher> ArchivedHashSet<K, H> {
     /// Gets the key corresponding to the given key in the hash set.
     #[inline]
     pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&K>
@@ -75,7 +77,7 @@ impl<K, H: Hasher + Default> ArchivedHashSet<K, H> {
         len: usize,
         load_factor: (usize, usize),
         pos: usize,
-        resolver: HashSetResolver,
+        resolver: HashSetResolver<H::Seed>,
         out: *mut Self,
     ) {
         let (fp, fo) = out_field!(out.inner);
@@ -89,12 +91,38 @@ impl<K, H: Hasher + Default> ArchivedHashSet<K, H> {
     }
 
     /// Serializes an iterator of keys as a hash set.
+    ///
+    /// The set is hashed with the [default seed](ArchivedHasher::default_seed)
+    /// of `H`, so serializing the same keys always produces the same bytes.
     #[inline]
     pub fn serialize_from_iter<'a, KU, S, I>(
         iter: I,
         load_factor: (usize, usize),
         serializer: &mut S,
-    ) -> Result<HashSetResolver, S::Error>
+    ) -> Result<HashSetResolver<H::Seed>, S::Error>
+    where
+        KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Error,
+        I: Clone + ExactSizeIterator<Item = &'a KU>,
+    {
+        Self::serialize_from_iter_with_seed(
+            iter,
+            load_factor,
+            H::default_seed(),
+            serializer,
+        )
+    }
+
+    /// Serializes an iterator of keys as a hash set which is hashed with the
+    /// given seed.
+    #[inline]
+    pub fn serialize_from_iter_with_seed<'a, KU, S, I>(
+        iter: I,
+        load_factor: (usize, usize),
+        seed: H::Seed,
+        serializer: &mut S,
+    ) -> Result<HashSetResolver<H::Seed>, S::Error>
     where
         KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
         S: Fallible + Writer + Allocator + ?Sized,
@@ -102,29 +130,32 @@ impl<K, H: Hasher + Default> ArchivedHashSet<K, H> {
         I: Clone + ExactSizeIterator<Item = &'a KU>,
     {
         Ok(HashSetResolver(
-            ArchivedHashMap::<K, (), H>::serialize_from_iter(
+            ArchivedHashMap::<K, (), H>::serialize_from_iter_with_seed(
                 iter.map(|x| (x, &())),
                 load_factor,
+                seed,
                 serializer,
             )?,
         ))
     }
 }
 
-impl<K: fmt::Debug, H> fmt::Debug for ArchivedHashSet<K, H> {
+impl<K: fmt::Debug, H: ArchivedHasher> fmt::Debug for ArchivedHashSet<K, H> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_set().entries(self.iter()).finish()
     }
 }
 
-impl<K: Hash + Eq, H: Hasher + Default> PartialEq for ArchivedHashSet<K, H> {
+impl<K: Hash + Eq, H: ArchivedHasher> PartialEq for ArchivedHashSet<K, H> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.inner == other.inner
     }
 }
 
-impl<K: Hash + Eq, H: Hasher + Default> Eq for ArchivedHashSet<K, H> {}
+impl<K: Hash + Eq, H: ArchivedHasher> Eq for ArchivedHashSet<K, H> {}
 
 /// The resolver for archived hash sets.
-pub struct HashSetResolver(HashMapResolver);
+///
+/// `S` is the seed of the set's hasher.
+pub struct HashSetResolver<S = ()>(HashMapResolver<S>);
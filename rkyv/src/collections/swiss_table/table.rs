@@ -160,6 +160,68 @@ impl<T> ArchivedHashTable<T> {
         }
     }
 
+    /// Searches for an entry without assuming that the table is valid.
+    ///
+    /// `cmp` is called with a pointer to each candidate entry, and must check
+    /// the parts of the entry it reads. Probing stops after visiting every
+    /// position once, so tables without any empty buckets can't loop forever.
+    ///
+    /// # Safety
+    ///
+    /// The control bytes and buckets of the table must be valid to read, as
+    /// checked by `check_layout`.
+    #[cfg(feature = "bytecheck")]
+    pub(crate) unsafe fn probe_checked<C, E>(
+        &self,
+        hash: u64,
+        mut cmp: C,
+    ) -> Result<Option<*const T>, E>
+    where
+        C: FnMut(*const T) -> Result<bool, E>,
+    {
+        if self.len.to_native() == 0 {
+            return Ok(None);
+        }
+
+        let h2_hash = h2(hash);
+        let capacity = self.capacity();
+        let mut probe_seq = Self::probe_seq(hash, capacity);
+        let bucket_mask = Self::bucket_mask(capacity);
+
+        for _ in 0..=bucket_mask {
+            if probe_seq.pos < capacity {
+                let mut any_empty = false;
+
+                for _ in 0..MAX_GROUP_WIDTH / Group::WIDTH {
+                    let group =
+                        unsafe { Group::read(self.control(probe_seq.pos)) };
+
+                    for bit in group.match_byte(h2_hash) {
+                        let index = (probe_seq.pos + bit) % capacity;
+                        let bucket = unsafe { self.bucket(index).as_ptr() };
+                        if cmp(bucket)? {
+                            return Ok(Some(bucket));
+                        }
+                    }
+
+                    any_empty = any_empty || group.match_empty().any_bit_set();
+
+                    probe_seq.next_group();
+                }
+
+                if any_empty {
+                    return Ok(None);
+                }
+            }
+
+            probe_seq.pos += probe_seq.stride;
+            probe_seq.pos &= bucket_mask;
+            probe_seq.stride += MAX_GROUP_WIDTH;
+        }
+
+        Ok(None)
+    }
+
     /// Returns the key-value pair corresponding to the supplied key.
     #[inline]
     pub fn get_with<C>(&self, hash: u64, cmp: C) -> Option<&T>
@@ -517,15 +579,17 @@ impl<T> ExactSizeIterator for RawIter<T> {
 
 #[cfg(feature = "bytecheck")]
 mod verify {
-    use core::fmt;
+    use core::{alloc::Layout, fmt, ops::Range};
 
     use bytecheck::{CheckBytes, Verify};
-    use rancor::{fail, Error, Fallible};
+    use rancor::{fail, Error, Fallible, ResultExt as _};
 
     use super::ArchivedHashTable;
     use crate::{
         simd::Group,
-        validation::{ArchiveContext, ArchiveContextExt},
+        validation::{
+            path::BucketCheckContext, ArchiveContext, ArchiveContextExt,
+        },
     };
 
     #[derive(Debug)]
@@ -562,25 +626,31 @@ mod verify {
     #[cfg(feature = "std")]
     impl std::error::Error for UnwrappedControlByte {}
 
-    unsafe impl<C, T> Verify<C> for ArchivedHashTable<T>
-    where
-        C: Fallible + ArchiveContext + ?Sized,
-        C::Error: Error,
-        T: CheckBytes<C>,
-    {
-        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+    impl<T> ArchivedHashTable<T> {
+        /// Checks that the control bytes and buckets of the table are located
+        /// in the subtree range, and returns a pointer to the start of their
+        /// allocation and its layout.
+        ///
+        /// Returns `None` if the table has no allocation.
+        pub(crate) fn check_layout<C>(
+            &self,
+            context: &mut C,
+        ) -> Result<Option<(*const u8, Layout)>, C::Error>
+        where
+            C: Fallible + ArchiveContext + ?Sized,
+            C::Error: Error,
+        {
             let len = self.len();
             let cap = self.capacity();
 
             if len == 0 && cap == 0 {
-                return Ok(());
+                return Ok(None);
             }
 
-            if self.len() >= cap {
+            if len >= cap {
                 fail!(InvalidLength { len, cap });
             }
 
-            // Check memory allocation
             let control_count = Self::control_count(cap)?;
             let (layout, control_offset) =
                 Self::memory_layout(cap, control_count)?;
@@ -591,26 +661,69 @@ mod verify {
                 .wrapping_sub(control_offset);
             context.check_subtree_ptr(ptr, &layout)?;
 
+            Ok(Some((ptr.cast_const(), layout)))
+        }
+    }
+
+    unsafe impl<C, T> Verify<C> for ArchivedHashTable<T>
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Error,
+        T: CheckBytes<C>,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            let cap = self.capacity();
+
+            // Check memory allocation
+            let (ptr, layout) = match self.check_layout(context)? {
+                Some(allocation) => allocation,
+                None => return Ok(()),
+            };
+            context.check_collection(len, layout.size())?;
+            let control_count = Self::control_count(cap)?;
+
             let range = unsafe { context.push_prefix_subtree(ptr)? };
 
-            // Check each non-empty bucket
-            let mut controls = self.control_iter();
-            let mut base_index = 0;
-            'outer: while base_index < cap {
-                while let Some(bit) = controls.next_full() {
-                    let index = base_index + bit;
-                    if index >= cap {
-                        break 'outer;
-                    }
+            // Check each non-empty bucket, one group of control bytes at a
+            // time. The table address is captured as an integer so that
+            // groups can be checked from multiple threads. Each bucket is
+            // checked as its own element so that contexts can continue after
+            // an invalid bucket.
+            let table = self as *const Self as usize;
+            let group_count = cap.div_ceil(Group::WIDTH);
+            context.check_elements(
+                group_count,
+                &|context: &mut C, groups: Range<usize>| {
+                    // SAFETY: The table outlives the call to
+                    // `check_elements`.
+                    let table = unsafe { &*(table as *const Self) };
+                    for group in groups {
+                        let base_index = group * Group::WIDTH;
+                        let full = unsafe {
+                            Group::read(table.control(base_index)).match_full()
+                        };
+                        for bit in full {
+                            let index = base_index + bit;
+                            if index >= cap {
+                                break;
+                            }
 
-                    unsafe {
-                        T::check_bytes(self.bucket(index).as_ptr(), context)?;
+                            let bucket =
+                                unsafe { table.bucket(index).as_ptr() };
+                            context.check_element(&mut |context| unsafe {
+                                T::check_bytes(bucket, context).with_trace(
+                                    || BucketCheckContext {
+                                        index,
+                                        address: bucket as usize,
+                                    },
+                                )
+                            })?;
+                        }
                     }
-                }
-
-                controls.move_next();
-                base_index += Group::WIDTH;
-            }
+                    Ok(())
+                },
+            )?;
 
             // Verify that wrapped bytes are set correctly
             for i in cap..usize::min(2 * cap, control_count) {
@@ -198,6 +198,8 @@ mod verify {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
             let ptr =
                 unsafe { context.bounds_check_subtree_rel_ptr(&self.ptr)? };
+            let len = ptr_meta::metadata(ptr);
+            context.check_collection(len, len)?;
 
             let range = unsafe { context.push_prefix_subtree(ptr)? };
             unsafe {
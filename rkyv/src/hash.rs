@@ -5,7 +5,144 @@ use core::{
     ops::BitXor as _,
 };
 
-use crate::primitive::{FixedIsize, FixedUsize};
+use crate::{
+    primitive::{ArchivedU64, FixedIsize, FixedUsize},
+    Portable,
+};
+
+/// A hasher which can be used to hash the keys of archived hash maps and sets.
+///
+/// Each archived hash map stores a seed for its hasher next to its table. The
+/// seed is chosen by a [`HashSeed`] when the map is serialized. By default,
+/// the same seed is always used so that serializing the same map always
+/// produces the same archive. Hashers which implement `Default` (like
+/// [`FxHasher64`]) have a `()` seed, which takes up no space in the archive.
+pub trait ArchivedHasher: Hasher {
+    /// The archived seed of the hasher.
+    type Seed: Copy + Portable;
+
+    /// Returns the fixed seed used when no other seed is chosen.
+    fn default_seed() -> Self::Seed;
+
+    /// Generates a new random seed for a hash map.
+    ///
+    /// Without the `std` feature, there is no source of randomness and this
+    /// returns the same seed every time.
+    fn random_seed() -> Self::Seed;
+
+    /// Creates a new hasher from the given seed.
+    fn with_seed(seed: &Self::Seed) -> Self;
+}
+
+impl<H: Hasher + Default> ArchivedHasher for H {
+    type Seed = ();
+
+    #[inline]
+    fn default_seed() -> Self::Seed {}
+
+    #[inline]
+    fn random_seed() -> Self::Seed {}
+
+    #[inline]
+    fn with_seed(_: &Self::Seed) -> Self {
+        Self::default()
+    }
+}
+
+/// Chooses the seeds of the hashers of archived hash maps and sets.
+///
+/// Hash maps and sets archived with [`HashWith<H, S>`](crate::with::HashWith)
+/// get their seeds from `S`. To archive maps with a seed of your own, implement
+/// this trait for a type which returns it.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use rkyv::{
+///     hash::{ArchivedHasher, HashSeed, SipHasher13},
+///     primitive::ArchivedU64,
+///     with::HashWith,
+///     Archive,
+/// };
+///
+/// struct MySeed;
+///
+/// impl HashSeed<SipHasher13> for MySeed {
+///     fn seed() -> <SipHasher13 as ArchivedHasher>::Seed {
+///         [ArchivedU64::from_native(1), ArchivedU64::from_native(2)]
+///     }
+/// }
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(HashWith<SipHasher13, MySeed>)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+pub trait HashSeed<H: ArchivedHasher> {
+    /// Returns the seed for a hash map or set which is being serialized.
+    fn seed() -> H::Seed;
+}
+
+/// A [`HashSeed`] which always returns the
+/// [default seed](ArchivedHasher::default_seed) of the hasher.
+///
+/// This makes archives reproducible: serializing the same map always produces
+/// the same bytes.
+#[derive(Debug)]
+pub struct DefaultSeed;
+
+impl<H: ArchivedHasher> HashSeed<H> for DefaultSeed {
+    #[inline]
+    fn seed() -> H::Seed {
+        H::default_seed()
+    }
+}
+
+/// A [`HashSeed`] which generates a new [random
+/// seed](ArchivedHasher::random_seed) for every map.
+///
+/// Random seeds make it harder to construct keys which collide in a particular
+/// map, at the cost of archives which differ every time they are serialized.
+#[derive(Debug)]
+pub struct RandomSeed;
+
+impl<H: ArchivedHasher> HashSeed<H> for RandomSeed {
+    #[inline]
+    fn seed() -> H::Seed {
+        H::random_seed()
+    }
+}
+
+/// Returns random words to seed hashers with.
+///
+/// Without the `std` feature, there is no source of randomness and the same
+/// words are always returned.
+#[inline]
+fn random_words() -> [u64; 2] {
+    #[cfg(feature = "std")]
+    {
+        use std::{collections::hash_map::RandomState, hash::BuildHasher};
+
+        let state = RandomState::new();
+        let mut first = state.build_hasher();
+        first.write_u8(0);
+        let mut second = state.build_hasher();
+        second.write_u8(1);
+        [first.finish(), second.finish()]
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        DEFAULT_SEED_WORDS
+    }
+}
+
+/// The words used to make the default seeds of hashers: the first digits of
+/// the fractional part of pi.
+const DEFAULT_SEED_WORDS: [u64; 2] =
+    [0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344];
 
 /// A cross-platform 64-bit implementation of fxhash.
 #[derive(Default)]
@@ -114,6 +251,349 @@ impl Hasher for FxHasher64 {
     }
 }
 
+/// A 64-bit implementation of fxhash which starts from a seed.
+///
+/// This hashes the same way as [`FxHasher64`], except that each archived hash
+/// map stores the seed it was built with. The seed is chosen by the
+/// [`HashSeed`] of [`HashWith`](crate::with::HashWith), which defaults to
+/// [`DefaultSeed`]. Use [`RandomSeed`] to give each map its own seed, which
+/// makes it harder to construct keys which collide in a particular map. Even
+/// with a random seed, this does not offer real protection against hash
+/// flooding; use [`SipHasher13`] for keys which may be chosen by an attacker.
+pub struct SeededFxHasher64 {
+    inner: FxHasher64,
+}
+
+impl ArchivedHasher for SeededFxHasher64 {
+    type Seed = ArchivedU64;
+
+    #[inline]
+    fn default_seed() -> Self::Seed {
+        ArchivedU64::from_native(DEFAULT_SEED_WORDS[0])
+    }
+
+    #[inline]
+    fn random_seed() -> Self::Seed {
+        ArchivedU64::from_native(random_words()[0])
+    }
+
+    #[inline]
+    fn with_seed(seed: &Self::Seed) -> Self {
+        Self {
+            inner: FxHasher64 {
+                hash: seed.to_native(),
+            },
+        }
+    }
+}
+
+impl Hasher for SeededFxHasher64 {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.inner.write_u8(i);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.inner.write_u16(i);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.inner.write_u32(i);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.inner.write_u64(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.inner.write_u128(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_usize(i);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.inner.write_isize(i);
+    }
+}
+
+/// A cross-platform implementation of SipHash-1-3 with a 128-bit key.
+///
+/// This is the same hash function used by the standard library's `HashMap`.
+/// Each archived hash map stores the key it was built with, which is chosen by
+/// the [`HashSeed`] of [`HashWith`](crate::with::HashWith). It is slower than
+/// [`FxHasher64`], especially for short keys.
+///
+/// The default seed is [`DefaultSeed`], which uses the same public key for
+/// every map and so offers no protection against hash flooding. To protect
+/// lookups when keys may be chosen by an attacker, archive maps with
+/// `HashWith<SipHasher13, RandomSeed>` so that each one gets its own random
+/// key. Without the `std` feature, there is no source of randomness and every
+/// map uses the same key even with [`RandomSeed`].
+///
+/// # Examples
+/// ```
+/// use core::hash::Hasher;
+///
+/// use rkyv::hash::SipHasher13;
+///
+/// let (k0, k1) = (0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908);
+/// let mut hasher = SipHasher13::new_with_keys(k0, k1);
+/// hasher.write(b"1234");
+/// hasher.write(b"56789");
+/// assert_eq!(hasher.finish(), 0xf3c7_afb3_38b5_c3ee);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SipHasher13 {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64,
+    ntail: usize,
+    length: u64,
+}
+
+impl SipHasher13 {
+    /// Creates a new hasher with the given key.
+    #[inline]
+    pub fn new_with_keys(k0: u64, k1: u64) -> Self {
+        Self {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    #[inline]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline]
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.v0 ^= word;
+    }
+}
+
+#[inline]
+fn read_partial_word(bytes: &[u8]) -> u64 {
+    let mut word = [0; 8];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+impl ArchivedHasher for SipHasher13 {
+    type Seed = [ArchivedU64; 2];
+
+    #[inline]
+    fn default_seed() -> Self::Seed {
+        DEFAULT_SEED_WORDS.map(ArchivedU64::from_native)
+    }
+
+    #[inline]
+    fn random_seed() -> Self::Seed {
+        random_words().map(ArchivedU64::from_native)
+    }
+
+    #[inline]
+    fn with_seed(seed: &Self::Seed) -> Self {
+        Self::new_with_keys(seed[0].to_native(), seed[1].to_native())
+    }
+}
+
+impl Hasher for SipHasher13 {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len() as u64);
+
+        if self.ntail != 0 {
+            let fill = (8 - self.ntail).min(bytes.len());
+            self.tail |= read_partial_word(&bytes[..fill]) << (8 * self.ntail);
+            self.ntail += fill;
+            bytes = &bytes[fill..];
+            if self.ntail < 8 {
+                return;
+            }
+            self.compress(self.tail);
+            self.tail = 0;
+            self.ntail = 0;
+        }
+
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            self.compress(read_partial_word(word));
+        }
+        let rest = words.remainder();
+        self.tail = read_partial_word(rest);
+        self.ntail = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = *self;
+        state.compress((self.length << 56) | self.tail);
+        state.v2 ^= 0xff;
+        state.round();
+        state.round();
+        state.round();
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as FixedUsize).to_le_bytes());
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write(&(i as FixedIsize).to_le_bytes());
+    }
+}
+
+/// A CRC-32C (Castagnoli) hasher.
+///
+/// Unlike [`FxHasher64`], the result of this hasher does not depend on how the
+/// input bytes are split across calls to [`write`](Hasher::write). This makes
+/// it suitable for computing checksums of archives as they are being written.
+/// The 32-bit checksum is returned in the low bits of
+/// [`finish`](Hasher::finish).
+///
+/// # Examples
+/// ```
+/// use core::hash::Hasher;
+///
+/// use rkyv::hash::Crc32c;
+///
+/// let mut hasher = Crc32c::default();
+/// hasher.write(b"1234");
+/// hasher.write(b"56789");
+/// assert_eq!(hasher.finish(), 0xe306_9283);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32c {
+    state: u32,
+}
+
+impl Crc32c {
+    const POLYNOMIAL: u32 = 0x82_f6_3b_78;
+    const TABLE: [u32; 256] = Self::make_table();
+
+    const fn make_table() -> [u32; 256] {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ Self::POLYNOMIAL
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    }
+
+    /// Returns the checksum of the bytes written so far.
+    #[inline]
+    pub fn checksum(&self) -> u32 {
+        !self.state
+    }
+}
+
+impl Default for Crc32c {
+    #[inline]
+    fn default() -> Self {
+        Self { state: !0 }
+    }
+}
+
+impl Hasher for Crc32c {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut crc = self.state;
+        for &byte in bytes {
+            crc = Self::TABLE[((crc ^ byte as u32) & 0xff) as usize]
+                ^ (crc >> 8);
+        }
+        self.state = crc;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.checksum() as u64
+    }
+}
+
+/// Hashes the given value with a hasher created from the given seed.
+#[inline]
+pub fn hash_value_with_seed<Q, H: ArchivedHasher>(
+    value: &Q,
+    seed: &H::Seed,
+) -> u64
+where
+    Q: Hash + ?Sized,
+{
+    let mut state = H::with_seed(seed);
+    value.hash(&mut state);
+    state.finish()
+}
+
 /// Hashes the given value with the default value of the specified `Hasher`.
 pub fn hash_value<Q, H: Hasher + Default>(value: &Q) -> u64
 where
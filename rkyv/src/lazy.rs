@@ -0,0 +1,519 @@
+//! An archived value which is validated on demand.
+
+use core::fmt;
+
+use rancor::Fallible;
+
+use crate::{
+    ArchivePointee, ArchiveUnsized, Portable, RelPtr, SerializeUnsized,
+};
+
+/// An archived value which is only validated when it is accessed.
+///
+/// `ArchivedLazy` is produced by serializing a field with
+/// [`Lazy`](crate::with::Lazy). The value is stored out-of-line like an
+/// [`ArchivedBox`](crate::boxed::ArchivedBox), but validating an
+/// `ArchivedLazy` only checks that its pointer is in bounds and aligned. The
+/// pointed-to value is not checked until it is accessed through a
+/// [`LazyAccess`].
+///
+/// Laziness is opted into by the schema: collections like `ArchivedVec` and
+/// `ArchivedHashMap` are still validated eagerly unless their elements are
+/// `ArchivedLazy`. This makes it possible to access one value of a very large
+/// map without validating every other value in it.
+///
+/// `ArchivedLazy` does not implement `Deserialize`, because deserializing it
+/// after accessing the root of an archive would read unvalidated memory. Get
+/// the value from a `LazyAccess` and deserialize that instead.
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+#[repr(transparent)]
+pub struct ArchivedLazy<T: ArchivePointee + ?Sized> {
+    ptr: RelPtr<T>,
+}
+
+impl<T: ArchivePointee + ?Sized> ArchivedLazy<T> {
+    /// Returns a reference to the value without validating it.
+    ///
+    /// # Safety
+    ///
+    /// The value must have already been validated, or the archive must come
+    /// from a trusted source.
+    #[inline]
+    pub unsafe fn get_unchecked(&self) -> &T {
+        &*self.ptr.as_ptr()
+    }
+
+    /// Resolves an archived lazy value from the given value and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `value`
+    #[inline]
+    pub unsafe fn resolve_from_ref<U: ArchiveUnsized<Archived = T> + ?Sized>(
+        value: &U,
+        pos: usize,
+        resolver: LazyResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.ptr);
+        RelPtr::emplace_unsized(
+            pos + fp,
+            resolver.pos,
+            value.archived_metadata(),
+            fo,
+        );
+    }
+
+    /// Serializes an archived lazy value from the given value and serializer.
+    #[inline]
+    pub fn serialize_from_ref<U, S>(
+        value: &U,
+        serializer: &mut S,
+    ) -> Result<LazyResolver, S::Error>
+    where
+        U: SerializeUnsized<S, Archived = T> + ?Sized,
+        S: Fallible + ?Sized,
+    {
+        Ok(LazyResolver {
+            pos: value.serialize_unsized(serializer)?,
+        })
+    }
+}
+
+impl<T: ArchivePointee + ?Sized> fmt::Debug for ArchivedLazy<T>
+where
+    T::ArchivedMetadata: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArchivedLazy").field(&self.ptr).finish()
+    }
+}
+
+/// The resolver for [`ArchivedLazy`].
+pub struct LazyResolver {
+    pos: usize,
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::verify::{Checked, LazyAccess};
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{any::TypeId, cell::RefCell, fmt, mem::size_of};
+    #[cfg(feature = "std")]
+    use std::collections::HashSet;
+
+    use bytecheck::{
+        rancor::{Error, Fallible, ResultExt as _, Strategy},
+        CheckBytes, Verify,
+    };
+    #[cfg(not(feature = "std"))]
+    use hashbrown::HashSet;
+    use rancor::fail;
+
+    use super::ArchivedLazy;
+    use crate::{
+        validation::{
+            path::PointeeCheckContext,
+            util::access_pos_with_context,
+            validators::{DefaultValidator, ValidationLimits},
+            ArchiveContext, ArchiveContextExt, LayoutRaw,
+        },
+        Archive, ArchivePointee,
+    };
+
+    unsafe impl<T, C> Verify<C> for ArchivedLazy<T>
+    where
+        T: ArchivePointee + LayoutRaw + ?Sized,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        #[inline]
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            // Only check that the value is in bounds. Its subtree is not
+            // claimed, and is checked when it is accessed.
+            unsafe {
+                context.bounds_check_subtree_rel_ptr(&self.ptr)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct OutOfBounds {
+        address: usize,
+    }
+
+    impl fmt::Display for OutOfBounds {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "lazy value at address {:#x} is not located in the archive",
+                self.address,
+            )
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for OutOfBounds {}
+
+    /// Accesses an archive while only validating the lazy values which are
+    /// actually used.
+    ///
+    /// Accessing the root of the archive validates everything except the
+    /// values behind [`ArchivedLazy`]s. Each lazy value is validated the first
+    /// time it is accessed with [`get`](LazyAccess::get), and the result is
+    /// cached so later accesses are free. Large lazy collections can be
+    /// navigated without validating them completely by getting a [`Checked`]
+    /// value with [`checked`](LazyAccess::checked) instead.
+    ///
+    /// The root and every lazy value are checked with the same validator, so
+    /// resource limits apply to all of them together and shared pointers must
+    /// agree across all of them. The subtrees of different lazy values are not
+    /// checked for overlap with each other or with the rest of the archive.
+    /// This is sound because lazily-validated values can only be accessed
+    /// immutably.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::{
+    ///     lazy::LazyAccess,
+    ///     rancor::Failure,
+    ///     to_bytes,
+    ///     with::{Lazy, Map},
+    ///     Archive, Serialize,
+    /// };
+    ///
+    /// #[derive(Archive, Serialize)]
+    /// #[archive(check_bytes)]
+    /// struct Index {
+    ///     #[with(Map<Lazy>)]
+    ///     entries: Vec<String>,
+    /// }
+    ///
+    /// let index = Index {
+    ///     entries: vec!["hello".to_string(), "world".to_string()],
+    /// };
+    /// let bytes = to_bytes::<_, 256, Failure>(&index).unwrap();
+    ///
+    /// let access = LazyAccess::new(&bytes);
+    /// let archived = access.root::<Index, Failure>().unwrap();
+    /// let entry = access.get::<_, Failure>(&archived.entries[1]).unwrap();
+    /// assert_eq!(entry.as_str(), "world");
+    /// assert_eq!(access.checked_len(), 1);
+    /// ```
+    #[derive(Debug)]
+    pub struct LazyAccess<'a> {
+        bytes: &'a [u8],
+        validator: RefCell<DefaultValidator>,
+        checked: RefCell<HashSet<(usize, TypeId)>>,
+    }
+
+    impl<'a> LazyAccess<'a> {
+        /// Creates a new lazy accessor for the given bytes.
+        #[inline]
+        pub fn new(bytes: &'a [u8]) -> Self {
+            Self::with_limits(bytes, ValidationLimits::default())
+        }
+
+        /// Creates a new lazy accessor for the given bytes which enforces the
+        /// given resource limits.
+        ///
+        /// The limits apply to the root and all of the lazy values checked
+        /// together.
+        #[inline]
+        pub fn with_limits(bytes: &'a [u8], limits: ValidationLimits) -> Self {
+            Self {
+                bytes,
+                validator: RefCell::new(DefaultValidator::with_limits(
+                    bytes, limits,
+                )),
+                checked: RefCell::new(HashSet::new()),
+            }
+        }
+
+        /// Returns the bytes of the archive.
+        #[inline]
+        pub fn bytes(&self) -> &'a [u8] {
+            self.bytes
+        }
+
+        /// Returns the number of lazy values which have been validated.
+        #[inline]
+        pub fn checked_len(&self) -> usize {
+            self.checked.borrow().len()
+        }
+
+        /// Returns the total number of collection elements checked so far.
+        #[inline]
+        pub fn total_elements(&self) -> usize {
+            self.validator.borrow().archive().total_elements()
+        }
+
+        /// Returns the total archived size in bytes of all collections checked
+        /// so far.
+        #[inline]
+        pub fn archived_bytes(&self) -> usize {
+            self.validator.borrow().archive().archived_bytes()
+        }
+
+        /// Returns the estimated number of bytes that deserializing all
+        /// collections checked so far would allocate.
+        #[inline]
+        pub fn alloc_bytes(&self) -> usize {
+            self.validator.borrow().archive().alloc_bytes()
+        }
+
+        /// Calls `f` with the validator of this accessor, reset to check a new
+        /// subtree of the archive.
+        ///
+        /// If `f` fails, the shared values found so far are forgotten because
+        /// some of them may not have been completely checked.
+        pub(crate) fn with_context<R, E: Error>(
+            &self,
+            f: impl FnOnce(&mut DefaultValidator) -> Result<R, E>,
+        ) -> Result<R, E> {
+            let mut validator = self.validator.borrow_mut();
+            validator.reset_subtree(self.bytes);
+            let result = f(&mut validator);
+            if result.is_err() {
+                validator.clear_shared();
+            }
+            result
+        }
+
+        /// Accesses the root of the archive by calculating the root position.
+        ///
+        /// Everything except the values behind `ArchivedLazy`s is validated.
+        #[inline]
+        pub fn root<T, E>(&self) -> Result<&'a T::Archived, E>
+        where
+            T: Archive,
+            T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+            E: Error,
+        {
+            self.root_pos::<T, E>(
+                self.bytes.len().saturating_sub(size_of::<T::Archived>()),
+            )
+        }
+
+        /// Accesses the root of the archive at the given position.
+        ///
+        /// Everything except the values behind `ArchivedLazy`s is validated.
+        #[inline]
+        pub fn root_pos<T, E>(&self, pos: usize) -> Result<&'a T::Archived, E>
+        where
+            T: Archive,
+            T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+            E: Error,
+        {
+            self.with_context(|validator| {
+                access_pos_with_context::<T, _, E>(self.bytes, pos, validator)
+            })
+        }
+
+        /// Validates the value behind the given `ArchivedLazy` if it has not
+        /// been validated yet, and returns it.
+        ///
+        /// The `ArchivedLazy` must be located in the archive of this accessor.
+        pub fn get<T, E>(&self, lazy: &'a ArchivedLazy<T>) -> Result<&'a T, E>
+        where
+            T: ArchivePointee
+                + CheckBytes<Strategy<DefaultValidator, E>>
+                + LayoutRaw
+                + ?Sized
+                + 'static,
+            E: Error,
+        {
+            let ptr = self.bounds_check_lazy(lazy)?;
+            unsafe { self.check_ptr(ptr) }
+        }
+
+        /// Returns the value behind the given `ArchivedLazy` after only
+        /// checking that it is located in the archive.
+        ///
+        /// The returned [`Checked`] validates only the parts of the value
+        /// which are accessed through it. The `ArchivedLazy` must be located
+        /// in the archive of this accessor.
+        pub fn checked<T, E>(
+            &'a self,
+            lazy: &'a ArchivedLazy<T>,
+        ) -> Result<Checked<'a, T>, E>
+        where
+            T: ArchivePointee + LayoutRaw + ?Sized,
+            E: Error,
+        {
+            let ptr = self.bounds_check_lazy(lazy)?;
+            Ok(unsafe { Checked::new(self, ptr) })
+        }
+
+        fn bounds_check_lazy<T, E>(
+            &self,
+            lazy: &'a ArchivedLazy<T>,
+        ) -> Result<*const T, E>
+        where
+            T: ArchivePointee + LayoutRaw + ?Sized,
+            E: Error,
+        {
+            let start = self.bytes.as_ptr() as usize;
+            let address = lazy as *const ArchivedLazy<T> as usize;
+            if address < start
+                || address - start + size_of::<ArchivedLazy<T>>()
+                    > self.bytes.len()
+            {
+                fail!(OutOfBounds { address });
+            }
+
+            self.with_context(|validator| unsafe {
+                Strategy::<_, E>::wrap(validator)
+                    .bounds_check_subtree_rel_ptr(&lazy.ptr)
+            })
+        }
+
+        /// Validates the value at the given pointer if it has not been
+        /// validated yet, and returns it.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must be located in the archive of this accessor and properly
+        /// aligned for `T`.
+        unsafe fn check_ptr<T, E>(&self, ptr: *const T) -> Result<&'a T, E>
+        where
+            T: CheckBytes<Strategy<DefaultValidator, E>>
+                + LayoutRaw
+                + ?Sized
+                + 'static,
+            E: Error,
+        {
+            let key = (ptr as *const u8 as usize, TypeId::of::<T>());
+            if !self.checked.borrow().contains(&key) {
+                self.with_context(|validator| unsafe {
+                    let context = Strategy::<_, E>::wrap(validator);
+                    let range = context.push_prefix_subtree(ptr)?;
+                    T::check_bytes(ptr, context).with_trace(|| {
+                        PointeeCheckContext {
+                            address: ptr as *const u8 as usize,
+                        }
+                    })?;
+                    context.pop_subtree_range(range)
+                })?;
+                self.checked.borrow_mut().insert(key);
+            }
+
+            // SAFETY: The value was validated either just now or by a
+            // previous call with the same pointer and type.
+            unsafe { Ok(&*ptr) }
+        }
+    }
+
+    /// A value in a [`LazyAccess`] which is located in the archive, but has not
+    /// been validated.
+    ///
+    /// Only the parts of the value which are accessed are validated. Getting
+    /// an element from a `Checked` [`ArchivedVec`](crate::vec::ArchivedVec),
+    /// [`ArchivedBox`](crate::boxed::ArchivedBox), or
+    /// [`ArchivedHashMap`](crate::collections::swiss_table::ArchivedHashMap)
+    /// checks only the pointers followed to reach it, and returns another
+    /// `Checked` value. Call [`check`](Checked::check) to validate the value
+    /// and get a reference to it.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rkyv::{
+    ///     lazy::LazyAccess, rancor::Failure, to_bytes, with::Lazy, Archive,
+    ///     Serialize,
+    /// };
+    ///
+    /// #[derive(Archive, Serialize)]
+    /// #[archive(check_bytes)]
+    /// struct Index {
+    ///     #[with(Lazy)]
+    ///     entries: HashMap<String, Vec<u32>>,
+    /// }
+    ///
+    /// let index = Index {
+    ///     entries: HashMap::from([
+    ///         ("a".to_string(), vec![1, 2, 3]),
+    ///         ("b".to_string(), vec![4, 5, 6]),
+    ///     ]),
+    /// };
+    /// let bytes = to_bytes::<_, 256, Failure>(&index).unwrap();
+    ///
+    /// let access = LazyAccess::new(&bytes);
+    /// let archived = access.root::<Index, Failure>().unwrap();
+    /// let entries = access.checked::<_, Failure>(&archived.entries).unwrap();
+    /// let values = entries.get::<_, Failure>("b").unwrap().unwrap();
+    /// let value = values.get::<Failure>(1).unwrap().unwrap();
+    /// assert_eq!(*value.check::<Failure>().unwrap(), 5);
+    /// ```
+    pub struct Checked<'a, T: ?Sized> {
+        access: &'a LazyAccess<'a>,
+        ptr: *const T,
+    }
+
+    impl<'a, T: ?Sized> Checked<'a, T> {
+        /// Creates a new `Checked` value from a pointer.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must be located in the archive of `access` and properly
+        /// aligned for `T`.
+        #[inline]
+        pub(crate) unsafe fn new(
+            access: &'a LazyAccess<'a>,
+            ptr: *const T,
+        ) -> Self {
+            Self { access, ptr }
+        }
+
+        /// Returns the accessor this value belongs to.
+        #[inline]
+        pub fn access(&self) -> &'a LazyAccess<'a> {
+            self.access
+        }
+
+        /// Returns a pointer to the unvalidated value.
+        #[inline]
+        pub fn as_ptr(&self) -> *const T {
+            self.ptr
+        }
+
+        /// Validates the value if it has not been validated yet, and returns
+        /// it.
+        #[inline]
+        pub fn check<E>(self) -> Result<&'a T, E>
+        where
+            T: CheckBytes<Strategy<DefaultValidator, E>> + LayoutRaw + 'static,
+            E: Error,
+        {
+            unsafe { self.access.check_ptr(self.ptr) }
+        }
+    }
+
+    impl<T: ?Sized> Clone for Checked<'_, T> {
+        #[inline]
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+
+    impl<T: ?Sized> Copy for Checked<'_, T> {}
+
+    impl<T: ?Sized> fmt::Debug for Checked<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("Checked")
+                .field(&(self.ptr as *const u8))
+                .finish()
+        }
+    }
+}
@@ -79,7 +79,13 @@
 //!   intended to be used only for very large archives and may cause unnecessary
 //!   data bloat.
 //! - `std`: Enables standard library support. Enabled by default.
+//! - `mmap`: Enables `MmapWriter`, which serializes directly into a
+//!   memory-mapped file.
+//! - `tokio`: Enables `AsyncIoWriter` and `serialize_async` for streaming
+//!   archives to a tokio `AsyncWrite` in bounded chunks.
 //! - `bytecheck`: Enables validation support through `bytecheck`.
+//! - `parallel`: Enables `ParallelValidator` and `access_parallel` for
+//!   validating large archives on multiple threads with `rayon`.
 //!
 //! ## Crate support
 //!
@@ -171,6 +177,7 @@ pub mod de;
 pub mod ffi;
 pub mod hash;
 mod impls;
+pub mod lazy;
 pub mod net;
 pub mod niche;
 pub mod ops;
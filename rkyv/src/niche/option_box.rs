@@ -233,3 +233,51 @@ pub enum OptionBoxResolver {
     /// The resolver for the `ArchivedBox`
     Some(BoxResolver),
 }
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use bytecheck::{
+        rancor::{Error, Fallible},
+        CheckBytes,
+    };
+
+    use super::ArchivedOptionBox;
+    use crate::{
+        boxed::ArchivedBox,
+        validation::{ArchiveContext, LayoutRaw},
+        ArchivePointee, RelPtr,
+    };
+
+    unsafe impl<T, C> CheckBytes<C> for ArchivedOptionBox<T>
+    where
+        T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized,
+        T::ArchivedMetadata: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        #[inline]
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            // `ArchivedOptionBox` and `ArchivedBox` are both transparent
+            // wrappers around a `RelPtr`. The pointer and its metadata are
+            // checked first so that the null check reads valid bytes.
+            let ptr = value.cast::<RelPtr<T>>();
+            unsafe {
+                RelPtr::<T>::check_bytes(ptr, context)?;
+            }
+
+            if unsafe { (*ptr).is_null() } {
+                Ok(())
+            } else {
+                unsafe {
+                    ArchivedBox::<T>::check_bytes(
+                        value.cast::<ArchivedBox<T>>(),
+                        context,
+                    )
+                }
+            }
+        }
+    }
+}
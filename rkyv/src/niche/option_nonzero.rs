@@ -16,6 +16,7 @@ macro_rules! impl_archived_option_nonzero {
         #[doc = concat!("A niched archived `Option<", stringify!($nz), ">`")]
         #[derive(Portable)]
         #[archive(crate)]
+        #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
         #[repr(transparent)]
         pub struct $ar {
             inner: Archived<$ne>,
@@ -1,8 +1,8 @@
 //! Archived versions of shared pointers.
 
 use core::{
-    borrow::Borrow, cmp, fmt, hash, marker::PhantomData, ops::Deref, pin::Pin,
-    ptr,
+    any::TypeId, borrow::Borrow, cmp, fmt, hash, marker::PhantomData,
+    ops::Deref, pin::Pin, ptr,
 };
 
 use rancor::Fallible;
@@ -83,10 +83,15 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
         serializer: &mut S,
     ) -> Result<RcResolver, S::Error>
     where
-        U: SerializeUnsized<S> + ?Sized,
+        U: SerializeUnsized<S> + ?Sized + 'static,
+        F: 'static,
         S: Fallible + Writer + Sharing + ?Sized,
     {
-        let pos = serializer.serialize_shared(value)?;
+        // Only values serialized from the same type with the same flavor may
+        // be unified by their contents, so that every pointer to a shared
+        // value validates it as the same archived type.
+        let type_id = TypeId::of::<(PhantomData<F>, PhantomData<U>)>();
+        let pos = serializer.serialize_shared_by_content(value, type_id)?;
 
         // The positions of serialized `Rc` values must be unique. If we didn't
         // write any data by serializing `value`, pad the serializer by a byte
@@ -193,7 +198,6 @@ pub struct RcResolver {
 #[derive(Portable)]
 #[archive(crate)]
 #[repr(u8)]
-#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
 pub enum ArchivedRcWeak<T: ArchivePointee + ?Sized, F> {
     /// A null weak pointer
     None,
@@ -266,7 +270,8 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
         serializer: &mut S,
     ) -> Result<RcWeakResolver, S::Error>
     where
-        U: SerializeUnsized<S, Archived = T> + ?Sized,
+        U: SerializeUnsized<S, Archived = T> + ?Sized + 'static,
+        F: 'static,
         S: Fallible + Writer + Sharing + ?Sized,
     {
         Ok(match value {
@@ -312,50 +317,109 @@ struct ArchivedRcWeakVariantSome<T: ArchivePointee + ?Sized, F>(
 
 #[cfg(feature = "bytecheck")]
 mod verify {
-    use core::any::TypeId;
+    use core::{any::TypeId, ptr::addr_of};
 
     use bytecheck::{
-        rancor::{Error, Fallible},
-        CheckBytes, Verify,
+        rancor::{Error, Fallible, ResultExt as _},
+        CheckBytes, InvalidEnumDiscriminantError, Verify,
     };
+    use rancor::fail;
 
-    use super::ArchivedRc;
+    use super::{
+        ArchivedRc, ArchivedRcWeak, ArchivedRcWeakTag,
+        ArchivedRcWeakVariantSome,
+    };
     use crate::{
         validation::{
-            ArchiveContext, ArchiveContextExt, LayoutRaw, SharedContext,
+            path::PointeeCheckContext, ArchiveContext, ArchiveContextExt,
+            LayoutRaw, SharedContext, SharedPointerKind,
         },
-        ArchivePointee,
+        ArchivePointee, RelPtr,
     };
 
-    unsafe impl<T, F, C> Verify<C> for ArchivedRc<T, F>
-    where
-        T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized + 'static,
-        T::ArchivedMetadata: CheckBytes<C>,
-        F: 'static,
-        C: Fallible + ArchiveContext + SharedContext + ?Sized,
-        C::Error: Error,
-    {
-        #[inline]
-        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+    impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
+        fn verify_shared<C>(
+            &self,
+            kind: SharedPointerKind,
+            context: &mut C,
+        ) -> Result<(), C::Error>
+        where
+            T: CheckBytes<C> + LayoutRaw + 'static,
+            F: 'static,
+            C: Fallible + ArchiveContext + SharedContext + ?Sized,
+            C::Error: Error,
+        {
             let ptr = self.ptr.as_ptr_wrapping();
+            let address = ptr as *const u8 as usize;
             let type_id = TypeId::of::<ArchivedRc<T, F>>();
+            let size =
+                T::layout_raw(ptr_meta::metadata(ptr)).into_error()?.size();
 
-            if context
-                .register_shared_ptr(ptr as *const u8 as usize, type_id)?
-            {
+            if context.register_shared_value(address, size, type_id, kind)? {
                 unsafe {
                     context.bounds_check_subtree_rel_ptr(&self.ptr)?;
                 }
+                context.check_pointee_collection(ptr)?;
 
                 let range = unsafe { context.push_prefix_subtree(ptr)? };
                 unsafe {
-                    T::check_bytes(ptr, context)?;
+                    T::check_bytes(ptr, context)
+                        .with_trace(|| PointeeCheckContext { address })?;
                 }
                 unsafe {
                     context.pop_subtree_range(range)?;
                 }
+                context.finish_shared_value(address)?;
             }
             Ok(())
         }
     }
+
+    unsafe impl<T, F, C> Verify<C> for ArchivedRc<T, F>
+    where
+        T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized + 'static,
+        T::ArchivedMetadata: CheckBytes<C>,
+        F: 'static,
+        C: Fallible + ArchiveContext + SharedContext + ?Sized,
+        C::Error: Error,
+    {
+        #[inline]
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            self.verify_shared(SharedPointerKind::Strong, context)
+        }
+    }
+
+    // `ArchivedRcWeak` is checked by hand so that its pointer is registered as
+    // a weak pointer.
+    unsafe impl<T, F, C> CheckBytes<C> for ArchivedRcWeak<T, F>
+    where
+        T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized + 'static,
+        T::ArchivedMetadata: CheckBytes<C>,
+        F: 'static,
+        C: Fallible + ArchiveContext + SharedContext + ?Sized,
+        C::Error: Error,
+    {
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            let tag = unsafe { *value.cast::<u8>() };
+            if tag == ArchivedRcWeakTag::None as u8 {
+                Ok(())
+            } else if tag == ArchivedRcWeakTag::Some as u8 {
+                let value = value.cast::<ArchivedRcWeakVariantSome<T, F>>();
+                let rc = unsafe { addr_of!((*value).1) };
+                unsafe {
+                    // `ArchivedRc` is a transparent wrapper around a `RelPtr`
+                    RelPtr::<T>::check_bytes(rc.cast(), context)?;
+                    (*rc).verify_shared(SharedPointerKind::Weak, context)
+                }
+            } else {
+                fail!(InvalidEnumDiscriminantError {
+                    enum_name: "ArchivedRcWeak",
+                    invalid_discriminant: tag,
+                })
+            }
+        }
+    }
 }
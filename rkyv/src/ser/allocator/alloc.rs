@@ -6,8 +6,13 @@ use alloc::{
 };
 use core::{alloc::Layout, fmt, ptr::NonNull};
 #[cfg(feature = "std")]
-use std::alloc::{alloc, alloc_zeroed, dealloc};
+use std::{
+    alloc::{alloc, alloc_zeroed, dealloc},
+    collections::hash_map,
+};
 
+#[cfg(not(feature = "std"))]
+use hashbrown::hash_map;
 use rancor::{fail, Error};
 
 use crate::{
@@ -41,6 +46,13 @@ impl<const N: usize> BumpAllocator<N> {
         }
     }
 
+    /// Resets the allocator to its initial state.
+    ///
+    /// The heap-allocated space is kept so that it can be reused.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
     /// Gets the memory layout of the heap-allocated space.
     pub fn layout() -> Layout {
         unsafe { Layout::from_size_align_unchecked(N, 1) }
@@ -115,12 +127,29 @@ const _: () = {
 
 /// Scratch space that always uses the global allocator.
 ///
+/// By default, popped allocations are returned to the global allocator right
+/// away. An allocator created with
+/// [`with_free_list`](GlobalAllocator::with_free_list) instead keeps popped
+/// allocations on a bounded free list and reuses them for later pushes. Sizes
+/// are rounded up to the next power of two so that allocations of similar
+/// sizes can share free list entries.
+///
 /// This allocator will panic if scratch is popped that it did not allocate. For
 /// this reason, it should only ever be used as a fallback allocator.
 #[derive(Debug, Default)]
 pub struct GlobalAllocator {
     remaining: Option<usize>,
     allocations: Vec<(*mut u8, Layout)>,
+    free: hash_map::HashMap<Layout, FreeList>,
+    free_bytes: usize,
+    max_free_bytes: usize,
+    generation: u64,
+}
+
+#[derive(Debug, Default)]
+struct FreeList {
+    ptrs: Vec<*mut u8>,
+    last_used: u64,
 }
 
 // SAFETY: AllocScratch is safe to send to another thread
@@ -143,21 +172,110 @@ impl GlobalAllocator {
     pub fn with_limit(limit: usize) -> Self {
         Self {
             remaining: Some(limit),
-            allocations: Vec::new(),
+            ..Self::default()
         }
     }
-}
 
-impl Drop for GlobalAllocator {
-    fn drop(&mut self) {
-        for (ptr, layout) in self.allocations.drain(..).rev() {
+    /// Creates a new scratch allocator which keeps up to `max_free_bytes` of
+    /// popped allocations for reuse.
+    pub fn with_free_list(max_free_bytes: usize) -> Self {
+        Self {
+            max_free_bytes,
+            ..Self::default()
+        }
+    }
+
+    /// Pops all outstanding allocations.
+    ///
+    /// The popped allocations are kept on the free list if there is room for
+    /// them.
+    pub fn clear(&mut self) {
+        while let Some((ptr, layout)) = self.allocations.pop() {
             unsafe {
-                dealloc(ptr, layout);
+                self.recycle(ptr, layout);
+            }
+        }
+    }
+
+    /// Returns the number of popped allocations which are kept for reuse.
+    pub fn free_allocations(&self) -> usize {
+        self.free.values().map(|list| list.ptrs.len()).sum()
+    }
+
+    /// Returns the total size of the popped allocations which are kept for
+    /// reuse.
+    pub fn free_bytes(&self) -> usize {
+        self.free_bytes
+    }
+
+    /// Frees the allocations on the free list whose layout was neither pushed
+    /// nor popped since the last call to `trim`.
+    ///
+    /// Trimming between uses keeps allocations with layouts that are no longer
+    /// needed from occupying the free list.
+    pub fn trim(&mut self) {
+        let generation = self.generation;
+        let free_bytes = &mut self.free_bytes;
+        self.free.retain(|layout, list| {
+            let keep = list.last_used == generation;
+            if !keep {
+                for &ptr in list.ptrs.iter() {
+                    unsafe {
+                        dealloc(ptr, *layout);
+                    }
+                }
+                *free_bytes -= layout.size() * list.ptrs.len();
             }
+            keep
+        });
+        self.generation += 1;
+    }
+
+    /// Returns all popped allocations to the global allocator.
+    ///
+    /// Outstanding allocations are not affected.
+    pub fn release(&mut self) {
+        for (layout, list) in self.free.drain() {
+            for ptr in list.ptrs {
+                unsafe {
+                    dealloc(ptr, layout);
+                }
+            }
+        }
+        self.free_bytes = 0;
+    }
+
+    fn size_class(&self, layout: Layout) -> Layout {
+        if self.max_free_bytes == 0 {
+            return layout;
+        }
+        layout
+            .size()
+            .checked_next_power_of_two()
+            .and_then(|size| Layout::from_size_align(size, layout.align()).ok())
+            .unwrap_or(layout)
+    }
+
+    unsafe fn recycle(&mut self, ptr: *mut u8, layout: Layout) {
+        if self.free_bytes + layout.size() <= self.max_free_bytes {
+            let list = self.free.entry(layout).or_default();
+            list.ptrs.push(ptr);
+            list.last_used = self.generation;
+            self.free_bytes += layout.size();
+        } else {
+            dealloc(ptr, layout);
         }
     }
 }
 
+impl Drop for GlobalAllocator {
+    fn drop(&mut self) {
+        self.max_free_bytes = 0;
+        self.clear();
+        self.release();
+    }
+}
+
 impl<E: Error> Allocator<E> for GlobalAllocator {
     #[inline]
     unsafe fn push_alloc(
@@ -172,8 +290,23 @@ impl<E: Error> Allocator<E> for GlobalAllocator {
                 });
             }
         }
-        let result_ptr = alloc(layout);
-        assert!(!result_ptr.is_null());
+        let layout = self.size_class(layout);
+        let generation = self.generation;
+        let reused = self.free.get_mut(&layout).and_then(|list| {
+            list.last_used = generation;
+            list.ptrs.pop()
+        });
+        let result_ptr = match reused {
+            Some(ptr) => {
+                self.free_bytes -= layout.size();
+                ptr
+            }
+            None => {
+                let ptr = alloc(layout);
+                assert!(!ptr.is_null());
+                ptr
+            }
+        };
         self.allocations.push((result_ptr, layout));
         let result_slice =
             ptr_meta::from_raw_parts_mut(result_ptr.cast(), layout.size());
@@ -187,10 +320,11 @@ impl<E: Error> Allocator<E> for GlobalAllocator {
         ptr: NonNull<u8>,
         layout: Layout,
     ) -> Result<(), E> {
+        let layout = self.size_class(layout);
         if let Some(&(last_ptr, last_layout)) = self.allocations.last() {
             if ptr.as_ptr() == last_ptr && layout == last_layout {
-                dealloc(ptr.as_ptr(), layout);
                 self.allocations.pop();
+                self.recycle(last_ptr, layout);
                 Ok(())
             } else {
                 fail!(GlobalAllocatorError::NotPoppedInReverseOrder {
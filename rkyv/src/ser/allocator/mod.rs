@@ -64,6 +64,26 @@ impl<P, B> BackupAllocator<P, B> {
     pub fn new(primary: P, backup: B) -> Self {
         Self { primary, backup }
     }
+
+    /// Returns a reference to the primary allocator.
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Returns a mutable reference to the primary allocator.
+    pub fn primary_mut(&mut self) -> &mut P {
+        &mut self.primary
+    }
+
+    /// Returns a reference to the backup allocator.
+    pub fn backup(&self) -> &B {
+        &self.backup
+    }
+
+    /// Returns a mutable reference to the backup allocator.
+    pub fn backup_mut(&mut self) -> &mut B {
+        &mut self.backup
+    }
 }
 
 impl<P, B, E> Allocator<E> for BackupAllocator<P, B>
@@ -1,10 +1,14 @@
 //! Serialization traits and adapters.
 
 pub mod allocator;
+#[cfg(feature = "std")]
+mod pool;
 pub mod sharing;
 pub mod writer;
 
-use ::core::{alloc::Layout, ptr::NonNull};
+use ::core::{alloc::Layout, any::TypeId, ptr::NonNull};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
 
 #[doc(inline)]
 pub use self::{
@@ -12,6 +16,9 @@ pub use self::{
     sharing::{Sharing, SharingExt},
     writer::{Positional, Writer, WriterExt},
 };
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use self::pool::*;
 use crate::{
     ser::{
         allocator::BufferAllocator, sharing::Duplicate, writer::BufferWriter,
@@ -24,6 +31,7 @@ use crate::{
         allocator::{BackupAllocator, BumpAllocator, GlobalAllocator},
         sharing::Unify,
     },
+    string::symbol::StringPool,
     util::AlignedVec,
 };
 
@@ -108,6 +116,44 @@ impl<W, A, S: Sharing<E>, E> Sharing<E> for Composite<W, A, S> {
     fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E> {
         self.share.add_shared_ptr(address, pos)
     }
+
+    #[inline]
+    fn start_shared_ptr(&mut self) -> Result<(), E> {
+        self.share.start_shared_ptr()
+    }
+
+    #[inline]
+    fn finish_shared_ptr(
+        &mut self,
+        address: usize,
+        pos: usize,
+        type_id: TypeId,
+    ) -> Result<usize, E> {
+        self.share.finish_shared_ptr(address, pos, type_id)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<W, A, S: StringPool<E>, E> StringPool<E> for Composite<W, A, S> {
+    #[inline]
+    fn start_string_table(&mut self) -> Result<(), E> {
+        self.share.start_string_table()
+    }
+
+    #[inline]
+    fn get_pooled_str(&self, value: &str) -> Result<Option<usize>, E> {
+        self.share.get_pooled_str(value)
+    }
+
+    #[inline]
+    fn add_pooled_str(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        self.share.add_pooled_str(value, pos)
+    }
+
+    #[inline]
+    fn finish_string_table(&mut self) -> Result<Vec<usize>, E> {
+        self.share.finish_string_table()
+    }
 }
 
 /// A serializer suitable for environments where allocations cannot be made.
@@ -136,3 +182,110 @@ pub type AllocSerializer<const A: usize> = Composite<
     BackupAllocator<BumpAllocator<A>, GlobalAllocator>,
     Unify,
 >;
+
+/// Reusable storage for an [`AllocSerializer`].
+///
+/// Creating a new `AllocSerializer` for every serialization allocates a new
+/// output buffer, scratch space, and shared pointer table. A
+/// `SerializerArena` keeps all of these between serializations and clears them
+/// before each use while retaining their capacity.
+///
+/// Scratch space which spills over into the global allocator is kept on a free
+/// list of up to [`SerializerArena::MAX_FREE_SCRATCH`] bytes. Clearing the
+/// arena frees the allocations whose layouts weren't used since the previous
+/// clear.
+///
+/// Use [`to_bytes_in`](crate::util::to_bytes_in) to serialize a value with an
+/// arena.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked, rancor::Failure, ser::SerializerArena,
+///     util::to_bytes_in,
+/// };
+///
+/// let mut arena = SerializerArena::<256>::new();
+///
+/// for i in 0..10 {
+///     let value = i.to_string();
+///     let bytes = to_bytes_in::<_, 256, Failure>(&value, &mut arena).unwrap();
+///     let archived = unsafe { access_unchecked::<String>(bytes) };
+///     assert_eq!(archived, &value);
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct SerializerArena<const A: usize> {
+    serializer: AllocSerializer<A>,
+}
+
+#[cfg(feature = "alloc")]
+impl<const A: usize> Default for SerializerArena<A> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const A: usize> SerializerArena<A> {
+    /// The maximum number of bytes of spilled scratch space kept for reuse.
+    pub const MAX_FREE_SCRATCH: usize = 1 << 20;
+
+    /// Creates a new serializer arena.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new serializer arena with an output buffer of the given
+    /// capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            serializer: Composite::new(
+                AlignedVec::with_capacity(capacity),
+                BackupAllocator::new(
+                    BumpAllocator::new(),
+                    GlobalAllocator::with_free_list(Self::MAX_FREE_SCRATCH),
+                ),
+                Unify::new(),
+            ),
+        }
+    }
+
+    /// Resets the writer position, rewinds the scratch space, and clears the
+    /// shared pointer table.
+    ///
+    /// The output buffer and shared pointer table keep their capacity. Spilled
+    /// scratch space is trimmed to the layouts used since the last clear.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.serializer.writer.clear();
+        self.serializer.allocator.primary_mut().clear();
+        let backup = self.serializer.allocator.backup_mut();
+        backup.clear();
+        backup.trim();
+        self.serializer.share.clear();
+    }
+
+    /// Clears the arena and returns its serializer.
+    #[inline]
+    pub fn serializer(&mut self) -> &mut AllocSerializer<A> {
+        self.clear();
+        &mut self.serializer
+    }
+
+    /// Returns the bytes written by the most recent serialization.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        self.serializer.writer.as_slice()
+    }
+
+    /// Consumes the arena and returns its serializer.
+    #[inline]
+    pub fn into_serializer(self) -> AllocSerializer<A> {
+        self.serializer
+    }
+}
@@ -0,0 +1,160 @@
+use core::{
+    hash::{Hash, Hasher},
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+use crate::{hash::FxHasher64, ser::SerializerArena};
+
+/// The number of shards to use if the available parallelism can't be
+/// determined.
+const DEFAULT_SHARDS: usize = 8;
+
+/// A thread-safe pool of reusable serializers.
+///
+/// Each serializer in the pool is a [`SerializerArena`], so output buffers,
+/// scratch space, and shared pointer tables are kept allocated between uses.
+/// Serializers are cleared when they are returned to the pool.
+///
+/// The pool is split into shards to reduce contention. Each thread prefers the
+/// shard selected by its thread ID, so serializers tend to be reused by the
+/// same thread that returned them.
+///
+/// # Examples
+/// ```
+/// use std::thread;
+///
+/// use rkyv::{
+///     access_unchecked, rancor::Failure, ser::SerializerPool,
+///     util::to_bytes_in,
+/// };
+///
+/// let pool = SerializerPool::<256>::new();
+///
+/// thread::scope(|s| {
+///     for i in 0..4 {
+///         let pool = &pool;
+///         s.spawn(move || {
+///             let value = i.to_string();
+///             let mut arena = pool.get();
+///             let bytes =
+///                 to_bytes_in::<_, 256, Failure>(&value, &mut arena).unwrap();
+///             let archived = unsafe { access_unchecked::<String>(bytes) };
+///             assert_eq!(archived, &value);
+///         });
+///     }
+/// });
+/// ```
+#[derive(Debug)]
+pub struct SerializerPool<const A: usize> {
+    shards: Box<[Mutex<Vec<SerializerArena<A>>>]>,
+}
+
+impl<const A: usize> Default for SerializerPool<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const A: usize> SerializerPool<A> {
+    /// Creates a new serializer pool with one shard for each available
+    /// hardware thread.
+    pub fn new() -> Self {
+        let shards = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(DEFAULT_SHARDS);
+        Self::with_shards(shards)
+    }
+
+    /// Creates a new serializer pool with the given number of shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards != 0, "serializer pools must have at least one shard");
+        Self {
+            shards: (0..shards).map(|_| Mutex::new(Vec::new())).collect(),
+        }
+    }
+
+    /// Returns the number of idle serializers in the pool.
+    pub fn idle(&self) -> usize {
+        self.shards.iter().map(|shard| lock(shard).len()).sum()
+    }
+
+    /// Takes a serializer from the pool, creating a new one if none are idle.
+    ///
+    /// The serializer is returned to the pool when the returned guard is
+    /// dropped.
+    pub fn get(&self) -> PooledSerializer<'_, A> {
+        let home = self.home_shard();
+
+        let mut arena = None;
+        for i in 0..self.shards.len() {
+            let index = (home + i) % self.shards.len();
+            if let Some(found) = lock(&self.shards[index]).pop() {
+                arena = Some(found);
+                break;
+            }
+        }
+
+        PooledSerializer {
+            pool: self,
+            shard: home,
+            arena: ManuallyDrop::new(arena.unwrap_or_default()),
+        }
+    }
+
+    fn home_shard(&self) -> usize {
+        let mut hasher = FxHasher64::default();
+        thread::current().id().hash(&mut hasher);
+        hasher.finish() as usize % self.shards.len()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Serializers are always cleared before they are returned, so a panic
+    // while holding the lock can't leave a shard in an inconsistent state.
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A serializer borrowed from a [`SerializerPool`].
+///
+/// The serializer is cleared and returned to the pool when this guard is
+/// dropped.
+#[derive(Debug)]
+pub struct PooledSerializer<'a, const A: usize> {
+    pool: &'a SerializerPool<A>,
+    shard: usize,
+    arena: ManuallyDrop<SerializerArena<A>>,
+}
+
+impl<const A: usize> Deref for PooledSerializer<'_, A> {
+    type Target = SerializerArena<A>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.arena
+    }
+}
+
+impl<const A: usize> DerefMut for PooledSerializer<'_, A> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.arena
+    }
+}
+
+impl<const A: usize> Drop for PooledSerializer<'_, A> {
+    fn drop(&mut self) {
+        // SAFETY: `arena` is not used again after being taken.
+        let mut arena = unsafe { ManuallyDrop::take(&mut self.arena) };
+        arena.clear();
+        lock(&self.pool.shards[self.shard]).push(arena);
+    }
+}
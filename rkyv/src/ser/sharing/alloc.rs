@@ -1,4 +1,14 @@
-use core::{fmt, mem::size_of};
+use alloc::{string::String, vec::Vec};
+use core::{
+    alloc::Layout,
+    any::TypeId,
+    cell::Cell,
+    cmp, fmt,
+    hash::{Hash as _, Hasher as _},
+    mem::{self, size_of},
+    ops::Range,
+    ptr::NonNull,
+};
 #[cfg(feature = "std")]
 use std::collections::hash_map;
 
@@ -6,7 +16,11 @@ use std::collections::hash_map;
 use hashbrown::hash_map;
 use rancor::{fail, Error};
 
-use crate::ser::Sharing;
+use crate::{
+    hash::FxHasher64,
+    ser::{Allocator, Positional, Sharing, Writer},
+    string::symbol::StringPool,
+};
 
 #[derive(Debug)]
 struct DuplicateSharedPointer {
@@ -27,11 +41,88 @@ impl fmt::Display for DuplicateSharedPointer {
 #[cfg(feature = "std")]
 impl std::error::Error for DuplicateSharedPointer {}
 
+#[derive(Debug)]
+struct UnenclosedSymbol;
+
+impl fmt::Display for UnenclosedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "strings were interned outside of an `Interned` value")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnenclosedSymbol {}
+
+#[derive(Debug)]
+struct NestedInterned;
+
+impl fmt::Display for NestedInterned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`Interned` values may not be nested")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NestedInterned {}
+
+/// The entries of the string table which is being written.
+#[derive(Debug, Default)]
+struct PooledStrs {
+    started: bool,
+    entries: Vec<usize>,
+    str_to_pos: hash_map::HashMap<String, usize>,
+}
+
+impl PooledStrs {
+    fn start<E: Error>(&mut self) -> Result<(), E> {
+        if self.started {
+            fail!(NestedInterned);
+        }
+        self.started = true;
+        Ok(())
+    }
+
+    fn get<E: Error>(&self, value: &str) -> Result<Option<usize>, E> {
+        if !self.started {
+            fail!(UnenclosedSymbol);
+        }
+        Ok(self.str_to_pos.get(value).copied())
+    }
+
+    fn add<E: Error>(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        if !self.started {
+            fail!(UnenclosedSymbol);
+        }
+        self.entries.push(pos);
+        self.str_to_pos.insert(String::from(value), pos);
+        Ok(())
+    }
+
+    fn finish<E: Error>(&mut self) -> Result<Vec<usize>, E> {
+        if !self.started {
+            fail!(UnenclosedSymbol);
+        }
+        self.started = false;
+        self.str_to_pos.clear();
+        Ok(mem::take(&mut self.entries))
+    }
+
+    fn clear(&mut self) {
+        self.started = false;
+        self.entries.clear();
+        self.str_to_pos.clear();
+    }
+}
+
 /// A shared pointer strategy that unifies serializations of the same shared
 /// pointer.
+///
+/// `Unify` is also a [`StringPool`] which writes each distinct interned string
+/// to the string table once.
 #[derive(Debug, Default)]
 pub struct Unify {
     shared_address_to_pos: hash_map::HashMap<usize, usize>,
+    pooled_strs: PooledStrs,
 }
 
 impl Unify {
@@ -40,6 +131,7 @@ impl Unify {
     pub fn new() -> Self {
         Self {
             shared_address_to_pos: hash_map::HashMap::new(),
+            pooled_strs: PooledStrs::default(),
         }
     }
 
@@ -48,8 +140,18 @@ impl Unify {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             shared_address_to_pos: hash_map::HashMap::with_capacity(capacity),
+            pooled_strs: PooledStrs::default(),
         }
     }
+
+    /// Clears all shared pointers and interned strings from the unifier.
+    ///
+    /// The capacity of the unifier is kept so that it can be reused.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.shared_address_to_pos.clear();
+        self.pooled_strs.clear();
+    }
 }
 
 impl<E: Error> Sharing<E> for Unify {
@@ -69,3 +171,388 @@ impl<E: Error> Sharing<E> for Unify {
         }
     }
 }
+
+impl<E: Error> StringPool<E> for Unify {
+    fn start_string_table(&mut self) -> Result<(), E> {
+        self.pooled_strs.start()
+    }
+
+    fn get_pooled_str(&self, value: &str) -> Result<Option<usize>, E> {
+        self.pooled_strs.get(value)
+    }
+
+    fn add_pooled_str(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        self.pooled_strs.add(value, pos)
+    }
+
+    fn finish_string_table(&mut self) -> Result<Vec<usize>, E> {
+        self.pooled_strs.finish()
+    }
+}
+
+/// A shared value which is in the middle of being serialized.
+#[derive(Debug)]
+struct Span {
+    /// The position that serialization of the value started at.
+    start: usize,
+    /// The lowest position pointed to by any of the bytes in the span.
+    min_target: Cell<usize>,
+    /// The number of content entries when the span was started.
+    entries_len: usize,
+    /// The number of logged addresses when the span was started.
+    added_len: usize,
+}
+
+/// The contents of a previously-serialized shared value.
+#[derive(Debug)]
+struct ContentEntry {
+    type_id: TypeId,
+    root_offset: usize,
+    bytes: Range<usize>,
+    pos: usize,
+    next: Option<usize>,
+}
+
+/// A serializer adapter that unifies shared values with the same contents.
+///
+/// [`Unify`] only unifies shared pointers with the same address, so separate
+/// `Rc` and `Arc`s with equal contents are each serialized. `DedupByContent`
+/// additionally compares the serialized bytes of each shared value, and points
+/// every shared pointer with the same type and contents to the first copy that
+/// was written.
+///
+/// Because the bytes of a shared value must be inspected before they can be
+/// discarded, `DedupByContent` wraps an entire serializer instead of being
+/// used as the sharing strategy of a [`Composite`](crate::ser::Composite). It
+/// forwards writes and allocations to the wrapped serializer, and buffers the
+/// bytes of shared values until they are complete. The wrapped serializer's
+/// sharing strategy is not used.
+///
+/// Shared values are only unified when:
+///
+/// - They were serialized from the same type with the same flavor of shared
+///   pointer.
+/// - Their serialized bytes are identical, including any padding that was
+///   written before them.
+/// - They don't point to any data outside of themselves. A shared value which
+///   contains a pointer to another shared value that was serialized before it
+///   started is never unified.
+///
+/// A copy of the bytes of each unique shared value is kept so that contents
+/// can be compared exactly.
+///
+/// The out-of-line bytes of strings and vectors (e.g. `String` and `Vec<u8>`)
+/// are never unified. Those bytes are owned by a single archived value, and
+/// validation requires that owned bytes never overlap. To deduplicate strings,
+/// store them as `Rc<str>` or `Arc<str>` instead, or intern them with
+/// [`Intern`](crate::with::Intern): `DedupByContent` is also a [`StringPool`]
+/// which writes each distinct interned string to the string table once.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use rkyv::{
+///     access,
+///     rancor::Failure,
+///     ser::{allocator::GlobalAllocator, sharing::DedupByContent, Composite},
+///     serialize,
+///     util::AlignedVec,
+/// };
+///
+/// let names = (0..4)
+///     .map(|_| Arc::<str>::from("a long string which is repeated"))
+///     .collect::<Vec<_>>();
+///
+/// let mut serializer = DedupByContent::new(Composite::new(
+///     AlignedVec::new(),
+///     GlobalAllocator::new(),
+///     (),
+/// ));
+/// serialize::<_, _, Failure>(&names, &mut serializer).unwrap();
+/// let bytes = serializer.into_inner().into_writer();
+///
+/// let archived = access::<Vec<Arc<str>>, Failure>(&bytes).unwrap();
+/// assert!(archived.iter().all(|name| name.as_ptr() == archived[0].as_ptr()));
+/// ```
+#[derive(Debug)]
+pub struct DedupByContent<S> {
+    inner: S,
+    buffer: Vec<u8>,
+    spans: Vec<Span>,
+    shared_address_to_pos: hash_map::HashMap<usize, usize>,
+    added: Vec<usize>,
+    content_heads: hash_map::HashMap<u64, usize>,
+    entries: Vec<ContentEntry>,
+    contents: Vec<u8>,
+    pooled_strs: PooledStrs,
+}
+
+impl<S> DedupByContent<S> {
+    /// Creates a new content-deduplicating serializer wrapping the given
+    /// serializer.
+    #[inline]
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            spans: Vec::new(),
+            shared_address_to_pos: hash_map::HashMap::new(),
+            added: Vec::new(),
+            content_heads: hash_map::HashMap::new(),
+            entries: Vec::new(),
+            contents: Vec::new(),
+            pooled_strs: PooledStrs::default(),
+        }
+    }
+
+    /// Returns a reference to the wrapped serializer.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the number of unique shared values which have been serialized.
+    #[inline]
+    pub fn unique_values(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Clears all shared pointers, contents, and interned strings from the
+    /// serializer.
+    ///
+    /// The wrapped serializer is not cleared.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.spans.clear();
+        self.shared_address_to_pos.clear();
+        self.added.clear();
+        self.content_heads.clear();
+        self.entries.clear();
+        self.contents.clear();
+        self.pooled_strs.clear();
+    }
+
+    /// Consumes the serializer and returns the wrapped serializer.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn point_to(&self, pos: usize) {
+        if let Some(span) = self.spans.last() {
+            span.min_target.set(cmp::min(span.min_target.get(), pos));
+        }
+    }
+
+    fn find_contents(
+        &self,
+        hash: u64,
+        type_id: TypeId,
+        root_offset: usize,
+        bytes: &[u8],
+    ) -> Option<usize> {
+        let mut next = self.content_heads.get(&hash).copied();
+        while let Some(index) = next {
+            let entry = &self.entries[index];
+            if entry.type_id == type_id
+                && entry.root_offset == root_offset
+                && self.contents[entry.bytes.clone()] == *bytes
+            {
+                return Some(entry.pos);
+            }
+            next = entry.next;
+        }
+        None
+    }
+
+    /// Moves every position added during `span` to the same offset in the
+    /// identical copy starting at `copy_start`.
+    fn relocate(&mut self, span: &Span, copy_start: usize) {
+        let relocate = |pos: &mut usize| {
+            if *pos >= span.start {
+                *pos = *pos - span.start + copy_start;
+            }
+        };
+
+        for address in &self.added[span.added_len..] {
+            if let Some(pos) = self.shared_address_to_pos.get_mut(address) {
+                relocate(pos);
+            }
+        }
+        for entry in &mut self.entries[span.entries_len..] {
+            relocate(&mut entry.pos);
+        }
+    }
+
+    fn insert_shared_ptr<E: Error>(
+        &mut self,
+        address: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        match self.shared_address_to_pos.entry(address) {
+            hash_map::Entry::Occupied(_) => {
+                fail!(DuplicateSharedPointer { address });
+            }
+            hash_map::Entry::Vacant(e) => {
+                e.insert(pos);
+            }
+        }
+        if !self.spans.is_empty() {
+            self.added.push(address);
+        }
+        Ok(())
+    }
+}
+
+fn hash_contents(type_id: TypeId, root_offset: usize, bytes: &[u8]) -> u64 {
+    let mut hasher = FxHasher64::default();
+    type_id.hash(&mut hasher);
+    root_offset.hash(&mut hasher);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+impl<S: Positional> Positional for DedupByContent<S> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos() + self.buffer.len()
+    }
+}
+
+impl<S: Writer<E>, E> Writer<E> for DedupByContent<S> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if self.spans.is_empty() {
+            self.inner.write(bytes)
+        } else {
+            self.buffer.extend_from_slice(bytes);
+            Ok(())
+        }
+    }
+}
+
+impl<S: Allocator<E>, E> Allocator<E> for DedupByContent<S> {
+    #[inline]
+    unsafe fn push_alloc(
+        &mut self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, E> {
+        self.inner.push_alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn pop_alloc(
+        &mut self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+    ) -> Result<(), E> {
+        self.inner.pop_alloc(ptr, layout)
+    }
+}
+
+impl<S: Writer<E>, E: Error> Sharing<E> for DedupByContent<S> {
+    fn get_shared_ptr(&self, address: usize) -> Option<usize> {
+        let pos = self.shared_address_to_pos.get(&address).copied()?;
+        self.point_to(pos);
+        Some(pos)
+    }
+
+    fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E> {
+        self.insert_shared_ptr(address, pos)
+    }
+
+    fn start_shared_ptr(&mut self) -> Result<(), E> {
+        self.spans.push(Span {
+            start: self.pos(),
+            min_target: Cell::new(usize::MAX),
+            entries_len: self.entries.len(),
+            added_len: self.added.len(),
+        });
+        Ok(())
+    }
+
+    fn finish_shared_ptr(
+        &mut self,
+        address: usize,
+        pos: usize,
+        type_id: TypeId,
+    ) -> Result<usize, E> {
+        let span = match self.spans.pop() {
+            Some(span) => span,
+            None => {
+                // Values which were never started can't be unified.
+                self.insert_shared_ptr(address, pos)?;
+                return Ok(pos);
+            }
+        };
+
+        // While a span is open, all of its bytes are buffered and the wrapped
+        // serializer doesn't move.
+        let buffer_start = span.start - self.inner.pos();
+        let mut result = pos;
+        let mut min_target = span.min_target.get();
+
+        if pos >= span.start && min_target >= span.start {
+            let root_offset = pos - span.start;
+            let bytes = &self.buffer[buffer_start..];
+            let hash = hash_contents(type_id, root_offset, bytes);
+
+            if let Some(existing) =
+                self.find_contents(hash, type_id, root_offset, bytes)
+            {
+                self.relocate(&span, existing - root_offset);
+                self.buffer.truncate(buffer_start);
+                result = existing;
+                min_target = existing;
+            } else {
+                let start = self.contents.len();
+                self.contents.extend_from_slice(bytes);
+                let next = self.content_heads.insert(hash, self.entries.len());
+                self.entries.push(ContentEntry {
+                    type_id,
+                    root_offset,
+                    bytes: start..self.contents.len(),
+                    pos,
+                    next,
+                });
+            }
+        }
+
+        self.insert_shared_ptr(address, result)?;
+        self.point_to(min_target);
+
+        if self.spans.is_empty() {
+            self.inner.write(&self.buffer)?;
+            self.buffer.clear();
+            self.added.clear();
+        }
+
+        Ok(result)
+    }
+}
+
+impl<S, E: Error> StringPool<E> for DedupByContent<S> {
+    fn start_string_table(&mut self) -> Result<(), E> {
+        self.pooled_strs.start()
+    }
+
+    fn get_pooled_str(&self, value: &str) -> Result<Option<usize>, E> {
+        let pos = self.pooled_strs.get(value)?;
+        if let Some(pos) = pos {
+            self.point_to(pos);
+        }
+        Ok(pos)
+    }
+
+    fn add_pooled_str(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        // Shared values which write a string table entry are never unified, so
+        // that entries are written once and in increasing order of position.
+        self.point_to(0);
+        self.pooled_strs.add(value, pos)
+    }
+
+    fn finish_string_table(&mut self) -> Result<Vec<usize>, E> {
+        self.pooled_strs.finish()
+    }
+}
@@ -4,6 +4,8 @@
 mod alloc;
 mod core;
 
+use ::core::any::TypeId;
+
 use rancor::{Fallible, Strategy};
 
 #[cfg(feature = "alloc")]
@@ -22,6 +24,37 @@ pub trait Sharing<E = <Self as Fallible>::Error> {
 
     /// Adds the serialized position of a shared pointer.
     fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E>;
+
+    /// Starts serializing a shared value which may be unified with other
+    /// shared values by its contents.
+    ///
+    /// Every call must be followed by a matching call to
+    /// [`finish_shared_ptr`](Sharing::finish_shared_ptr) once the value has
+    /// been serialized. By default, this does nothing.
+    #[inline]
+    fn start_shared_ptr(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    /// Finishes serializing a shared value started with
+    /// [`start_shared_ptr`](Sharing::start_shared_ptr) and adds it to the
+    /// registry.
+    ///
+    /// `type_id` identifies the type the value was serialized as. Returns the
+    /// position that pointers to the value should use, which may be the
+    /// position of a previously-serialized value with the same type and
+    /// contents. By default, this adds the shared pointer and returns `pos`.
+    #[inline]
+    fn finish_shared_ptr(
+        &mut self,
+        address: usize,
+        pos: usize,
+        type_id: TypeId,
+    ) -> Result<usize, E> {
+        let _ = type_id;
+        self.add_shared_ptr(address, pos)?;
+        Ok(pos)
+    }
 }
 
 impl<T, E> Sharing<E> for Strategy<T, E>
@@ -35,6 +68,19 @@ where
     fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E> {
         T::add_shared_ptr(self, address, pos)
     }
+
+    fn start_shared_ptr(&mut self) -> Result<(), E> {
+        T::start_shared_ptr(self)
+    }
+
+    fn finish_shared_ptr(
+        &mut self,
+        address: usize,
+        pos: usize,
+        type_id: TypeId,
+    ) -> Result<usize, E> {
+        T::finish_shared_ptr(self, address, pos, type_id)
+    }
 }
 
 /// TODO: Document this
@@ -76,6 +122,35 @@ pub trait SharingExt<E>: Sharing<E> {
             Ok(pos)
         }
     }
+
+    /// Archives the given shared value and returns its position, allowing the
+    /// sharing strategy to unify it with other values that have the same type
+    /// and contents.
+    ///
+    /// `type_id` must uniquely identify the archived type of the value. If the
+    /// value has already been added then it returns the position of the
+    /// previously added value.
+    #[inline]
+    fn serialize_shared_by_content<T: SerializeUnsized<Self> + ?Sized>(
+        &mut self,
+        value: &T,
+        type_id: TypeId,
+    ) -> Result<usize, <Self as Fallible>::Error>
+    where
+        Self: Fallible<Error = E>,
+    {
+        if let Some(pos) = self.get_shared(value) {
+            Ok(pos)
+        } else {
+            self.start_shared_ptr()?;
+            let pos = value.serialize_unsized(self)?;
+            self.finish_shared_ptr(
+                value as *const T as *const () as usize,
+                pos,
+                type_id,
+            )
+        }
+    }
 }
 
 impl<S, E> SharingExt<E> for S where S: Sharing<E> + ?Sized {}
@@ -1,8 +1,11 @@
-use core::{fmt, ptr::copy_nonoverlapping};
+use core::{fmt, hash::Hasher, ptr::copy_nonoverlapping};
 
 use rancor::{fail, Error};
 
-use crate::ser::{Positional, Writer};
+use crate::{
+    hash::Crc32c,
+    ser::{Positional, Writer},
+};
 
 #[derive(Debug)]
 struct BufferOverflow {
@@ -134,3 +137,302 @@ impl<T: AsMut<[u8]>, E: Error> Writer<E> for BufferWriter<T> {
         }
     }
 }
+
+/// A destination for fixed-size chunks of an archive.
+///
+/// Closures which accept a byte slice and return a `Result` implement `Sink`.
+pub trait Sink<E> {
+    /// Accepts the next chunk of the archive.
+    fn accept(&mut self, chunk: &[u8]) -> Result<(), E>;
+}
+
+impl<F, E> Sink<E> for F
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    #[inline]
+    fn accept(&mut self, chunk: &[u8]) -> Result<(), E> {
+        self(chunk)
+    }
+}
+
+/// Splits the output of a serializer into fixed-size chunks and passes each
+/// chunk to a [`Sink`] as soon as it is filled.
+///
+/// The size of each chunk is the length of the buffer the writer is created
+/// with. Every chunk except the last one will be exactly that size. The last
+/// chunk is passed to the sink when [`finish`](ChunkedWriter::finish) is
+/// called, and may be shorter. Concatenating all of the chunks produces exactly
+/// the same bytes that writing to a single buffer would have produced.
+///
+/// Chunks are always copied into the buffer before being passed to the sink,
+/// so an aligned buffer like [`AlignedBytes`](crate::util::AlignedBytes)
+/// produces aligned chunks.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Failure,
+///     ser::writer::ChunkedWriter,
+///     serialize,
+///     util::{AlignedBytes, AlignedVec},
+/// };
+///
+/// let value = "hello world, this is a long string".to_string();
+///
+/// let mut chunks = Vec::new();
+/// let mut writer = ChunkedWriter::new(AlignedBytes([0; 16]), |chunk: &[u8]| {
+///     chunks.push(chunk.to_vec());
+///     Ok::<_, Failure>(())
+/// });
+/// serialize::<_, _, Failure>(&value, &mut writer).unwrap();
+/// writer.finish::<Failure>().unwrap();
+///
+/// assert!(chunks[..chunks.len() - 1].iter().all(|c| c.len() == 16));
+///
+/// let mut bytes = AlignedVec::new();
+/// serialize::<_, _, Failure>(&value, &mut bytes).unwrap();
+/// assert_eq!(chunks.concat(), bytes.as_slice());
+/// ```
+#[derive(Debug)]
+pub struct ChunkedWriter<T, S> {
+    buffer: T,
+    sink: S,
+    chunk_size: usize,
+    len: usize,
+    pos: usize,
+}
+
+impl<T: AsMut<[u8]>, S> ChunkedWriter<T, S> {
+    /// Creates a new chunked writer that fills the given buffer before passing
+    /// it to the sink.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is empty.
+    #[inline]
+    pub fn new(mut buffer: T, sink: S) -> Self {
+        let chunk_size = buffer.as_mut().len();
+        assert!(chunk_size != 0, "chunked writer buffers must not be empty");
+        Self {
+            buffer,
+            sink,
+            chunk_size,
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    /// Returns the size of each chunk.
+    #[inline]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns a reference to the sink.
+    #[inline]
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Passes the final, possibly partial, chunk to the sink and returns the
+    /// sink.
+    ///
+    /// If the total length of the output is a multiple of the chunk size,
+    /// no additional chunk is passed to the sink.
+    pub fn finish<E>(mut self) -> Result<S, E>
+    where
+        S: Sink<E>,
+    {
+        if self.len != 0 {
+            self.sink.accept(&self.buffer.as_mut()[..self.len])?;
+        }
+        Ok(self.sink)
+    }
+}
+
+impl<T, S> Positional for ChunkedWriter<T, S> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<T: AsMut<[u8]>, S: Sink<E>, E> Writer<E> for ChunkedWriter<T, S> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        let mut remaining = bytes;
+        while !remaining.is_empty() {
+            let buffer = self.buffer.as_mut();
+            let count = usize::min(self.chunk_size - self.len, remaining.len());
+            buffer[self.len..self.len + count]
+                .copy_from_slice(&remaining[..count]);
+            self.len += count;
+            remaining = &remaining[count..];
+
+            if self.len == self.chunk_size {
+                self.sink.accept(buffer)?;
+                self.len = 0;
+            }
+        }
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
+/// A writer that discards all bytes and only counts how many were written.
+///
+/// Serializing a value with a `SizeCounter` computes the exact length of its
+/// archive without allocating an output buffer. See
+/// [`archived_size`](crate::util::archived_size) for a convenient way to do
+/// this.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Failure,
+///     ser::{writer::SizeCounter, Positional as _},
+///     serialize, to_bytes,
+/// };
+///
+/// let value = "hello world".to_string();
+///
+/// let mut counter = SizeCounter::new();
+/// serialize::<_, _, Failure>(&value, &mut counter).unwrap();
+///
+/// let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+/// assert_eq!(counter.pos(), bytes.len());
+/// ```
+#[derive(Debug, Default)]
+pub struct SizeCounter {
+    pos: usize,
+}
+
+impl SizeCounter {
+    /// Creates a new size counter starting at position 0.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_pos(0)
+    }
+
+    /// Creates a new size counter starting at the given position.
+    #[inline]
+    pub fn with_pos(pos: usize) -> Self {
+        Self { pos }
+    }
+}
+
+impl Positional for SizeCounter {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<E> Writer<E> for SizeCounter {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
+/// Wraps a [`Writer`] and keeps a running hash of every byte written to it.
+///
+/// All bytes that pass through the writer are hashed, including padding. The
+/// final digest can be stored alongside the archive and later verified with
+/// [`access_checked_with_digest`](crate::validation::util::access_checked_with_digest).
+///
+/// The hasher must produce the same result regardless of how its input is
+/// split across calls to [`write`](Hasher::write), since the verifier hashes
+/// the archive all at once. [`Crc32c`] (the default) satisfies this
+/// requirement.
+///
+/// # Examples
+/// ```
+/// use core::hash::Hasher as _;
+///
+/// use rkyv::{
+///     hash::Crc32c, rancor::Failure, ser::writer::HashingWriter, serialize,
+///     util::AlignedVec,
+/// };
+///
+/// let mut writer = HashingWriter::<_, Crc32c>::new(AlignedVec::new());
+/// serialize::<_, _, Failure>(&"hello world".to_string(), &mut writer)
+///     .unwrap();
+/// let digest = writer.digest();
+/// let bytes = writer.into_inner();
+///
+/// let mut hasher = Crc32c::default();
+/// hasher.write(&bytes);
+/// assert_eq!(hasher.finish(), digest);
+/// ```
+#[derive(Debug, Default)]
+pub struct HashingWriter<W, H = Crc32c> {
+    inner: W,
+    hasher: H,
+}
+
+impl<W, H: Default> HashingWriter<W, H> {
+    /// Creates a new hashing writer with a default hasher.
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Self::with_hasher(inner, H::default())
+    }
+}
+
+impl<W, H> HashingWriter<W, H> {
+    /// Creates a new hashing writer with the given hasher.
+    #[inline]
+    pub fn with_hasher(inner: W, hasher: H) -> Self {
+        Self { inner, hasher }
+    }
+
+    /// Returns a reference to the underlying writer.
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a reference to the hasher.
+    #[inline]
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Consumes the hashing writer and returns the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Consumes the hashing writer and returns the underlying writer and
+    /// hasher.
+    #[inline]
+    pub fn into_raw_parts(self) -> (W, H) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<W, H: Hasher> HashingWriter<W, H> {
+    /// Returns the digest of all of the bytes written so far.
+    #[inline]
+    pub fn digest(&self) -> u64 {
+        self.hasher.finish()
+    }
+}
+
+impl<W: Positional, H> Positional for HashingWriter<W, H> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+}
+
+impl<W: Writer<E>, H: Hasher, E> Writer<E> for HashingWriter<W, H> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.inner.write(bytes)?;
+        self.hasher.write(bytes);
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::{fs::File, ptr::copy_nonoverlapping};
+
+use memmap2::{Mmap, MmapMut};
+use rancor::{Error, ResultExt as _};
+
+use crate::ser::{Positional, Writer};
+
+/// The minimum number of bytes the backing file grows by.
+const MIN_GROWTH: usize = 4096;
+
+/// Wraps a [`File`] and equips it with [`Writer`] by writing through a
+/// memory map that grows as bytes are written.
+///
+/// Unlike [`IoWriter`](crate::ser::writer::IoWriter), the archived bytes never
+/// need to be read back from the file: once serialization is finished, the
+/// writer can be turned into a read-only [`Mmap`] of the archive with
+/// [`into_mmap`](MmapWriter::into_mmap). Memory maps are always page-aligned,
+/// so the returned mapping can be passed directly to `access` and
+/// `access_unchecked`.
+///
+/// The file must be opened for both reading and writing. Any existing contents
+/// of the file past the current position will be overwritten.
+///
+/// # Examples
+/// ```
+/// use std::fs::OpenOptions;
+///
+/// use rkyv::{
+///     access_unchecked, rancor::Failure, ser::writer::MmapWriter, serialize,
+/// };
+///
+/// let path = std::env::temp_dir()
+///     .join(format!("rkyv_mmap_writer_example_{}", std::process::id()));
+/// let file = OpenOptions::new()
+///     .read(true)
+///     .write(true)
+///     .create(true)
+///     .truncate(true)
+///     .open(&path)
+///     .unwrap();
+///
+/// let mut writer = MmapWriter::new(file);
+/// serialize::<_, _, Failure>(&"hello world".to_string(), &mut writer)
+///     .unwrap();
+/// let map = writer.into_mmap::<Failure>().unwrap();
+///
+/// let archived = unsafe { access_unchecked::<String>(&map) };
+/// assert_eq!(archived, "hello world");
+/// # drop(map);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MmapWriter {
+    file: File,
+    map: Option<MmapMut>,
+    pos: usize,
+}
+
+impl MmapWriter {
+    /// Creates a new memory-mapped writer from a file.
+    #[inline]
+    pub fn new(file: File) -> Self {
+        Self {
+            file,
+            map: None,
+            pos: 0,
+        }
+    }
+
+    /// Creates a new memory-mapped writer from a file and immediately grows
+    /// the file to the given capacity.
+    pub fn with_capacity<E: Error>(
+        file: File,
+        capacity: usize,
+    ) -> Result<Self, E> {
+        let mut result = Self::new(file);
+        if capacity != 0 {
+            unsafe {
+                result.remap(capacity)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns the number of bytes that can be written before the backing file
+    /// must be grown.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.as_ref().map_or(0, |map| map.len())
+    }
+
+    /// Flushes the bytes written so far to the backing file.
+    pub fn flush<E: Error>(&self) -> Result<(), E> {
+        if let Some(map) = &self.map {
+            map.flush_range(0, self.pos).into_error()?;
+        }
+        Ok(())
+    }
+
+    /// Truncates the backing file to the written length and returns a
+    /// read-only memory map of the archive.
+    ///
+    /// The returned map is page-aligned and contains exactly the bytes that
+    /// were written.
+    pub fn into_mmap<E: Error>(mut self) -> Result<Mmap, E> {
+        if let Some(map) = self.map.take() {
+            map.flush_range(0, self.pos).into_error()?;
+        }
+        self.file.set_len(self.pos as u64).into_error()?;
+        // SAFETY: The file is owned by the writer and is not modified by rkyv
+        // for as long as the returned map is alive.
+        unsafe { Mmap::map(&self.file).into_error() }
+    }
+
+    /// Grows the backing file to the given length and remaps it.
+    ///
+    /// # Safety
+    ///
+    /// `capacity` must be greater than or equal to the current position.
+    unsafe fn remap<E: Error>(&mut self, capacity: usize) -> Result<(), E> {
+        debug_assert!(capacity >= self.pos);
+
+        // Unmap the old region before resizing the file so that no mapping
+        // ever extends past the end of the file.
+        self.map = None;
+        self.file.set_len(capacity as u64).into_error()?;
+        self.map = Some(MmapMut::map_mut(&self.file).into_error()?);
+        Ok(())
+    }
+}
+
+impl Positional for MmapWriter {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<E: Error> Writer<E> for MmapWriter {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        let end_pos = self.pos + bytes.len();
+        let capacity = self.capacity();
+        if end_pos > capacity {
+            let new_capacity = usize::max(
+                end_pos,
+                usize::max(capacity.saturating_mul(2), MIN_GROWTH),
+            );
+            unsafe {
+                self.remap(new_capacity)?;
+            }
+        }
+
+        if let Some(map) = &mut self.map {
+            unsafe {
+                copy_nonoverlapping(
+                    bytes.as_ptr(),
+                    map.as_mut_ptr().add(self.pos),
+                    bytes.len(),
+                );
+            }
+        }
+        self.pos = end_pos;
+        Ok(())
+    }
+}
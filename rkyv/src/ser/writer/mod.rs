@@ -3,15 +3,23 @@
 #[cfg(feature = "alloc")]
 mod alloc;
 mod core;
+#[cfg(feature = "mmap")]
+mod mmap;
 #[cfg(feature = "std")]
 mod std;
+#[cfg(feature = "tokio")]
+mod tokio;
 
 use ::core::{mem, slice};
 use rancor::{Fallible, Strategy};
 
 pub use self::core::*;
+#[cfg(feature = "mmap")]
+pub use self::mmap::*;
 #[cfg(feature = "std")]
 pub use self::std::*;
+#[cfg(feature = "tokio")]
+pub use self::tokio::*;
 use crate::{Archive, ArchiveUnsized, RelPtr};
 
 /// A writer that knows its current position.
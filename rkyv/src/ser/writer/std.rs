@@ -1,8 +1,11 @@
-use std::io;
+use std::io::{self, IoSlice};
 
 use rancor::ResultExt as _;
 
-use crate::ser::{Positional, Writer};
+use crate::{
+    ser::{Positional, Writer},
+    util::ArchiveHeader,
+};
 
 /// Wraps a type that implements [`io::Write`](std::io::Write) and equips it
 /// with [`Writer`].
@@ -62,3 +65,278 @@ impl<W: io::Write, E: rancor::Error> Writer<E> for IoWriter<W> {
         Ok(())
     }
 }
+
+/// Wraps a type that implements [`io::Write`](std::io::Write) and
+/// [`io::Seek`](std::io::Seek) and equips it with [`Writer`]. The archive is
+/// prefixed with an [`ArchiveHeader`] that records the position of the root
+/// object.
+///
+/// When the writer is created, space for the header is reserved at the
+/// current position of the underlying writer. The archive body is then written
+/// after it, and [`finish`](SeekWriter::finish) seeks back to fill in the
+/// header. Because the root position is stored explicitly, the root object
+/// does not need to be located at the end of the archive.
+///
+/// Archives written with a `SeekWriter` can be accessed with
+/// [`access_with_header`](crate::validation::util::access_with_header) or
+/// [`access_with_header_unchecked`](crate::util::access_with_header_unchecked).
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+///
+/// use rkyv::{
+///     rancor::{Failure, Strategy},
+///     ser::writer::SeekWriter,
+///     util::{access_with_header_unchecked, AlignedVec},
+///     Serialize,
+/// };
+///
+/// const TYPE_TAG: u64 = 0x5354_5249_4e47;
+///
+/// let value = "hello world".to_string();
+/// let mut writer =
+///     SeekWriter::<_>::new::<Failure>(Cursor::new(Vec::new())).unwrap();
+/// let root_pos = value
+///     .serialize_and_resolve(Strategy::<_, Failure>::wrap(&mut writer))
+///     .unwrap();
+/// let buf = writer
+///     .finish::<Failure>(root_pos, TYPE_TAG)
+///     .unwrap()
+///     .into_inner();
+///
+/// let mut bytes = AlignedVec::new();
+/// bytes.extend_from_slice(&buf);
+/// let archived =
+///     unsafe { access_with_header_unchecked::<String>(&bytes).unwrap() };
+/// assert_eq!(archived, "hello world");
+/// ```
+#[derive(Debug)]
+pub struct SeekWriter<W> {
+    inner: W,
+    start: u64,
+    pos: usize,
+}
+
+impl<W: io::Write + io::Seek> SeekWriter<W> {
+    /// Creates a new serializer from a seekable writer and reserves space for
+    /// the archive header at the writer's current position.
+    pub fn new<E: rancor::Error>(mut inner: W) -> Result<Self, E> {
+        let start = inner.stream_position().into_error()?;
+        inner.write_all(&[0; ArchiveHeader::SIZE]).into_error()?;
+        Ok(Self {
+            inner,
+            start,
+            pos: ArchiveHeader::SIZE,
+        })
+    }
+
+    /// Writes the archive header with the given root position and type tag,
+    /// then returns the internal writer.
+    ///
+    /// The internal writer is left positioned at the end of the archive.
+    pub fn finish<E: rancor::Error>(
+        mut self,
+        root_pos: usize,
+        type_tag: u64,
+    ) -> Result<W, E> {
+        debug_assert!(root_pos >= ArchiveHeader::SIZE && root_pos < self.pos);
+
+        let header = ArchiveHeader {
+            root_pos: root_pos as u64,
+            len: self.pos as u64,
+            type_tag,
+        };
+        self.inner
+            .seek(io::SeekFrom::Start(self.start))
+            .into_error()?;
+        self.inner.write_all(&header.to_bytes()).into_error()?;
+        self.inner
+            .seek(io::SeekFrom::Start(self.start + self.pos as u64))
+            .into_error()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W> Positional for SeekWriter<W> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<W: io::Write, E: rancor::Error> Writer<E> for SeekWriter<W> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.inner.write_all(bytes).into_error()?;
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
+/// The default minimum size of a write that bypasses the buffer of a
+/// [`VectoredWriter`].
+pub const DEFAULT_VECTORED_THRESHOLD: usize = 8 * 1024;
+
+/// The default maximum number of bytes a [`VectoredWriter`] buffers before
+/// flushing them.
+pub const DEFAULT_VECTORED_MAX_BUFFERED: usize = 64 * 1024;
+
+/// Wraps a type that implements [`io::Write`](std::io::Write) and equips it
+/// with [`Writer`], passing large writes through without copying them.
+///
+/// Small writes (such as archived metadata, relative pointers, and padding)
+/// are collected in an internal buffer. When a write at least as large as the
+/// threshold is made, the buffered bytes and the borrowed slice are written
+/// together with [`write_vectored`](io::Write::write_vectored). This means
+/// that large byte slices, such as those serialized by
+/// [`ArchivedVec::serialize_copy_from_slice`](crate::vec::ArchivedVec::serialize_copy_from_slice)
+/// and [`CopyOptimize`](crate::with::CopyOptimize), are never copied into an
+/// intermediate buffer.
+///
+/// Buffered bytes are not written until the next large write,
+/// [`flush`](VectoredWriter::flush), or
+/// [`into_inner`](VectoredWriter::into_inner), or until the buffer reaches its
+/// [maximum size](VectoredWriter::set_max_buffered).
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked, rancor::Failure, ser::writer::VectoredWriter,
+///     serialize, util::AlignedVec,
+/// };
+///
+/// let value = "a".repeat(10_000);
+///
+/// let mut writer = VectoredWriter::new(Vec::new());
+/// serialize::<_, _, Failure>(&value, &mut writer).unwrap();
+/// let buf = writer.into_inner::<Failure>().unwrap();
+///
+/// let mut bytes = AlignedVec::new();
+/// bytes.extend_from_slice(&buf);
+/// let archived = unsafe { access_unchecked::<String>(&bytes) };
+/// assert_eq!(archived, &value);
+/// ```
+#[derive(Debug)]
+pub struct VectoredWriter<W> {
+    inner: W,
+    buffer: Vec<u8>,
+    pos: usize,
+    threshold: usize,
+    max_buffered: usize,
+}
+
+impl<W> VectoredWriter<W> {
+    /// Creates a new vectored writer with the default threshold.
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Self::with_threshold(inner, DEFAULT_VECTORED_THRESHOLD)
+    }
+
+    /// Creates a new vectored writer where writes of at least `threshold`
+    /// bytes bypass the internal buffer.
+    #[inline]
+    pub fn with_threshold(inner: W, threshold: usize) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            pos: 0,
+            threshold,
+            max_buffered: DEFAULT_VECTORED_MAX_BUFFERED,
+        }
+    }
+
+    /// Returns the number of bytes which have been buffered but not yet
+    /// written.
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the maximum number of bytes which are buffered before they are
+    /// flushed.
+    #[inline]
+    pub fn max_buffered(&self) -> usize {
+        self.max_buffered
+    }
+
+    /// Sets the maximum number of bytes which are buffered before they are
+    /// flushed.
+    ///
+    /// This bounds the memory used by archives made only of small writes. The
+    /// default is [`DEFAULT_VECTORED_MAX_BUFFERED`].
+    #[inline]
+    pub fn set_max_buffered(&mut self, max_buffered: usize) {
+        self.max_buffered = max_buffered;
+    }
+}
+
+impl<W: io::Write> VectoredWriter<W> {
+    /// Writes all buffered bytes to the internal writer.
+    pub fn flush<E: rancor::Error>(&mut self) -> Result<(), E> {
+        write_all_vectored(&mut self.inner, &self.buffer, &[]).into_error()?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Writes all buffered bytes and returns the internal writer.
+    pub fn into_inner<E: rancor::Error>(mut self) -> Result<W, E> {
+        self.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W> Positional for VectoredWriter<W> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<W: io::Write, E: rancor::Error> Writer<E> for VectoredWriter<W> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if bytes.len() >= self.threshold {
+            write_all_vectored(&mut self.inner, &self.buffer, bytes)
+                .into_error()?;
+            self.buffer.clear();
+        } else {
+            self.buffer.extend_from_slice(bytes);
+            if self.buffer.len() >= self.max_buffered {
+                write_all_vectored(&mut self.inner, &self.buffer, &[])
+                    .into_error()?;
+                self.buffer.clear();
+            }
+        }
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
+fn write_all_vectored<W: io::Write>(
+    writer: &mut W,
+    mut head: &[u8],
+    mut tail: &[u8],
+) -> io::Result<()> {
+    while !head.is_empty() || !tail.is_empty() {
+        let result = if head.is_empty() {
+            writer.write(tail)
+        } else if tail.is_empty() {
+            writer.write(head)
+        } else {
+            writer.write_vectored(&[IoSlice::new(head), IoSlice::new(tail)])
+        };
+
+        match result {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) if n < head.len() => head = &head[n..],
+            Ok(n) => {
+                tail = &tail[n - head.len()..];
+                head = &[];
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use core::{fmt, mem};
+
+use ::tokio::{
+    io::{AsyncWrite, AsyncWriteExt as _},
+    sync::mpsc,
+};
+use rancor::{fail, Error, ResultExt as _};
+
+use crate::ser::{Positional, Writer};
+
+/// The default size of the chunks sent to an [`AsyncIoWriter`] by
+/// [`serialize_async`](crate::util::serialize_async).
+pub const DEFAULT_ASYNC_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of filled chunks which may be waiting to be written to an
+/// [`AsyncIoWriter`] before the serializing thread blocks.
+pub const ASYNC_CHUNK_CAPACITY: usize = 2;
+
+/// Wraps a type that implements [`AsyncWrite`] and equips it with [`Writer`].
+///
+/// When used directly as a [`Writer`], bytes written during serialization are
+/// buffered in memory because serialization is synchronous. They are written
+/// to the underlying writer when [`drain`](AsyncIoWriter::drain) is awaited,
+/// which should be done between serializations.
+///
+/// To serialize a value without buffering the entire archive, use
+/// [`serialize_async`](crate::util::serialize_async) instead. It serializes on
+/// a blocking thread and streams [fixed-size
+/// chunks](AsyncIoWriter::set_chunk_size) to the underlying writer as they are
+/// filled, so at most [`ASYNC_CHUNK_CAPACITY`] + 1 chunks are held in memory at
+/// a time.
+///
+/// The position of the writer keeps advancing across drains, so multiple
+/// values can be serialized into the same stream.
+///
+/// # Examples
+/// ```
+/// # tokio::runtime::Builder::new_current_thread()
+/// #     .build()
+/// #     .unwrap()
+/// #     .block_on(async {
+/// use rkyv::{
+///     access_unchecked, rancor::Failure, ser::writer::AsyncIoWriter,
+///     serialize, util::AlignedVec,
+/// };
+///
+/// let mut writer = AsyncIoWriter::new(Vec::new());
+/// serialize::<_, _, Failure>(&"hello world".to_string(), &mut writer)
+///     .unwrap();
+/// writer.drain::<Failure>().await.unwrap();
+/// let buf = writer.into_inner::<Failure>().await.unwrap();
+///
+/// let mut bytes = AlignedVec::new();
+/// bytes.extend_from_slice(&buf);
+/// let archived = unsafe { access_unchecked::<String>(&bytes) };
+/// assert_eq!(archived, "hello world");
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct AsyncIoWriter<W> {
+    inner: W,
+    buffer: Vec<u8>,
+    pos: usize,
+    chunk_size: usize,
+}
+
+impl<W> AsyncIoWriter<W> {
+    /// Creates a new serializer from an async writer.
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Self::with_pos(inner, 0)
+    }
+
+    /// Creates a new serializer from an async writer, and assumes that the
+    /// underlying writer is currently at the given position.
+    #[inline]
+    pub fn with_pos(inner: W, pos: usize) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            pos,
+            chunk_size: DEFAULT_ASYNC_CHUNK_SIZE,
+        }
+    }
+
+    /// Returns the number of bytes which have been buffered but not yet
+    /// drained.
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the size of the chunks streamed to this writer by
+    /// [`serialize_async`](crate::util::serialize_async).
+    #[inline]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Sets the size of the chunks streamed to this writer by
+    /// [`serialize_async`](crate::util::serialize_async).
+    ///
+    /// The default is [`DEFAULT_ASYNC_CHUNK_SIZE`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    #[inline]
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        assert!(chunk_size != 0, "async chunk size must not be zero");
+        self.chunk_size = chunk_size;
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncIoWriter<W> {
+    /// Writes all buffered bytes to the underlying writer.
+    ///
+    /// The capacity of the buffer is retained for future serializations.
+    pub async fn drain<E: Error>(&mut self) -> Result<(), E> {
+        self.inner.write_all(&self.buffer).await.into_error()?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Drains all buffered bytes, flushes the underlying writer, and returns
+    /// it.
+    pub async fn into_inner<E: Error>(mut self) -> Result<W, E> {
+        self.drain().await?;
+        self.inner.flush().await.into_error()?;
+        Ok(self.inner)
+    }
+
+    /// Writes every chunk received from a [`ChunkSender`] to the underlying
+    /// writer until the sender is dropped.
+    ///
+    /// If a write fails, the receiver is closed so that the sender stops
+    /// serializing, and the error is returned once the sender is dropped.
+    pub(crate) async fn write_chunks<E: Error>(
+        &mut self,
+        mut receiver: mpsc::Receiver<Vec<u8>>,
+    ) -> Result<(), E> {
+        let mut result = Ok(());
+        while let Some(chunk) = receiver.recv().await {
+            if result.is_ok() {
+                result = self.inner.write_all(&chunk).await.into_error();
+                if result.is_ok() {
+                    self.pos += chunk.len();
+                } else {
+                    receiver.close();
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<W> Positional for AsyncIoWriter<W> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<W, E> Writer<E> for AsyncIoWriter<W> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.buffer.extend_from_slice(bytes);
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ChunkReceiverClosed;
+
+impl fmt::Display for ChunkReceiverClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "async writer stopped receiving serialized chunks")
+    }
+}
+
+impl std::error::Error for ChunkReceiverClosed {}
+
+/// The [`Writer`] used by [`serialize_async`](crate::util::serialize_async)
+/// on its serializing thread.
+///
+/// Bytes are collected into fixed-size chunks, and each chunk is sent to the
+/// [`AsyncIoWriter`] as soon as it is filled. Sending blocks while the
+/// async writer is behind, which bounds the memory used by serialization.
+#[derive(Debug)]
+pub struct ChunkSender {
+    sender: mpsc::Sender<Vec<u8>>,
+    buffer: Vec<u8>,
+    pos: usize,
+    chunk_size: usize,
+}
+
+impl ChunkSender {
+    pub(crate) fn new(
+        sender: mpsc::Sender<Vec<u8>>,
+        pos: usize,
+        chunk_size: usize,
+    ) -> Self {
+        Self {
+            sender,
+            buffer: Vec::with_capacity(chunk_size),
+            pos,
+            chunk_size,
+        }
+    }
+
+    fn send<E: Error>(&mut self) -> Result<(), E> {
+        let chunk =
+            mem::replace(&mut self.buffer, Vec::with_capacity(self.chunk_size));
+        if self.sender.blocking_send(chunk).is_err() {
+            fail!(ChunkReceiverClosed);
+        }
+        Ok(())
+    }
+
+    /// Sends the last, possibly partial, chunk.
+    pub(crate) fn finish<E: Error>(mut self) -> Result<(), E> {
+        if !self.buffer.is_empty() {
+            self.send()?;
+        }
+        Ok(())
+    }
+}
+
+impl Positional for ChunkSender {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<E: Error> Writer<E> for ChunkSender {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        let mut remaining = bytes;
+        while !remaining.is_empty() {
+            let len = usize::min(
+                self.chunk_size - self.buffer.len(),
+                remaining.len(),
+            );
+            self.buffer.extend_from_slice(&remaining[..len]);
+            remaining = &remaining[len..];
+            if self.buffer.len() == self.chunk_size {
+                self.send()?;
+            }
+        }
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Archived versions of string types.
 
 pub mod repr;
+pub mod symbol;
 
 use core::{
     borrow::Borrow,
@@ -244,13 +245,15 @@ pub struct StringResolver {
 #[cfg(feature = "bytecheck")]
 mod verify {
     use bytecheck::{
-        rancor::{Error, Fallible},
+        rancor::{Error, Fallible, ResultExt as _},
         CheckBytes, Verify,
     };
 
     use crate::{
         string::{repr::ArchivedStringRepr, ArchivedString},
-        validation::{ArchiveContext, ArchiveContextExt},
+        validation::{
+            path::PointeeCheckContext, ArchiveContext, ArchiveContextExt,
+        },
     };
 
     unsafe impl<C> Verify<C> for ArchivedString
@@ -260,6 +263,9 @@ mod verify {
     {
         #[inline]
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let len = self.repr.len();
+            context.check_collection(len, len)?;
+
             if self.repr.is_inline() {
                 unsafe {
                     str::check_bytes(self.repr.as_str_ptr(), context)?;
@@ -267,17 +273,20 @@ mod verify {
             } else {
                 let base = (&self.repr as *const ArchivedStringRepr).cast();
                 let offset = unsafe { self.repr.out_of_line_offset() };
-                let metadata = self.repr.len();
 
                 let ptr = unsafe {
                     context.bounds_check_subtree_base_offset::<str>(
-                        base, offset, metadata,
+                        base, offset, len,
                     )?
                 };
 
                 let range = unsafe { context.push_prefix_subtree(ptr)? };
                 unsafe {
-                    str::check_bytes(ptr, context)?;
+                    str::check_bytes(ptr, context).with_trace(|| {
+                        PointeeCheckContext {
+                            address: ptr as *const u8 as usize,
+                        }
+                    })?;
                 }
                 unsafe {
                     context.pop_subtree_range(range)?;
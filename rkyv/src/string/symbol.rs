@@ -0,0 +1,760 @@
+//! Interned strings.
+//!
+//! Fields archived with [`Intern`](crate::with::Intern) become
+//! [`ArchivedSymbol`]s: relative pointers to the entries of a single string
+//! table which is written once per archive. The string table is written by
+//! [`Interned`], which should wrap the root of the archive.
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use core::{borrow::Borrow, cmp, fmt, hash, ops::Deref, ptr, slice};
+
+use rancor::{Fallible, Strategy};
+
+#[cfg(feature = "alloc")]
+use crate::{
+    ser::{Allocator, Writer, WriterExt as _},
+    string::StringResolver,
+    Serialize,
+};
+use crate::{
+    string::ArchivedString,
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Portable, RelPtr,
+};
+
+/// A string pooling strategy.
+///
+/// String pools keep track of the entries of the string table which is being
+/// written. Each entry of the table is an [`ArchivedString`], and
+/// [`ArchivedSymbol`]s point to the entries of the table. This trait is
+/// required to serialize fields with [`Intern`](crate::with::Intern).
+///
+/// Strings can only be pooled between calls to
+/// [`start_string_table`](StringPool::start_string_table) and
+/// [`finish_string_table`](StringPool::finish_string_table), which are made by
+/// [`Interned`].
+#[cfg(feature = "alloc")]
+pub trait StringPool<E = <Self as Fallible>::Error> {
+    /// Starts pooling the strings of a new string table.
+    ///
+    /// Returns an error if a string table is already being pooled. The strings
+    /// of that string table are kept.
+    fn start_string_table(&mut self) -> Result<(), E>;
+
+    /// Gets the position of the string table entry for a string.
+    ///
+    /// Returns `None` if the string has not yet been added, and an error if no
+    /// string table is being pooled.
+    fn get_pooled_str(&self, value: &str) -> Result<Option<usize>, E>;
+
+    /// Adds the position of the string table entry for a string.
+    ///
+    /// Returns an error if no string table is being pooled.
+    fn add_pooled_str(&mut self, value: &str, pos: usize) -> Result<(), E>;
+
+    /// Finishes pooling the strings of the current string table, and returns
+    /// the positions of its entries in the order they were added.
+    ///
+    /// Returns an error if no string table is being pooled.
+    fn finish_string_table(&mut self) -> Result<Vec<usize>, E>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T, E> StringPool<E> for Strategy<T, E>
+where
+    T: StringPool<E> + ?Sized,
+{
+    #[inline]
+    fn start_string_table(&mut self) -> Result<(), E> {
+        T::start_string_table(self)
+    }
+
+    #[inline]
+    fn get_pooled_str(&self, value: &str) -> Result<Option<usize>, E> {
+        T::get_pooled_str(self, value)
+    }
+
+    #[inline]
+    fn add_pooled_str(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        T::add_pooled_str(self, value, pos)
+    }
+
+    #[inline]
+    fn finish_string_table(&mut self) -> Result<Vec<usize>, E> {
+        T::finish_string_table(self)
+    }
+}
+
+/// Helper methods for [`StringPool`].
+#[cfg(feature = "alloc")]
+pub trait StringPoolExt<E>: StringPool<E> {
+    /// Writes the string table entry for the given string and returns its
+    /// position. If the string has already been added then it returns the
+    /// position of the previously added entry.
+    fn serialize_pooled_str(
+        &mut self,
+        value: &str,
+    ) -> Result<usize, <Self as Fallible>::Error>
+    where
+        Self: Fallible<Error = E> + Writer<E>,
+    {
+        if let Some(pos) = self.get_pooled_str(value)? {
+            return Ok(pos);
+        }
+
+        let resolver = ArchivedString::serialize_from_str(value, self)?;
+        let pos = self.align_for::<ArchivedString>()?;
+        unsafe {
+            self.resolve_aligned(&PooledStr(value), resolver)?;
+        }
+
+        self.add_pooled_str(value, pos)?;
+        Ok(pos)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S, E> StringPoolExt<E> for S where S: StringPool<E> + ?Sized {}
+
+/// A string which is written as an entry of the string table.
+#[cfg(feature = "alloc")]
+struct PooledStr<'a>(&'a str);
+
+#[cfg(feature = "alloc")]
+impl Archive for PooledStr<'_> {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve(
+        &self,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedString::resolve_from_str(self.0, pos, resolver, out);
+    }
+}
+
+/// An archived interned string.
+///
+/// This is a [`RelPtr`] to an entry in the string table of the archive. Each
+/// distinct string is written to the table once, and every symbol with the
+/// same contents points to the same entry. Symbols are produced by serializing
+/// a field with [`Intern`](crate::with::Intern), and their entries are listed
+/// in the [`ArchivedInterned`] which encloses them.
+///
+/// During validation, every symbol must point to the start of an entry of the
+/// string table of the enclosing `ArchivedInterned`. Each entry is only checked
+/// the first time a symbol pointing to it is checked.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(transparent)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+pub struct ArchivedSymbol {
+    ptr: RelPtr<ArchivedString>,
+}
+
+impl ArchivedSymbol {
+    /// Gets the string table entry of the symbol.
+    #[inline]
+    pub fn entry(&self) -> &ArchivedString {
+        unsafe { &*self.ptr.as_ptr() }
+    }
+
+    /// Extracts a string slice containing the entire symbol.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.entry().as_str()
+    }
+
+    /// Returns whether two symbols point to the same string table entry.
+    ///
+    /// Symbols from the same string table point to the same entry if and only
+    /// if they have the same contents.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        ptr::eq(self.entry(), other.entry())
+    }
+
+    /// Resolves an archived symbol from the given resolver.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of
+    ///   [`serialize_from_str`](ArchivedSymbol::serialize_from_str)
+    #[inline]
+    pub unsafe fn emplace(
+        pos: usize,
+        resolver: SymbolResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.ptr);
+        RelPtr::emplace(pos + fp, resolver.pos, fo);
+    }
+
+    /// Serializes an archived symbol from a given `str`.
+    ///
+    /// Returns an error if the symbol is not being serialized inside of an
+    /// [`Interned`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn serialize_from_str<S>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<SymbolResolver, S::Error>
+    where
+        S: Fallible + Writer + StringPool + ?Sized,
+    {
+        Ok(SymbolResolver {
+            pos: serializer.serialize_pooled_str(value)?,
+        })
+    }
+}
+
+impl AsRef<str> for ArchivedSymbol {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for ArchivedSymbol {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for ArchivedSymbol {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Deref for ArchivedSymbol {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for ArchivedSymbol {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Eq for ArchivedSymbol {}
+
+impl hash::Hash for ArchivedSymbol {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl Ord for ArchivedSymbol {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialEq for ArchivedSymbol {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.as_str() == other.as_str()
+    }
+}
+
+impl PartialOrd for ArchivedSymbol {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq<&str> for ArchivedSymbol {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        PartialEq::eq(self.as_str(), *other)
+    }
+}
+
+impl PartialEq<str> for ArchivedSymbol {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        PartialEq::eq(self.as_str(), other)
+    }
+}
+
+impl PartialEq<ArchivedSymbol> for &str {
+    #[inline]
+    fn eq(&self, other: &ArchivedSymbol) -> bool {
+        PartialEq::eq(other.as_str(), *self)
+    }
+}
+
+impl PartialEq<ArchivedSymbol> for str {
+    #[inline]
+    fn eq(&self, other: &ArchivedSymbol) -> bool {
+        PartialEq::eq(other.as_str(), self)
+    }
+}
+
+/// The resolver for `ArchivedSymbol`.
+pub struct SymbolResolver {
+    pos: usize,
+}
+
+/// An entry of the string table which is being written.
+#[cfg(feature = "alloc")]
+struct TableEntry {
+    pos: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl Archive for TableEntry {
+    type Archived = ArchivedSymbol;
+    type Resolver = SymbolResolver;
+
+    #[inline]
+    unsafe fn resolve(
+        &self,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSymbol::emplace(pos, resolver, out);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: Fallible + ?Sized> Serialize<S> for TableEntry {
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(SymbolResolver { pos: self.pos })
+    }
+}
+
+/// The archived string table which [`ArchivedSymbol`]s point into.
+///
+/// The table holds a symbol for each of its entries, in the order they were
+/// written to the archive.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(transparent)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ArchivedStringTable {
+    entries: ArchivedVec<ArchivedSymbol>,
+}
+
+impl ArchivedStringTable {
+    /// Returns the number of strings in the string table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the string table is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the string at the given index, or `None` if the index is out of
+    /// bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(ArchivedSymbol::as_str)
+    }
+
+    /// Returns an iterator over the strings in the string table, in the order
+    /// they were written.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+}
+
+impl fmt::Debug for ArchivedStringTable {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the strings of an [`ArchivedStringTable`].
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, ArchivedSymbol>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(ArchivedSymbol::as_str)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// A value archived along with the string table for the symbols inside of it.
+///
+/// Strings interned while the value is serialized are written once each, and
+/// the serializer's [`StringPool`] collects their entries into a single string
+/// table which is written after the value. Wrap the root of an archive in
+/// `Interned` so that the string table is written once per archive.
+///
+/// Serializing a field with [`Intern`](crate::with::Intern) outside of an
+/// `Interned` fails, as does serializing an `Interned` inside of another one.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access,
+///     from_bytes,
+///     rancor::Failure,
+///     string::symbol::Interned,
+///     to_bytes,
+///     with::Intern,
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// #[archive(check_bytes)]
+/// struct Token {
+///     #[with(Intern)]
+///     ident: String,
+/// }
+///
+/// let tokens = Interned(
+///     ["x", "y", "x"]
+///         .iter()
+///         .map(|ident| Token {
+///             ident: ident.to_string(),
+///         })
+///         .collect::<Vec<_>>(),
+/// );
+///
+/// let bytes = to_bytes::<_, 256, Failure>(&tokens).unwrap();
+/// let archived = access::<Interned<Vec<Token>>, Failure>(&bytes).unwrap();
+/// assert_eq!(archived.strings().len(), 2);
+/// assert_eq!(archived.value()[2].ident, "x");
+///
+/// let deserialized =
+///     from_bytes::<Interned<Vec<Token>>, Failure>(&bytes).unwrap();
+/// assert_eq!(deserialized, tokens);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interned<T>(pub T);
+
+/// An archived [`Interned`].
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+pub struct ArchivedInterned<T> {
+    strings: ArchivedStringTable,
+    value: T,
+}
+
+impl<T> ArchivedInterned<T> {
+    /// Returns the string table of the value.
+    #[inline]
+    pub fn strings(&self) -> &ArchivedStringTable {
+        &self.strings
+    }
+
+    /// Returns the archived value.
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedInterned<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedInterned")
+            .field("strings", &self.strings)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// The resolver for [`Interned`].
+pub struct InternedResolver<R> {
+    strings: VecResolver,
+    len: usize,
+    value: R,
+}
+
+impl<T: Archive> Archive for Interned<T> {
+    type Archived = ArchivedInterned<T::Archived>;
+    type Resolver = InternedResolver<T::Resolver>;
+
+    #[inline]
+    unsafe fn resolve(
+        &self,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        let (fp, fo) = out_field!(out.strings);
+        let (sp, so) = out_field!(fo.entries);
+        ArchivedVec::resolve_from_len(
+            resolver.len,
+            pos + fp + sp,
+            resolver.strings,
+            so,
+        );
+        let (fp, fo) = out_field!(out.value);
+        self.0.resolve(pos + fp, resolver.value, fo);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, S> Serialize<S> for Interned<T>
+where
+    T: Serialize<S>,
+    S: Fallible + Writer + Allocator + StringPool + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        serializer.start_string_table()?;
+        let value = self.0.serialize(serializer)?;
+        let entries = serializer.finish_string_table()?;
+
+        let strings = ArchivedVec::<ArchivedSymbol>::serialize_from_iter::<
+            TableEntry,
+            _,
+            _,
+        >(
+            entries.iter().map(|&pos| TableEntry { pos }),
+            serializer,
+        )?;
+
+        Ok(InternedResolver {
+            strings,
+            len: entries.len(),
+            value,
+        })
+    }
+}
+
+impl<T, D> Deserialize<Interned<T>, D> for ArchivedInterned<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<Interned<T>, D::Error> {
+        Ok(Interned(self.value.deserialize(deserializer)?))
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{
+        any::TypeId,
+        fmt,
+        mem::size_of,
+        ops::Range,
+        ptr::{addr_of, slice_from_raw_parts},
+    };
+
+    use bytecheck::{
+        rancor::{fail, Error, Fallible, ResultExt as _},
+        CheckBytes, StructCheckContext, Verify,
+    };
+
+    use super::{ArchivedInterned, ArchivedStringTable, ArchivedSymbol};
+    use crate::{
+        string::ArchivedString,
+        validation::{
+            path::PointeeCheckContext, ArchiveContext, ArchiveContextExt,
+            SharedContext, SharedPointerKind,
+        },
+    };
+
+    #[derive(Debug)]
+    struct InvalidSymbol {
+        address: usize,
+        table: Option<Range<usize>>,
+    }
+
+    impl fmt::Display for InvalidSymbol {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.table {
+                Some(table) => write!(
+                    f,
+                    "symbol pointer {:#x} does not point to an entry of the string \
+                     table at {:#x}..{:#x}",
+                    self.address, table.start, table.end,
+                ),
+                None => write!(
+                    f,
+                    "symbol pointer {:#x} is not inside of a string table",
+                    self.address,
+                ),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for InvalidSymbol {}
+
+    unsafe impl<C> Verify<C> for ArchivedSymbol
+    where
+        C: Fallible + ArchiveContext + SharedContext + ?Sized,
+        C::Error: Error,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let ptr = self.ptr.as_ptr_wrapping();
+            let address = ptr as usize;
+
+            let table = match context.string_table() {
+                Some(table) => table,
+                None => fail!(InvalidSymbol {
+                    address,
+                    table: None,
+                }),
+            };
+            // SAFETY: The enclosing `ArchivedInterned` checked that the string
+            // table is in bounds and aligned, and every bit pattern of an
+            // `ArchivedSymbol` is valid.
+            let entries = unsafe {
+                &*slice_from_raw_parts(
+                    table.start as *const ArchivedSymbol,
+                    (table.end - table.start) / size_of::<ArchivedSymbol>(),
+                )
+            };
+            // Entries are written in increasing order of position
+            if entries
+                .binary_search_by_key(&address, |entry| {
+                    entry.ptr.as_ptr_wrapping() as usize
+                })
+                .is_err()
+            {
+                fail!(InvalidSymbol {
+                    address,
+                    table: Some(table),
+                });
+            }
+
+            // Entries of the string table are registered as shared values, so
+            // each entry is only checked once and no entry may overlap another.
+            if context.register_shared_value(
+                address,
+                size_of::<ArchivedString>(),
+                TypeId::of::<ArchivedSymbol>(),
+                SharedPointerKind::Strong,
+            )? {
+                unsafe {
+                    context.bounds_check_subtree_rel_ptr(&self.ptr)?;
+                }
+
+                let range = unsafe { context.push_prefix_subtree(ptr)? };
+                unsafe {
+                    ArchivedString::check_bytes(ptr, context)
+                        .with_trace(|| PointeeCheckContext { address })?;
+                }
+                unsafe {
+                    context.pop_subtree_range(range)?;
+                }
+                context.finish_shared_value(address)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    unsafe impl<T, C> CheckBytes<C> for ArchivedInterned<T>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + SharedContext + ?Sized,
+        C::Error: Error,
+    {
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            // Symbols in the value are checked against the entries of the
+            // string table, so the entries are bounds checked first. Every bit
+            // pattern of the pointer and length of the table is valid, so they
+            // can be read before the table is checked.
+            let strings = unsafe { addr_of!((*value).strings) };
+            let len = unsafe { (*strings).len() };
+            let entries =
+                unsafe { (*strings).entries.bounds_check_elements(context)? };
+            let start = entries.cast::<ArchivedSymbol>() as usize;
+            let end = start + len * size_of::<ArchivedSymbol>();
+
+            let previous =
+                unsafe { context.set_string_table(Some(start..end)) };
+            let result = unsafe { check_fields(value, strings, context) };
+            unsafe {
+                context.set_string_table(previous);
+            }
+            result
+        }
+    }
+
+    // The value is checked before the string table because its out-of-line
+    // data, including the entries of the table, is written first.
+    unsafe fn check_fields<T, C>(
+        value: *const ArchivedInterned<T>,
+        strings: *const ArchivedStringTable,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + SharedContext + ?Sized,
+        C::Error: Error,
+    {
+        unsafe {
+            T::check_bytes(addr_of!((*value).value), context).with_trace(
+                || StructCheckContext {
+                    struct_name: "ArchivedInterned",
+                    field_name: "value",
+                },
+            )?;
+        }
+        unsafe {
+            ArchivedStringTable::check_bytes(strings, context).with_trace(
+                || StructCheckContext {
+                    struct_name: "ArchivedInterned",
+                    field_name: "strings",
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
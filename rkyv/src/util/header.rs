@@ -0,0 +1,75 @@
+/// A fixed-size header that records where the root of an archive is located.
+///
+/// Archives written with a header do not need to place their root object at
+/// the end of the buffer. Instead, the header at the start of the buffer
+/// stores the position of the root object, the total length of the archive
+/// (including the header), and a user-defined type tag that can be used to
+/// identify the type of the root object.
+///
+/// The header is always encoded in little-endian byte order and is exactly
+/// [`SIZE`](ArchiveHeader::SIZE) bytes long. Its size is a multiple of 16 so
+/// that the body of the archive remains aligned.
+///
+/// # Examples
+/// ```
+/// use rkyv::util::ArchiveHeader;
+///
+/// let header = ArchiveHeader {
+///     root_pos: 64,
+///     len: 72,
+///     type_tag: 0x1234,
+/// };
+/// let bytes = header.to_bytes();
+/// assert_eq!(ArchiveHeader::from_bytes(&bytes), Some(header));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ArchiveHeader {
+    /// The position of the root object, relative to the start of the header.
+    pub root_pos: u64,
+    /// The length of the archive in bytes, including the header.
+    pub len: u64,
+    /// A user-defined tag identifying the type of the root object.
+    pub type_tag: u64,
+}
+
+impl ArchiveHeader {
+    /// The size of an encoded header in bytes.
+    pub const SIZE: usize = 32;
+
+    /// The magic bytes at the start of every encoded header.
+    pub const MAGIC: [u8; 4] = *b"rkyv";
+
+    /// Encodes the header into bytes.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut result = [0; Self::SIZE];
+        result[0..4].copy_from_slice(&Self::MAGIC);
+        // Bytes 4..8 are reserved and always zero
+        result[8..16].copy_from_slice(&self.root_pos.to_le_bytes());
+        result[16..24].copy_from_slice(&self.len.to_le_bytes());
+        result[24..32].copy_from_slice(&self.type_tag.to_le_bytes());
+        result
+    }
+
+    /// Decodes a header from the start of the given bytes.
+    ///
+    /// Returns `None` if there are not enough bytes for a header or the magic
+    /// bytes do not match.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(0..Self::SIZE)?;
+        if bytes[0..4] != Self::MAGIC {
+            return None;
+        }
+
+        let read_u64 = |start: usize| {
+            let mut buf = [0; 8];
+            buf.copy_from_slice(&bytes[start..start + 8]);
+            u64::from_le_bytes(buf)
+        };
+
+        Some(Self {
+            root_pos: read_u64(8),
+            len: read_u64(16),
+            type_tag: read_u64(24),
+        })
+    }
+}
@@ -12,6 +12,7 @@
 
 #[cfg(feature = "alloc")]
 mod aligned_vec;
+mod header;
 mod scratch_vec;
 
 use core::{
@@ -26,10 +27,22 @@ use rancor::Strategy;
 #[cfg(feature = "alloc")]
 pub use self::aligned_vec::*;
 #[doc(inline)]
+pub use self::header::*;
+#[doc(inline)]
 pub use self::scratch_vec::*;
+#[cfg(feature = "tokio")]
+use crate::ser::writer::{AsyncIoWriter, ChunkSender};
 use crate::Portable;
 #[cfg(feature = "alloc")]
-use crate::{de::pooling::Unify, ser::AllocSerializer};
+use crate::{
+    de::pooling::Unify,
+    ser::{
+        allocator::{AllocationTracker, GlobalAllocator},
+        sharing,
+        writer::SizeCounter,
+        AllocSerializer, Composite, Positional as _, SerializerArena,
+    },
+};
 use crate::{
     ser::Writer, Archive, ArchiveUnsized, Deserialize, RelPtr, Serialize,
     SerializeUnsized,
@@ -234,6 +247,24 @@ pub unsafe fn access_unsized_unchecked_mut<T: ArchiveUnsized + ?Sized>(
     access_pos_unsized_unchecked_mut::<T>(bytes, pos)
 }
 
+/// Accesses an archived value from the given byte slice by reading the root
+/// position from an [`ArchiveHeader`] at the start of the slice.
+///
+/// Returns `None` if the slice does not begin with a header.
+///
+/// # Safety
+///
+/// - The byte slice must represent an archived object with a header, such as
+///   one written by [`SeekWriter`](crate::ser::writer::SeekWriter).
+/// - The header must record the correct root position and length.
+#[inline]
+pub unsafe fn access_with_header_unchecked<T: Archive + ?Sized>(
+    bytes: &[u8],
+) -> Option<&T::Archived> {
+    let header = ArchiveHeader::from_bytes(bytes)?;
+    Some(access_pos_unchecked::<T>(bytes, header.root_pos as usize))
+}
+
 /// A buffer of bytes aligned to 16 bytes.
 ///
 /// # Examples
@@ -323,6 +354,98 @@ where
     Ok(serialize_into(value, Default::default())?.into_writer())
 }
 
+/// The size of a value's archive, as computed by [`archived_size`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArchivedSize {
+    /// The exact length of the archive in bytes.
+    pub len: usize,
+    /// The maximum number of scratch bytes that were concurrently allocated
+    /// during serialization.
+    pub max_scratch: usize,
+    /// The minimum size of a scratch buffer which could serialize the value
+    /// without falling back to the global allocator.
+    ///
+    /// See [`AllocationTracker::min_buffer_size`] for details on how this is
+    /// calculated.
+    pub min_scratch_buffer: usize,
+}
+
+/// A serializer which computes the size of an archive without writing it.
+#[cfg(feature = "alloc")]
+pub type SizeSerializer =
+    Composite<SizeCounter, AllocationTracker<GlobalAllocator>, sharing::Unify>;
+
+/// Computes the exact length of the archive of the given value, along with the
+/// amount of scratch space needed to serialize it.
+///
+/// No output buffer is allocated: the value is serialized with a
+/// [`SizeCounter`], which only advances its position. Shared pointers are
+/// unified the same way that [`to_bytes`] unifies them, so the returned length
+/// is exactly the length of the bytes `to_bytes` would return.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Failure, to_bytes, util::archived_size};
+///
+/// let value = vec!["hello".to_string(), "world".to_string()];
+///
+/// let size = archived_size::<_, Failure>(&value).unwrap();
+/// let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+/// assert_eq!(size.len, bytes.len());
+/// assert!(size.max_scratch > 0);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn archived_size<T, E>(value: &T) -> Result<ArchivedSize, E>
+where
+    T: Serialize<Strategy<SizeSerializer, E>>,
+{
+    let serializer = serialize_into(
+        value,
+        Composite::new(
+            SizeCounter::new(),
+            AllocationTracker::new(GlobalAllocator::new()),
+            sharing::Unify::new(),
+        ),
+    )?;
+    Ok(ArchivedSize {
+        len: serializer.writer.pos(),
+        max_scratch: serializer.allocator.max_bytes_allocated(),
+        min_scratch_buffer: serializer.allocator.min_buffer_size(),
+    })
+}
+
+/// Serializes the given value using the storage of the given arena and returns
+/// the resulting bytes.
+///
+/// The arena is cleared before serializing, so the bytes from any previous
+/// serialization are overwritten. The returned bytes are aligned the same way
+/// that the bytes returned from [`to_bytes`] are.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Failure, ser::SerializerArena, util::to_bytes_in};
+///
+/// let mut arena = SerializerArena::<256>::new();
+/// let value = vec![1, 2, 3, 4];
+///
+/// let bytes = to_bytes_in::<_, 256, Failure>(&value, &mut arena).unwrap();
+/// let deserialized = rkyv::from_bytes::<Vec<i32>, Failure>(bytes).unwrap();
+/// assert_eq!(deserialized, value);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn to_bytes_in<'a, T, const N: usize, E>(
+    value: &T,
+    arena: &'a mut SerializerArena<N>,
+) -> Result<&'a [u8], E>
+where
+    T: Serialize<Strategy<AllocSerializer<N>, E>>,
+{
+    serialize(value, arena.serializer())?;
+    Ok(arena.bytes())
+}
+
 /// Serializes the given value into the given serializer and then returns the
 /// serializer.
 #[inline]
@@ -361,6 +484,91 @@ where
     Ok(())
 }
 
+/// Serializes the given value into the given serializer while streaming the
+/// serialized bytes to its async writer.
+///
+/// Serialization itself is synchronous, so it runs on a blocking thread. The
+/// bytes are split into [chunks](crate::ser::writer::AsyncIoWriter::chunk_size)
+/// which are written to the
+/// [`AsyncIoWriter`](crate::ser::writer::AsyncIoWriter) as soon as they are
+/// filled. The serializing thread waits whenever
+/// [`ASYNC_CHUNK_CAPACITY`](crate::ser::writer::ASYNC_CHUNK_CAPACITY) chunks
+/// are waiting to be written, so the memory used does not grow with the size of
+/// the archive.
+///
+/// Because the value and serializer are moved to another thread, they are
+/// taken by value. The serializer is returned so that more values can be
+/// serialized into the same stream.
+///
+/// # Examples
+/// ```
+/// # tokio::runtime::Builder::new_current_thread()
+/// #     .build()
+/// #     .unwrap()
+/// #     .block_on(async {
+/// use rkyv::{
+///     rancor::Failure,
+///     ser::{
+///         allocator::GlobalAllocator, sharing::Unify, writer::AsyncIoWriter,
+///         Composite, Positional as _,
+///     },
+///     util::serialize_async,
+/// };
+///
+/// let serializer = Composite::new(
+///     AsyncIoWriter::new(Vec::new()),
+///     GlobalAllocator::new(),
+///     Unify::new(),
+/// );
+/// let serializer =
+///     serialize_async::<_, _, _, _, Failure>(vec![1, 2, 3], serializer)
+///         .await
+///         .unwrap();
+/// assert_eq!(serializer.writer.buffered(), 0);
+/// assert_ne!(serializer.pos(), 0);
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn serialize_async<T, W, A, S, E>(
+    value: T,
+    serializer: Composite<AsyncIoWriter<W>, A, S>,
+) -> Result<Composite<AsyncIoWriter<W>, A, S>, E>
+where
+    T: Serialize<Strategy<Composite<ChunkSender, A, S>, E>> + Send + 'static,
+    W: tokio::io::AsyncWrite + Unpin,
+    A: Send + 'static,
+    S: Send + 'static,
+    E: rancor::Error,
+{
+    use rancor::ResultExt as _;
+    use tokio::{sync::mpsc, task};
+
+    use crate::ser::writer::ASYNC_CHUNK_CAPACITY;
+
+    let (mut writer, allocator, share) = serializer.into_raw_parts();
+    writer.drain().await?;
+
+    let (sender, receiver) = mpsc::channel(ASYNC_CHUNK_CAPACITY);
+    let sender = ChunkSender::new(sender, writer.pos(), writer.chunk_size());
+    let task = task::spawn_blocking(move || {
+        let mut serializer = Composite::new(sender, allocator, share);
+        let result = serialize(&value, &mut serializer);
+        let (sender, allocator, share) = serializer.into_raw_parts();
+        (result.and_then(|()| sender.finish()), allocator, share)
+    });
+
+    let write_result = writer.write_chunks(receiver).await;
+    let (result, allocator, share) = match task.await {
+        Ok(parts) => parts,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => return Err(e).into_error(),
+    };
+    write_result?;
+    result?;
+
+    Ok(Composite::new(writer, allocator, share))
+}
+
 /// Serializes a [`RelPtr`] to the given unsized value into the given
 /// serializer.
 #[inline]
@@ -1,6 +1,8 @@
 //! Validation implementations and helper types.
 
+pub mod path;
 pub mod util;
+mod validated;
 pub mod validators;
 
 use core::{alloc::Layout, alloc::LayoutError, any::TypeId, ops::Range};
@@ -9,6 +11,7 @@ use bytecheck::rancor::{Error, Fallible, Strategy};
 use ptr_meta::Pointee;
 use rancor::ResultExt as _;
 
+pub use self::validated::*;
 use crate::{ArchivePointee, RelPtr};
 
 // Replace this trait with core::mem::{align_of_val_raw, size_of_val_raw} when
@@ -24,6 +27,16 @@ where
     fn layout_raw(
         metadata: <Self as Pointee>::Metadata,
     ) -> Result<Layout, LayoutError>;
+
+    /// Gets the number of elements in the type if it is a collection.
+    ///
+    /// Validators use this to check unsized pointees against their collection
+    /// limits. By default, types are not collections.
+    #[inline]
+    fn collection_len(metadata: <Self as Pointee>::Metadata) -> Option<usize> {
+        let _ = metadata;
+        None
+    }
 }
 
 impl<T> LayoutRaw for T {
@@ -42,6 +55,11 @@ impl<T> LayoutRaw for [T] {
     ) -> Result<Layout, LayoutError> {
         Layout::array::<T>(metadata)
     }
+
+    #[inline]
+    fn collection_len(metadata: <Self as Pointee>::Metadata) -> Option<usize> {
+        Some(metadata)
+    }
 }
 
 impl LayoutRaw for str {
@@ -51,6 +69,11 @@ impl LayoutRaw for str {
     ) -> Result<Layout, LayoutError> {
         Layout::array::<u8>(metadata)
     }
+
+    #[inline]
+    fn collection_len(metadata: <Self as Pointee>::Metadata) -> Option<usize> {
+        Some(metadata)
+    }
 }
 
 #[cfg(feature = "std")]
@@ -61,6 +84,11 @@ impl LayoutRaw for ::std::ffi::CStr {
     ) -> Result<Layout, LayoutError> {
         Layout::array::<::std::os::raw::c_char>(metadata)
     }
+
+    #[inline]
+    fn collection_len(metadata: <Self as Pointee>::Metadata) -> Option<usize> {
+        Some(metadata)
+    }
 }
 
 /// A context that can validate nonlocal archive memory.
@@ -125,6 +153,84 @@ pub unsafe trait ArchiveContext<E = <Self as Fallible>::Error> {
         &mut self,
         range: Range<usize>,
     ) -> Result<(), E>;
+
+    /// Checks that a collection with `len` elements occupying `size` bytes of
+    /// the archive is within the resource limits of the validator.
+    ///
+    /// Collections call this while they are validated so that the validator
+    /// can bound how much memory deserializing an archive would allocate.
+    /// Validators estimate that from the archived size, since the native types
+    /// of the elements aren't known. By default, collections are not limited.
+    #[inline]
+    fn check_collection(&mut self, len: usize, size: usize) -> Result<(), E> {
+        let _ = (len, size);
+        Ok(())
+    }
+
+    /// Checks the `len` elements of a collection by calling `check` with
+    /// ranges of element indices.
+    ///
+    /// `check` must check the elements in its range in order, as if they were
+    /// the only elements in the collection. Contexts may split the elements
+    /// into multiple ranges and check them concurrently as long as the result
+    /// is the same as checking all of the elements in order. By default, all
+    /// of the elements are checked with a single call to `check`.
+    #[inline]
+    fn check_elements(
+        &mut self,
+        len: usize,
+        check: &(dyn Fn(&mut Self, Range<usize>) -> Result<(), E> + Sync),
+    ) -> Result<(), E> {
+        check(self, 0..len)
+    }
+
+    /// Checks a single element of a collection while its elements are being
+    /// checked with [`check_elements`](ArchiveContext::check_elements).
+    ///
+    /// Collections which check several elements with each call to `check`
+    /// call this for each of those elements. Contexts may record the error of
+    /// an invalid element and return `Ok` so that the remaining elements are
+    /// checked, as long as the enclosing call to `check_elements` still
+    /// returns an error. By default, this calls `check`.
+    #[inline]
+    fn check_element(
+        &mut self,
+        check: &mut dyn FnMut(&mut Self) -> Result<(), E>,
+    ) -> Result<(), E> {
+        check(self)
+    }
+
+    /// Returns the address range of the entries of the string table which
+    /// [`ArchivedSymbol`]s are currently checked against.
+    ///
+    /// By default, there is no string table and every symbol is invalid.
+    ///
+    /// [`ArchivedSymbol`]: crate::string::symbol::ArchivedSymbol
+    #[inline]
+    fn string_table(&self) -> Option<Range<usize>> {
+        None
+    }
+
+    /// Sets the address range of the entries of the string table which symbols
+    /// are checked against, and returns the previous range.
+    ///
+    /// [`ArchivedInterned`] sets this while it is checked. By default, this
+    /// does nothing.
+    ///
+    /// # Safety
+    ///
+    /// `table` must be the address range of a slice of `ArchivedSymbol`s which
+    /// is in bounds and aligned.
+    ///
+    /// [`ArchivedInterned`]: crate::string::symbol::ArchivedInterned
+    #[inline]
+    unsafe fn set_string_table(
+        &mut self,
+        table: Option<Range<usize>>,
+    ) -> Option<Range<usize>> {
+        let _ = table;
+        None
+    }
 }
 
 unsafe impl<T, E> ArchiveContext<E> for Strategy<T, E>
@@ -161,6 +267,43 @@ where
     ) -> Result<(), E> {
         T::pop_subtree_range(self, range)
     }
+
+    #[inline]
+    fn check_collection(&mut self, len: usize, size: usize) -> Result<(), E> {
+        T::check_collection(self, len, size)
+    }
+
+    fn check_elements(
+        &mut self,
+        len: usize,
+        check: &(dyn Fn(&mut Self, Range<usize>) -> Result<(), E> + Sync),
+    ) -> Result<(), E> {
+        T::check_elements(self, len, &|context: &mut T, range| {
+            check(Strategy::wrap(context), range)
+        })
+    }
+
+    fn check_element(
+        &mut self,
+        check: &mut dyn FnMut(&mut Self) -> Result<(), E>,
+    ) -> Result<(), E> {
+        T::check_element(self, &mut |context: &mut T| {
+            check(Strategy::wrap(context))
+        })
+    }
+
+    #[inline]
+    fn string_table(&self) -> Option<Range<usize>> {
+        T::string_table(self)
+    }
+
+    #[inline]
+    unsafe fn set_string_table(
+        &mut self,
+        table: Option<Range<usize>>,
+    ) -> Option<Range<usize>> {
+        T::set_string_table(self, table)
+    }
 }
 
 /// Helper methods for `ArchiveContext`s.
@@ -205,6 +348,13 @@ pub trait ArchiveContextExt<E>: ArchiveContext<E> {
         &mut self,
         root: *const T,
     ) -> Result<Range<usize>, E>;
+
+    /// Checks the pointee of a subtree pointer against the collection limits
+    /// of the validator if it is a collection.
+    fn check_pointee_collection<T: LayoutRaw + ?Sized>(
+        &mut self,
+        ptr: *const T,
+    ) -> Result<(), E>;
 }
 
 impl<C: ArchiveContext<E> + ?Sized, E: Error> ArchiveContextExt<E> for C {
@@ -268,6 +418,60 @@ impl<C: ArchiveContext<E> + ?Sized, E: Error> ArchiveContextExt<E> for C {
             (root as *const u8).add(layout.size()),
         )
     }
+
+    /// Checks the pointee of a subtree pointer against the collection limits
+    /// of the validator if it is a collection.
+    #[inline]
+    fn check_pointee_collection<T: LayoutRaw + ?Sized>(
+        &mut self,
+        ptr: *const T,
+    ) -> Result<(), E> {
+        let metadata = ptr_meta::metadata(ptr);
+        if let Some(len) = T::collection_len(metadata) {
+            let layout = T::layout_raw(metadata).into_error()?;
+            self.check_collection(len, layout.size())?;
+        }
+        Ok(())
+    }
+}
+
+/// An archived struct whose fields can be checked one at a time.
+///
+/// This is implemented by `#[derive(Archive)]` for structs with named fields
+/// when `#[archive(check_bytes)]` is used. It lets
+/// [`check_fields_with_report`](util::check_fields_with_report) keep checking
+/// the remaining fields of a struct after one of them is invalid.
+///
+/// # Safety
+///
+/// `check_field` must return `Ok` for every index in `0..FIELDS.len()` if and
+/// only if every field of the struct is valid for the given context.
+pub unsafe trait CheckFields<C: Fallible + ?Sized> {
+    /// The names of the fields of the struct, in declaration order.
+    const FIELDS: &'static [&'static str];
+
+    /// Checks the field at the given index in [`FIELDS`](CheckFields::FIELDS).
+    ///
+    /// Indices which do not name a field are ignored.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be aligned and point to enough bytes to represent the
+    /// struct.
+    unsafe fn check_field(
+        value: *const Self,
+        index: usize,
+        context: &mut C,
+    ) -> Result<(), C::Error>;
+}
+
+/// The kind of a pointer to shared memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SharedPointerKind {
+    /// A strong pointer like an `ArchivedRc`
+    Strong,
+    /// A weak pointer like an `ArchivedRcWeak`
+    Weak,
 }
 
 /// A context that can validate shared archive memory.
@@ -283,6 +487,38 @@ pub trait SharedContext<E = <Self as Fallible>::Error> {
         address: usize,
         type_id: TypeId,
     ) -> Result<bool, E>;
+
+    /// Registers a pointer of the given kind to a shared value of the given
+    /// type and size.
+    ///
+    /// Returns `true` if the value was newly-registered and `check_bytes`
+    /// should be called. After the value has been checked,
+    /// [`finish_shared_value`](SharedContext::finish_shared_value) must be
+    /// called with the same address.
+    ///
+    /// By default, this calls
+    /// [`register_shared_ptr`](SharedContext::register_shared_ptr).
+    #[inline]
+    fn register_shared_value(
+        &mut self,
+        address: usize,
+        size: usize,
+        type_id: TypeId,
+        kind: SharedPointerKind,
+    ) -> Result<bool, E> {
+        let _ = (size, kind);
+        self.register_shared_ptr(address, type_id)
+    }
+
+    /// Finishes checking a shared value which was newly registered by
+    /// [`register_shared_value`](SharedContext::register_shared_value).
+    ///
+    /// By default, this does nothing.
+    #[inline]
+    fn finish_shared_value(&mut self, address: usize) -> Result<(), E> {
+        let _ = address;
+        Ok(())
+    }
 }
 
 impl<T, E> SharedContext<E> for Strategy<T, E>
@@ -296,4 +532,18 @@ where
     ) -> Result<bool, E> {
         T::register_shared_ptr(self, address, type_id)
     }
+
+    fn register_shared_value(
+        &mut self,
+        address: usize,
+        size: usize,
+        type_id: TypeId,
+        kind: SharedPointerKind,
+    ) -> Result<bool, E> {
+        T::register_shared_value(self, address, size, type_id, kind)
+    }
+
+    fn finish_shared_value(&mut self, address: usize) -> Result<(), E> {
+        T::finish_shared_value(self, address)
+    }
 }
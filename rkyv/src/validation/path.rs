@@ -0,0 +1,496 @@
+//! Logical paths to values that failed validation.
+
+use core::{fmt, mem};
+#[cfg(feature = "std")]
+use std::{any::Any, boxed::Box, error, string::ToString as _, vec::Vec};
+
+#[cfg(feature = "std")]
+use bytecheck::{
+    NamedEnumVariantCheckContext, StructCheckContext, TupleStructCheckContext,
+    UnnamedEnumVariantCheckContext,
+};
+
+#[cfg(feature = "std")]
+use crate::validation::validators::ArchiveError;
+
+/// Context for errors resulting from checking an element of a collection.
+///
+/// This context is used by collections to trace which element failed
+/// validation.
+#[derive(Debug)]
+pub struct IndexCheckContext {
+    /// The index of the element that was invalid.
+    pub index: usize,
+    /// The address of the element that was invalid.
+    pub address: usize,
+}
+
+impl fmt::Display for IndexCheckContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "while checking element {} at address {:#x}",
+            self.index, self.address,
+        )
+    }
+}
+
+/// Context for errors resulting from checking a bucket of a hash table.
+///
+/// This context is used by hash tables to trace which bucket failed
+/// validation.
+#[derive(Debug)]
+pub struct BucketCheckContext {
+    /// The index of the bucket that was invalid.
+    pub index: usize,
+    /// The address of the bucket that was invalid.
+    pub address: usize,
+}
+
+impl fmt::Display for BucketCheckContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "while checking hash table bucket {} at address {:#x}",
+            self.index, self.address,
+        )
+    }
+}
+
+/// Context for errors resulting from checking the target of a relative
+/// pointer.
+///
+/// This context is used by boxes, shared pointers, and strings to trace where
+/// their out-of-line value is located.
+#[derive(Debug)]
+pub struct PointeeCheckContext {
+    /// The address of the value that was invalid.
+    pub address: usize,
+}
+
+impl fmt::Display for PointeeCheckContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "while checking pointee at address {:#x}", self.address)
+    }
+}
+
+/// A single step in the logical path to a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A named field of a struct or enum variant.
+    Field(&'static str),
+    /// An unnamed field of a tuple struct or enum variant.
+    TupleField(usize),
+    /// An enum variant.
+    Variant(&'static str),
+    /// An element of a collection.
+    Index(usize),
+    /// A bucket of a hash table.
+    Bucket(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Field(name) => write!(f, ".{}", name),
+            PathSegment::TupleField(index) => write!(f, ".{}", index),
+            PathSegment::Variant(name) => write!(f, "::{}", name),
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+            PathSegment::Bucket(index) => write!(f, "{{{}}}", index),
+        }
+    }
+}
+
+/// The logical path from the root of an archive to a value.
+///
+/// Paths are displayed starting from `root`, for example
+/// `root.players[3].inventory.name`.
+#[derive(Clone, Copy, Debug)]
+pub struct ValidationPath<'a> {
+    // Segments are stored from the innermost value outwards.
+    segments: &'a [PathSegment],
+}
+
+impl<'a> ValidationPath<'a> {
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn new(segments: &'a [PathSegment]) -> Self {
+        Self { segments }
+    }
+
+    /// Returns an iterator over the segments of the path, starting from the
+    /// root.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a PathSegment> {
+        self.segments.iter().rev()
+    }
+}
+
+impl fmt::Display for ValidationPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "root")?;
+        for segment in self.iter() {
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error type which records the logical path to the value that failed
+/// validation.
+///
+/// Using `ValidationError` as the error type for [`access`](crate::access)
+/// collects the fields, variants, and elements that were being checked when
+/// validation failed, along with the address of the innermost value with a
+/// known location.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access, rancor::Failure, to_bytes, validation::path::ValidationError,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Player {
+///     name: String,
+///     alive: bool,
+/// }
+///
+/// let players = vec![
+///     Player { name: "alice".to_string(), alive: true },
+///     Player { name: "bob".to_string(), alive: false },
+/// ];
+/// let mut bytes = to_bytes::<_, 256, Failure>(&players).unwrap();
+///
+/// // Corrupt the `alive` field of the second player
+/// let archived = unsafe { rkyv::access_unchecked::<Vec<Player>>(&bytes) };
+/// let base = bytes.as_ptr() as usize;
+/// let player_offset = &archived[1] as *const _ as usize - base;
+/// let alive_offset = &archived[1].alive as *const bool as usize - base;
+/// bytes[alive_offset] = 2;
+///
+/// let error = access::<Vec<Player>, ValidationError>(&bytes).unwrap_err();
+/// assert_eq!(error.path().to_string(), "root[1].alive");
+/// assert_eq!(error.offset_in(&bytes), Some(player_offset));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ValidationError {
+    inner: Box<dyn error::Error + Send + Sync + 'static>,
+    // Segments and traces are stored from the innermost value outwards.
+    segments: Vec<PathSegment>,
+    traces: Vec<Box<str>>,
+    address: Option<usize>,
+    expected_size: Option<usize>,
+    expected_align: Option<usize>,
+    // Errors collected from the elements of a collection or the fields of a
+    // struct, and the segments of the ones which were checked successfully.
+    collected: Vec<ValidationError>,
+    intact: Vec<PathSegment>,
+}
+
+#[cfg(feature = "std")]
+impl ValidationError {
+    /// Returns the error which caused validation to fail.
+    #[inline]
+    pub fn inner(&self) -> &(dyn error::Error + Send + Sync + 'static) {
+        &*self.inner
+    }
+
+    /// Returns the logical path to the value that failed validation.
+    #[inline]
+    pub fn path(&self) -> ValidationPath<'_> {
+        ValidationPath {
+            segments: &self.segments,
+        }
+    }
+
+    /// Returns the address of the innermost value with a known location that
+    /// was being checked when validation failed.
+    #[inline]
+    pub fn address(&self) -> Option<usize> {
+        self.address
+    }
+
+    /// Returns the offset within `bytes` of the innermost value with a known
+    /// location that was being checked when validation failed.
+    ///
+    /// Returns `None` if no location is known or it is not inside `bytes`.
+    pub fn offset_in(&self, bytes: &[u8]) -> Option<usize> {
+        let offset = self.address?.checked_sub(bytes.as_ptr() as usize)?;
+        (offset <= bytes.len()).then_some(offset)
+    }
+
+    /// Returns the size of the value that failed validation, if it was
+    /// checked against the bounds of the archive.
+    #[inline]
+    pub fn expected_size(&self) -> Option<usize> {
+        self.expected_size
+    }
+
+    /// Returns the alignment that the value which failed validation required,
+    /// if it was misaligned.
+    #[inline]
+    pub fn expected_align(&self) -> Option<usize> {
+        self.expected_align
+    }
+
+    /// Creates an error from the errors of some elements of a collection or
+    /// fields of a struct.
+    ///
+    /// `intact` contains the segments of the elements or fields which were
+    /// checked successfully.
+    pub(crate) fn collected(
+        collected: Vec<ValidationError>,
+        intact: Vec<PathSegment>,
+    ) -> Self {
+        let mut result = Self::new(CollectedErrors {
+            count: collected.len(),
+        });
+        result.collected = collected;
+        result.intact = intact;
+        result
+    }
+
+    /// Flattens this error and the errors it collected into `errors`, and
+    /// adds the paths of any intact elements to `intact`.
+    ///
+    /// `outer` contains the segments of the path to this error, from the
+    /// innermost segment outwards.
+    pub(crate) fn flatten(
+        mut self,
+        outer: &[PathSegment],
+        errors: &mut Vec<ValidationError>,
+        intact: &mut Vec<Vec<PathSegment>>,
+    ) {
+        self.segments.extend_from_slice(outer);
+        if self.collected.is_empty() {
+            errors.push(self);
+            return;
+        }
+
+        // The outermost segment of each collected error is the element or
+        // field which failed. Intact elements are only listed when they are
+        // addressed the same way, which is not the case for hash tables whose
+        // buckets are checked one group at a time.
+        let failed = self
+            .collected
+            .first()
+            .and_then(|error| error.segments.last())
+            .map(mem::discriminant);
+        for segment in self.intact.drain(..) {
+            if Some(mem::discriminant(&segment)) == failed {
+                let mut path = Vec::with_capacity(self.segments.len() + 1);
+                path.push(segment);
+                path.extend_from_slice(&self.segments);
+                intact.push(path);
+            }
+        }
+
+        for mut error in self.collected.drain(..) {
+            if error.address.is_none() {
+                error.address = self.address;
+            }
+            error.flatten(&self.segments, errors, intact);
+        }
+    }
+
+    fn set_address(&mut self, address: usize) {
+        if self.address.is_none() {
+            self.address = Some(address);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.inner, self.path())?;
+        if let Some(address) = self.address {
+            write!(f, " (address {:#x})", address)?;
+        }
+        for trace in self.traces.iter() {
+            write!(f, "\n{}", trace)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ValidationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&*self.inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl rancor::Trace for ValidationError {
+    fn trace<R>(mut self, trace: R) -> Self
+    where
+        R: fmt::Debug + fmt::Display + Send + Sync + 'static,
+    {
+        let any = &trace as &dyn Any;
+        if let Some(context) = any.downcast_ref::<StructCheckContext>() {
+            self.segments.push(PathSegment::Field(context.field_name));
+        } else if let Some(context) =
+            any.downcast_ref::<TupleStructCheckContext>()
+        {
+            self.segments
+                .push(PathSegment::TupleField(context.field_index));
+        } else if let Some(context) =
+            any.downcast_ref::<NamedEnumVariantCheckContext>()
+        {
+            self.segments.push(PathSegment::Field(context.field_name));
+            self.segments
+                .push(PathSegment::Variant(context.variant_name));
+        } else if let Some(context) =
+            any.downcast_ref::<UnnamedEnumVariantCheckContext>()
+        {
+            self.segments
+                .push(PathSegment::TupleField(context.field_index));
+            self.segments
+                .push(PathSegment::Variant(context.variant_name));
+        } else if let Some(context) = any.downcast_ref::<IndexCheckContext>() {
+            self.segments.push(PathSegment::Index(context.index));
+            self.set_address(context.address);
+        } else if let Some(context) = any.downcast_ref::<BucketCheckContext>() {
+            self.segments.push(PathSegment::Bucket(context.index));
+            self.set_address(context.address);
+        } else if let Some(context) = any.downcast_ref::<PointeeCheckContext>()
+        {
+            self.set_address(context.address);
+        } else {
+            self.traces.push(trace.to_string().into_boxed_str());
+        }
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl rancor::Error for ValidationError {
+    fn new<T: error::Error + Send + Sync + 'static>(source: T) -> Self {
+        let (address, expected_size, expected_align) = match (&source
+            as &dyn Any)
+            .downcast_ref::<ArchiveError>()
+        {
+            Some(ArchiveError::Unaligned { address, align }) => {
+                (Some(*address), None, Some(*align))
+            }
+            Some(ArchiveError::InvalidSubtreePointer {
+                address, size, ..
+            }) => (Some(*address), Some(*size), None),
+            _ => (None, None, None),
+        };
+
+        Self {
+            inner: Box::new(source),
+            segments: Vec::new(),
+            traces: Vec::new(),
+            address,
+            expected_size,
+            expected_align,
+            collected: Vec::new(),
+            intact: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct CollectedErrors {
+    count: usize,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for CollectedErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} elements or fields were invalid", self.count)
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for CollectedErrors {}
+
+/// A report of every error found while validating an archive.
+///
+/// Reports are created by
+/// [`check_with_report`](crate::validation::util::check_with_report), which
+/// validates an archive with a
+/// [`CollectingValidator`](crate::validation::validators::CollectingValidator).
+/// When an element of a collection is invalid, its error is recorded and
+/// validation continues with the next element. Fields of structs and enums are
+/// still checked in order, and the first invalid field stops the checking of
+/// the rest of its parent. The fields of the root struct can be checked
+/// individually with
+/// [`check_fields_with_report`](crate::validation::util::check_fields_with_report).
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    errors: Vec<ValidationError>,
+    // Segments are stored from the innermost value outwards.
+    intact: Vec<Vec<PathSegment>>,
+}
+
+#[cfg(feature = "std")]
+impl ValidationReport {
+    pub(crate) fn from_result(result: Result<(), ValidationError>) -> Self {
+        let mut report = Self::default();
+        if let Err(error) = result {
+            error.flatten(&[], &mut report.errors, &mut report.intact);
+        }
+        report
+    }
+
+    /// Returns whether the archive was valid.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns every error found while validating the archive.
+    ///
+    /// Each error records the path to the invalid value, its address, and the
+    /// expected size and alignment of the value when they are known.
+    #[inline]
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+
+    /// Returns the paths of the elements which were checked successfully in
+    /// collections that had invalid elements, and of the root fields which
+    /// were checked successfully when some root fields were invalid.
+    ///
+    /// Hash table buckets are not listed because empty buckets can't be told
+    /// apart from intact ones.
+    pub fn intact_elements(
+        &self,
+    ) -> impl ExactSizeIterator<Item = ValidationPath<'_>> {
+        self.intact.iter().map(|path| ValidationPath::new(path))
+    }
+
+    /// Consumes the report and returns the errors it contains.
+    #[inline]
+    pub fn into_errors(self) -> Vec<ValidationError> {
+        self.errors
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "archive is valid");
+        }
+
+        write!(f, "found {} errors in archive", self.errors.len())?;
+        for error in self.errors.iter() {
+            write!(f, "\n- {} at {}", error.inner, error.path())?;
+            if let Some(address) = error.address {
+                write!(f, " (address {:#x})", address)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,26 @@
 //! Utility methods for accessing and deserializing safely.
 
-use core::{mem::size_of, pin::Pin};
+use core::{fmt, hash::Hasher, mem::size_of, ops::Range, pin::Pin};
 
 use bytecheck::CheckBytes;
 use ptr_meta::Pointee;
-use rancor::{Error, ResultExt as _, Strategy};
+use rancor::{fail, Error, ResultExt as _, Strategy};
 
+#[cfg(feature = "parallel")]
+use crate::validation::validators::ParallelValidator;
+#[cfg(feature = "std")]
+use crate::validation::{
+    path::{ValidationError, ValidationReport},
+    validators::CollectingValidator,
+    CheckFields,
+};
 use crate::{
     de::pooling::Unify,
     deserialize,
-    util::{access_pos_unchecked, access_pos_unchecked_mut},
+    util::{access_pos_unchecked, access_pos_unchecked_mut, ArchiveHeader},
     validation::{
-        validators::DefaultValidator, ArchiveContext, ArchiveContextExt as _,
+        validators::{DefaultValidator, ValidationLimits},
+        ArchiveContext, ArchiveContextExt as _,
     },
     Archive, Deserialize,
 };
@@ -160,6 +169,506 @@ where
     access_with_context::<T, DefaultValidator, E>(bytes, &mut validator)
 }
 
+/// Accesses an archived value from the given byte slice by calculating the root
+/// position after checking its validity and that it stays within the given
+/// resource limits.
+///
+/// This should be used instead of [`access`] for archives from untrusted
+/// sources. See [`ValidationLimits`] for more details.
+#[inline]
+pub fn access_with_limits<T, E>(
+    bytes: &[u8],
+    limits: ValidationLimits,
+) -> Result<&T::Archived, E>
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+    E: Error,
+{
+    let mut validator = DefaultValidator::with_limits(bytes, limits);
+    access_with_context::<T, DefaultValidator, E>(bytes, &mut validator)
+}
+
+/// Accesses an archived value from the given byte slice at the given position
+/// after checking its validity on multiple threads.
+///
+/// Large collections are checked in parallel with a [`ParallelValidator`].
+/// Collections which contain shared pointers are checked sequentially, so the
+/// result is always the same as that of [`access_pos`].
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn access_pos_parallel<T, E>(
+    bytes: &[u8],
+    pos: usize,
+) -> Result<&T::Archived, E>
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<ParallelValidator, E>>,
+    E: Error + Send,
+{
+    let mut validator = ParallelValidator::new(bytes);
+    access_pos_with_context::<T, ParallelValidator, E>(
+        bytes,
+        pos,
+        &mut validator,
+    )
+}
+
+/// Accesses an archived value from the given byte slice by calculating the root
+/// position after checking its validity on multiple threads.
+///
+/// Large collections are checked in parallel with a [`ParallelValidator`].
+/// Collections which contain shared pointers are checked sequentially, so the
+/// result is always the same as that of [`access`].
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Failure, to_bytes, validation::util::access_parallel};
+///
+/// let value = (0..10_000).map(|i| i.to_string()).collect::<Vec<_>>();
+/// let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+///
+/// let archived = access_parallel::<Vec<String>, Failure>(&bytes).unwrap();
+/// assert_eq!(archived[1234].as_str(), "1234");
+/// ```
+#[cfg(feature = "parallel")]
+#[inline]
+pub fn access_parallel<T, E>(bytes: &[u8]) -> Result<&T::Archived, E>
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<ParallelValidator, E>>,
+    E: Error + Send,
+{
+    let mut validator = ParallelValidator::new(bytes);
+    access_with_context::<T, ParallelValidator, E>(bytes, &mut validator)
+}
+
+/// Checks a byte slice for a valid archived `T` at the given position and
+/// reports every error found.
+///
+/// Unlike [`access_pos`], validation continues after invalid collection
+/// elements so that all of them can be reported. See [`ValidationReport`] for
+/// more details.
+#[cfg(feature = "std")]
+pub fn check_pos_with_report<T>(bytes: &[u8], pos: usize) -> ValidationReport
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<CollectingValidator, ValidationError>>,
+{
+    let mut validator = CollectingValidator::new(bytes);
+    let result = check_pos_with_context::<T, _, ValidationError>(
+        bytes,
+        pos,
+        &mut validator,
+    );
+    ValidationReport::from_result(result)
+}
+
+/// Checks a byte slice for a valid archived `T` by calculating the root
+/// position and reports every error found.
+///
+/// Unlike [`access`], validation continues after invalid collection elements
+/// so that all of them can be reported. See [`ValidationReport`] for more
+/// details.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked, rancor::Failure, to_bytes,
+///     validation::util::check_with_report,
+/// };
+///
+/// let value = vec![true, false, true, false];
+/// let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+/// let base = bytes.as_ptr() as usize;
+/// let archived = unsafe { access_unchecked::<Vec<bool>>(&bytes) };
+/// let first = &archived[1] as *const bool as usize - base;
+/// let second = &archived[3] as *const bool as usize - base;
+/// bytes[first] = 2;
+/// bytes[second] = 2;
+///
+/// let report = check_with_report::<Vec<bool>>(&bytes);
+/// assert_eq!(report.errors().len(), 2);
+/// assert_eq!(report.errors()[0].path().to_string(), "root[1]");
+/// assert_eq!(report.errors()[1].path().to_string(), "root[3]");
+/// assert_eq!(report.intact_elements().len(), 2);
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+pub fn check_with_report<T>(bytes: &[u8]) -> ValidationReport
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<CollectingValidator, ValidationError>>,
+{
+    check_pos_with_report::<T>(
+        bytes,
+        bytes.len().saturating_sub(size_of::<T::Archived>()),
+    )
+}
+
+/// Checks a byte slice for a valid archived struct `T` at the given position
+/// and reports every error found, checking the fields of the struct one at a
+/// time.
+///
+/// Unlike [`check_pos_with_report`], validation also continues after invalid
+/// fields of the root struct so that the intact fields can be reported. The
+/// archived struct must implement [`CheckFields`], which
+/// `#[archive(check_bytes)]` derives for structs with named fields. Any
+/// [`Verify`](bytecheck::Verify) impl of the root struct is not run.
+#[cfg(feature = "std")]
+pub fn check_pos_fields_with_report<T>(
+    bytes: &[u8],
+    pos: usize,
+) -> ValidationReport
+where
+    T: Archive,
+    T::Archived: CheckFields<Strategy<CollectingValidator, ValidationError>>,
+{
+    fn check<T>(
+        bytes: &[u8],
+        pos: usize,
+        validator: &mut CollectingValidator,
+    ) -> Result<(), ValidationError>
+    where
+        T: CheckFields<Strategy<CollectingValidator, ValidationError>>,
+    {
+        unsafe {
+            let offset = pos.try_into().into_error()?;
+            let ptr = validator.bounds_check_subtree_base_offset::<T>(
+                bytes.as_ptr(),
+                offset,
+                (),
+            )?;
+
+            let range = validator.push_prefix_subtree(ptr)?;
+            validator.check_fields(ptr)?;
+            validator.pop_subtree_range(range)?;
+        }
+
+        Ok(())
+    }
+
+    let mut validator = CollectingValidator::new(bytes);
+    let result = check::<T::Archived>(bytes, pos, &mut validator);
+    ValidationReport::from_result(result)
+}
+
+/// Checks a byte slice for a valid archived struct `T` by calculating the root
+/// position and reports every error found, checking the fields of the struct
+/// one at a time.
+///
+/// See [`check_pos_fields_with_report`] for more details.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked, rancor::Failure, to_bytes,
+///     validation::util::check_fields_with_report, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Flags {
+///     a: bool,
+///     b: bool,
+///     c: bool,
+/// }
+///
+/// let value = Flags {
+///     a: true,
+///     b: false,
+///     c: true,
+/// };
+/// let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+/// let base = bytes.as_ptr() as usize;
+/// let archived = unsafe { access_unchecked::<Flags>(&bytes) };
+/// let a = &archived.a as *const bool as usize - base;
+/// bytes[a] = 2;
+///
+/// let report = check_fields_with_report::<Flags>(&bytes);
+/// assert_eq!(report.errors().len(), 1);
+/// assert_eq!(report.errors()[0].path().to_string(), "root.a");
+/// let intact = report
+///     .intact_elements()
+///     .map(|path| path.to_string())
+///     .collect::<Vec<_>>();
+/// assert_eq!(intact, vec!["root.b", "root.c"]);
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+pub fn check_fields_with_report<T>(bytes: &[u8]) -> ValidationReport
+where
+    T: Archive,
+    T::Archived: CheckFields<Strategy<CollectingValidator, ValidationError>>,
+{
+    check_pos_fields_with_report::<T>(
+        bytes,
+        bytes.len().saturating_sub(size_of::<T::Archived>()),
+    )
+}
+
+#[derive(Debug)]
+struct RangeOutOfBounds {
+    range: Range<usize>,
+    len: usize,
+}
+
+impl fmt::Display for RangeOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archive range {}..{} is out of bounds for buffer of length {}",
+            self.range.start, self.range.end, self.len,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RangeOutOfBounds {}
+
+fn slice_range<E: Error>(
+    bytes: &[u8],
+    range: Range<usize>,
+) -> Result<&[u8], E> {
+    match bytes.get(range.clone()) {
+        Some(archive) => Ok(archive),
+        None => fail!(RangeOutOfBounds {
+            range,
+            len: bytes.len(),
+        }),
+    }
+}
+
+/// Accesses an archived value at the given position within an archive located
+/// at `range` in a larger buffer, after checking its validity.
+///
+/// `pos` is relative to the start of the archive. Pointers are only allowed to
+/// point within `range`, and alignment is checked against the absolute address
+/// of each value. The archive should start at an address that is aligned for
+/// the archive, which is usually 16 bytes.
+#[inline]
+pub fn access_pos_in_range<T, E>(
+    bytes: &[u8],
+    range: Range<usize>,
+    pos: usize,
+) -> Result<&T::Archived, E>
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+    E: Error,
+{
+    access_pos::<T, E>(slice_range(bytes, range)?, pos)
+}
+
+/// Accesses an archived value from an archive located at `range` in a larger
+/// buffer by calculating the root position, after checking its validity.
+///
+/// This allows many archives to be stored in the same buffer, for example in
+/// a single memory-mapped file. Pointers are only allowed to point within
+/// `range`, and alignment is checked against the absolute address of each
+/// value. The archive should start at an address that is aligned for the
+/// archive, which is usually 16 bytes.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Failure, to_bytes, util::AlignedVec,
+///     validation::util::access_range,
+/// };
+///
+/// let first = to_bytes::<_, 256, Failure>(&"first".to_string()).unwrap();
+/// let second = to_bytes::<_, 256, Failure>(&"second".to_string()).unwrap();
+///
+/// let mut buffer = AlignedVec::new();
+/// buffer.extend_from_slice(&first);
+/// // Pad the buffer so that the second archive is aligned
+/// let start = buffer.len().next_multiple_of(AlignedVec::ALIGNMENT);
+/// buffer.resize(start, 0);
+/// buffer.extend_from_slice(&second);
+///
+/// let archived =
+///     access_range::<String, Failure>(&buffer, start..buffer.len()).unwrap();
+/// assert_eq!(archived, "second");
+/// ```
+#[inline]
+pub fn access_range<T, E>(
+    bytes: &[u8],
+    range: Range<usize>,
+) -> Result<&T::Archived, E>
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+    E: Error,
+{
+    access::<T, E>(slice_range(bytes, range)?)
+}
+
+#[derive(Debug)]
+enum HeaderError {
+    Missing,
+    TypeTagMismatch { expected: u64, found: u64 },
+    RootInHeader { root_pos: u64 },
+    LengthMismatch { declared: u64, actual: usize },
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => {
+                write!(f, "buffer does not begin with an archive header")
+            }
+            Self::TypeTagMismatch { expected, found } => write!(
+                f,
+                "archive header type tag mismatch: expected {:#x}, found {:#x}",
+                expected, found,
+            ),
+            Self::RootInHeader { root_pos } => write!(
+                f,
+                "archive header root position {} is inside the header",
+                root_pos,
+            ),
+            Self::LengthMismatch { declared, actual } => write!(
+                f,
+                "archive header declares a length of {} bytes, but the \
+                 buffer is {} bytes long",
+                declared, actual,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HeaderError {}
+
+/// Accesses an archived value from the given byte slice by reading the root
+/// position from an [`ArchiveHeader`] at the start of the slice, after checking
+/// its validity.
+///
+/// The header must have the given type tag, and the archive must fit within
+/// the byte slice. Only the bytes after the header are validated, so the
+/// archive may not point into the header. Any bytes after the length recorded
+/// in the header are ignored.
+///
+/// This is a safe alternative to
+/// [`access_with_header_unchecked`](crate::util::access_with_header_unchecked).
+#[inline]
+pub fn access_with_header<T, E>(
+    bytes: &[u8],
+    type_tag: u64,
+) -> Result<&T::Archived, E>
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+    E: Error,
+{
+    let header = match ArchiveHeader::from_bytes(bytes) {
+        Some(header) => header,
+        None => fail!(HeaderError::Missing),
+    };
+    if header.type_tag != type_tag {
+        fail!(HeaderError::TypeTagMismatch {
+            expected: type_tag,
+            found: header.type_tag,
+        });
+    }
+    if header.len < ArchiveHeader::SIZE as u64
+        || header.len > bytes.len() as u64
+    {
+        fail!(HeaderError::LengthMismatch {
+            declared: header.len,
+            actual: bytes.len(),
+        });
+    }
+    let root_pos = match header.root_pos.checked_sub(ArchiveHeader::SIZE as u64)
+    {
+        Some(root_pos) => root_pos,
+        None => fail!(HeaderError::RootInHeader {
+            root_pos: header.root_pos,
+        }),
+    };
+
+    // Positions in the header are relative to the start of the header, but
+    // only the body of the archive is validated.
+    let bytes = &bytes[ArchiveHeader::SIZE..header.len as usize];
+    let root_pos = root_pos.try_into().into_error()?;
+    access_pos::<T, E>(bytes, root_pos)
+}
+
+#[derive(Debug)]
+struct DigestMismatch {
+    expected: u64,
+    actual: u64,
+}
+
+impl fmt::Display for DigestMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archive digest mismatch: expected {:#x}, found {:#x}",
+            self.expected, self.actual,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DigestMismatch {}
+
+/// Accesses an archived value from the given byte slice by calculating the root
+/// position after verifying its digest and checking its validity.
+///
+/// The digest of the entire byte slice is computed with a default `H` and
+/// compared against `digest` before any validation is performed. Digests can
+/// be computed while serializing with a
+/// [`HashingWriter`](crate::ser::writer::HashingWriter).
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     hash::Crc32c, rancor::Failure, ser::writer::HashingWriter, serialize,
+///     util::AlignedVec, validation::util::access_checked_with_digest,
+/// };
+///
+/// let mut writer = HashingWriter::<_, Crc32c>::new(AlignedVec::new());
+/// serialize::<_, _, Failure>(&"hello world".to_string(), &mut writer)
+///     .unwrap();
+/// let digest = writer.digest();
+/// let mut bytes = writer.into_inner();
+///
+/// let archived =
+///     access_checked_with_digest::<String, Crc32c, Failure>(&bytes, digest)
+///         .unwrap();
+/// assert_eq!(archived, "hello world");
+///
+/// bytes[0] ^= 1;
+/// assert!(access_checked_with_digest::<String, Crc32c, Failure>(
+///     &bytes, digest
+/// )
+/// .is_err());
+/// ```
+#[inline]
+pub fn access_checked_with_digest<T, H, E>(
+    bytes: &[u8],
+    digest: u64,
+) -> Result<&T::Archived, E>
+where
+    T: Archive,
+    T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+    H: Hasher + Default,
+    E: Error,
+{
+    let mut hasher = H::default();
+    hasher.write(bytes);
+    let actual = hasher.finish();
+    if actual != digest {
+        fail!(DigestMismatch {
+            expected: digest,
+            actual,
+        });
+    }
+
+    access::<T, E>(bytes)
+}
+
 // TODO: `Pin` is not technically correct for the return type. `Pin` requires
 // the pinned value to be dropped before its memory can be reused, but archived
 // types explicitly do not require that. It just wants immovable types.
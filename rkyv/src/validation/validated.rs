@@ -0,0 +1,207 @@
+#[cfg(not(feature = "std"))]
+use alloc::{rc::Rc, sync::Arc};
+use core::{fmt, marker::PhantomData, mem::size_of, ops::Deref};
+#[cfg(feature = "std")]
+use std::{rc::Rc, sync::Arc};
+
+use bytecheck::CheckBytes;
+use rancor::{Error, Strategy};
+
+use crate::{
+    util::{access_pos_unchecked, AlignedVec},
+    validation::{util::access_pos, validators::DefaultValidator},
+    Archive,
+};
+
+/// A buffer which always dereferences to the same bytes.
+///
+/// # Safety
+///
+/// - Every call to `deref` must return the same bytes at the same address.
+/// - Moving the buffer must not move its bytes.
+/// - The bytes must not be modified while the buffer is alive.
+pub unsafe trait StableBytes: Deref<Target = [u8]> {}
+
+/// A [`StableBytes`] buffer whose clones share its bytes.
+///
+/// A [`Validated`] can only be cloned if its buffer is `SharedBytes`. Buffers
+/// like [`AlignedVec`] copy their bytes when cloned, and the copy is only
+/// guaranteed to be aligned to [`AlignedVec::ALIGNMENT`]. An archive which
+/// contains more strictly aligned values must be validated again after it is
+/// copied.
+///
+/// # Safety
+///
+/// Cloned buffers must dereference to the same bytes at the same address.
+pub unsafe trait SharedBytes: StableBytes + Clone {}
+
+// SAFETY: Shared slices always refer to the same bytes, which can't be modified
+// while they are borrowed.
+unsafe impl StableBytes for &[u8] {}
+
+// SAFETY: Copies of a shared slice refer to the same bytes.
+unsafe impl SharedBytes for &[u8] {}
+
+// SAFETY: `AlignedVec` stores its bytes in a separate allocation which does not
+// move when the vector is moved. The vector can only be modified through a
+// mutable reference.
+unsafe impl StableBytes for AlignedVec {}
+
+// SAFETY: The bytes of an `Rc` are shared and can't be modified.
+unsafe impl StableBytes for Rc<[u8]> {}
+
+// SAFETY: Clones of an `Rc` point to the same allocation.
+unsafe impl SharedBytes for Rc<[u8]> {}
+
+// SAFETY: The bytes of an `Arc` are shared and can't be modified.
+unsafe impl StableBytes for Arc<[u8]> {}
+
+// SAFETY: Clones of an `Arc` point to the same allocation.
+unsafe impl SharedBytes for Arc<[u8]> {}
+
+// SAFETY: Memory maps do not move when they are moved, and read-only maps can't
+// be modified through the map.
+#[cfg(feature = "mmap")]
+unsafe impl StableBytes for memmap2::Mmap {}
+
+/// An archived value which has already been validated.
+///
+/// `Validated` owns or borrows the buffer containing an archive, and can only
+/// be created by checking that the buffer contains a valid archived `T`. Once
+/// created, it dereferences to the archived value without checking it again,
+/// and without requiring any `unsafe` code. This makes it possible to validate
+/// an archive once and then pass it through many layers of code.
+///
+/// Borrowing a `Validated` with [`as_slice`](Validated::as_slice) returns a
+/// `Validated` that can be copied freely. Only `Validated`s with
+/// [`SharedBytes`] buffers can be cloned.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Failure, to_bytes, validation::Validated};
+///
+/// fn print_names(names: Validated<Vec<String>, &[u8]>) {
+///     for name in names.iter() {
+///         println!("{}", name);
+///     }
+/// }
+///
+/// let value = vec!["alice".to_string(), "bob".to_string()];
+/// let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+///
+/// let names = Validated::<Vec<String>, _>::new::<Failure>(bytes).unwrap();
+/// assert_eq!(names[1].as_str(), "bob");
+/// print_names(names.as_slice());
+/// ```
+pub struct Validated<T, B> {
+    bytes: B,
+    pos: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Archive, B: StableBytes> Validated<T, B> {
+    /// Checks that the given buffer contains a valid archived `T` at the root
+    /// position.
+    pub fn new<E>(bytes: B) -> Result<Self, E>
+    where
+        T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+        E: Error,
+    {
+        let pos = bytes.len().saturating_sub(size_of::<T::Archived>());
+        Self::new_pos(bytes, pos)
+    }
+
+    /// Checks that the given buffer contains a valid archived `T` at the given
+    /// position.
+    pub fn new_pos<E>(bytes: B, pos: usize) -> Result<Self, E>
+    where
+        T::Archived: CheckBytes<Strategy<DefaultValidator, E>>,
+        E: Error,
+    {
+        access_pos::<T, E>(&bytes, pos)?;
+        Ok(Self {
+            bytes,
+            pos,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the archived value.
+    #[inline]
+    pub fn get(&self) -> &T::Archived {
+        // SAFETY: The bytes were checked to contain a valid `T::Archived` at
+        // `pos` when `self` was created, and `StableBytes` guarantees that
+        // they have not moved or changed since.
+        unsafe { access_pos_unchecked::<T>(&self.bytes, self.pos) }
+    }
+
+    /// Returns a `Validated` which borrows the buffer of this one.
+    #[inline]
+    pub fn as_slice(&self) -> Validated<T, &[u8]> {
+        Validated {
+            bytes: &self.bytes,
+            pos: self.pos,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, B> Validated<T, B> {
+    /// Returns the position of the archived value in the buffer.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the buffer containing the archived value.
+    #[inline]
+    pub fn buffer(&self) -> &B {
+        &self.bytes
+    }
+
+    /// Consumes the `Validated` and returns the buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.bytes
+    }
+}
+
+impl<T: Archive, B: StableBytes> Deref for Validated<T, B> {
+    type Target = T::Archived;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: Archive, B: StableBytes> AsRef<T::Archived> for Validated<T, B> {
+    #[inline]
+    fn as_ref(&self) -> &T::Archived {
+        self.get()
+    }
+}
+
+impl<T, B: SharedBytes> Clone for Validated<T, B> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            pos: self.pos,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, B: SharedBytes + Copy> Copy for Validated<T, B> {}
+
+impl<T, B> fmt::Debug for Validated<T, B>
+where
+    T: Archive,
+    T::Archived: fmt::Debug,
+    B: StableBytes,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
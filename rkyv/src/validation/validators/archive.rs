@@ -3,6 +3,7 @@
 use core::{
     alloc::{Layout, LayoutError},
     fmt,
+    mem::{self, size_of},
     num::NonZeroUsize,
     ops::Range,
 };
@@ -10,7 +11,7 @@ use core::{
 use bytecheck::rancor::Error;
 use rancor::{fail, OptionExt};
 
-use crate::validation::ArchiveContext;
+use crate::{primitive::ArchivedUsize, validation::ArchiveContext};
 
 /// Errors that can occur when checking archive memory.
 #[derive(Debug)]
@@ -40,6 +41,29 @@ pub enum ArchiveError {
         /// A layout error
         layout_error: LayoutError,
     },
+    /// A collection had more elements than the maximum collection length.
+    ExceededMaximumCollectionLength {
+        /// The number of elements in the collection
+        len: usize,
+        /// The maximum collection length
+        max: usize,
+    },
+    /// The total number of elements in all collections exceeded the maximum.
+    ExceededMaximumTotalElements {
+        /// The maximum total number of elements
+        max: usize,
+    },
+    /// The total archived size of all collections exceeded the maximum.
+    ExceededMaximumArchivedBytes {
+        /// The maximum total archived size in bytes
+        max: usize,
+    },
+    /// The estimated number of bytes that deserializing all collections would
+    /// allocate exceeded the maximum.
+    ExceededMaximumAllocBytes {
+        /// The maximum estimated allocation size in bytes
+        max: usize,
+    },
 }
 
 impl fmt::Display for ArchiveError {
@@ -70,6 +94,28 @@ impl fmt::Display for ArchiveError {
             ArchiveError::LayoutError { layout_error } => {
                 write!(f, "a layout error occurred: {}", layout_error)
             }
+            ArchiveError::ExceededMaximumCollectionLength { len, max } => {
+                write!(
+                    f,
+                    "collection length {} exceeded the maximum of {}",
+                    len, max,
+                )
+            }
+            ArchiveError::ExceededMaximumTotalElements { max } => write!(
+                f,
+                "total number of collection elements exceeded the maximum of {}",
+                max,
+            ),
+            ArchiveError::ExceededMaximumArchivedBytes { max } => write!(
+                f,
+                "total archived size of collections exceeded the maximum of {} bytes",
+                max,
+            ),
+            ArchiveError::ExceededMaximumAllocBytes { max } => write!(
+                f,
+                "deserializing collections would allocate more than the maximum of {} bytes",
+                max,
+            ),
         }
     }
 }
@@ -77,11 +123,102 @@ impl fmt::Display for ArchiveError {
 #[cfg(feature = "std")]
 impl std::error::Error for ArchiveError {}
 
+/// Limits on the resources an archive may use.
+///
+/// A valid archive is safe to access, but it may still be expensive to use.
+/// For example, a vector of zero-sized types can claim an enormous length
+/// without occupying any bytes, and deserializing large collections may
+/// allocate much more memory than the archive itself. Limits allow archives
+/// from untrusted sources to be rejected during validation instead.
+///
+/// Every limit is optional, and the default limits do not restrict anything.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Failure, to_bytes, validation::util::access_with_limits,
+///     validation::validators::ValidationLimits,
+/// };
+///
+/// let value = vec![0u32; 100];
+/// let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+///
+/// let limits = ValidationLimits {
+///     max_collection_len: Some(10),
+///     ..ValidationLimits::default()
+/// };
+/// assert!(access_with_limits::<Vec<u32>, Failure>(&bytes, limits).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValidationLimits {
+    /// The maximum depth of nested subtrees.
+    pub max_subtree_depth: Option<NonZeroUsize>,
+    /// The maximum number of elements in any single collection.
+    pub max_collection_len: Option<usize>,
+    /// The maximum total number of elements in all collections.
+    pub max_total_elements: Option<usize>,
+    /// The maximum total archived size in bytes of all collections.
+    ///
+    /// This only counts the bytes that collections occupy in the archive. Use
+    /// `max_alloc_bytes` to limit how much deserializing them would allocate.
+    pub max_archived_bytes: Option<usize>,
+    /// The maximum number of bytes that deserializing all collections may
+    /// allocate.
+    ///
+    /// The allocation size of a collection is estimated from its archived
+    /// size. Native pointers and lengths may be wider than archived ones, so
+    /// the archived size is multiplied by the largest growth of the builtin
+    /// types. Those are hash maps, which are archived as three
+    /// [`ArchivedUsize`]s and deserialized to a four-word table and a 16-byte
+    /// hasher (four times larger on 64-bit targets with the default 32-bit
+    /// archived pointers), and strings and vectors, which are archived as two
+    /// `ArchivedUsize`s and deserialized to three words. This is an upper bound
+    /// for the builtin collections, but fields skipped with
+    /// [`Skip`](crate::with::Skip) and custom wrappers may allocate more.
+    pub max_alloc_bytes: Option<usize>,
+}
+
+/// How many times larger the native form of a collection may be than its
+/// archived form.
+const ALLOC_GROWTH: usize = {
+    const fn growth(native: usize, archived: usize) -> usize {
+        (native + archived - 1) / archived
+    }
+
+    let strings =
+        growth(3 * size_of::<usize>(), 2 * size_of::<ArchivedUsize>());
+    let maps = growth(
+        4 * size_of::<usize>() + 2 * size_of::<u64>(),
+        3 * size_of::<ArchivedUsize>(),
+    );
+    if strings > maps {
+        strings
+    } else {
+        maps
+    }
+};
+
+/// Estimates the number of bytes that deserializing a collection which
+/// occupies `size` bytes in the archive would allocate.
+#[inline]
+fn estimate_alloc_bytes(size: usize) -> usize {
+    size.saturating_mul(ALLOC_GROWTH)
+}
+
+/// The subtree range and maximum subtree depth of an [`ArchiveValidator`].
+#[cfg(feature = "std")]
+pub(super) type SubtreeState = (Range<usize>, Option<NonZeroUsize>);
+
 /// A validator that can verify archives with nonlocal memory.
 #[derive(Debug)]
 pub struct ArchiveValidator {
     subtree_range: Range<usize>,
     max_subtree_depth: Option<NonZeroUsize>,
+    limits: ValidationLimits,
+    total_elements: usize,
+    archived_bytes: usize,
+    alloc_bytes: usize,
+    string_table: Option<Range<usize>>,
 }
 
 // SAFETY: `ArchiveValidator` is safe to send between threads because the
@@ -108,15 +245,169 @@ impl ArchiveValidator {
         bytes: &[u8],
         max_subtree_depth: Option<NonZeroUsize>,
     ) -> Self {
+        Self::with_limits(
+            bytes,
+            ValidationLimits {
+                max_subtree_depth,
+                ..ValidationLimits::default()
+            },
+        )
+    }
+
+    /// Creates a new bounds validator for the given bytes which enforces the
+    /// given resource limits.
+    #[inline]
+    pub fn with_limits(bytes: &[u8], limits: ValidationLimits) -> Self {
         let Range { start, end } = bytes.as_ptr_range();
         Self {
             subtree_range: Range {
                 start: start as usize,
                 end: end as usize,
             },
-            max_subtree_depth,
+            max_subtree_depth: limits.max_subtree_depth,
+            limits,
+            total_elements: 0,
+            archived_bytes: 0,
+            alloc_bytes: 0,
+            string_table: None,
         }
     }
+
+    /// Returns the resource limits of the validator.
+    #[inline]
+    pub fn limits(&self) -> &ValidationLimits {
+        &self.limits
+    }
+
+    /// Returns the total number of collection elements checked so far.
+    #[inline]
+    pub fn total_elements(&self) -> usize {
+        self.total_elements
+    }
+
+    /// Returns the total archived size in bytes of all collections checked so
+    /// far.
+    #[inline]
+    pub fn archived_bytes(&self) -> usize {
+        self.archived_bytes
+    }
+
+    /// Returns the estimated number of bytes that deserializing all
+    /// collections checked so far would allocate.
+    #[inline]
+    pub fn alloc_bytes(&self) -> usize {
+        self.alloc_bytes
+    }
+
+    /// Resets the subtree range and maximum subtree depth to cover all of the
+    /// given bytes again. The collection totals are kept.
+    #[inline]
+    pub(crate) fn reset_subtree(&mut self, bytes: &[u8]) {
+        let Range { start, end } = bytes.as_ptr_range();
+        self.subtree_range = Range {
+            start: start as usize,
+            end: end as usize,
+        };
+        self.max_subtree_depth = self.limits.max_subtree_depth;
+    }
+
+    /// Returns the current subtree range and maximum subtree depth so that
+    /// they can be restored after a failed check.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(super) fn save_subtree(&self) -> SubtreeState {
+        (self.subtree_range.clone(), self.max_subtree_depth)
+    }
+
+    /// Restores a subtree range and maximum subtree depth returned from
+    /// `save_subtree`.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(super) fn restore_subtree(&mut self, state: SubtreeState) {
+        (self.subtree_range, self.max_subtree_depth) = state;
+    }
+
+    /// Returns the current subtree range.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    pub(super) fn subtree_range(&self) -> &Range<usize> {
+        &self.subtree_range
+    }
+
+    /// Creates a validator which can check a sibling subtree of the current
+    /// subtree range independently.
+    ///
+    /// The forked validator starts with no collections checked, and its total
+    /// limits are reduced by the totals checked so far.
+    #[cfg(feature = "parallel")]
+    pub(super) fn fork(&self) -> Self {
+        Self {
+            subtree_range: self.subtree_range.clone(),
+            max_subtree_depth: self.max_subtree_depth,
+            limits: ValidationLimits {
+                max_total_elements: self
+                    .limits
+                    .max_total_elements
+                    .map(|max| max - self.total_elements),
+                max_archived_bytes: self
+                    .limits
+                    .max_archived_bytes
+                    .map(|max| max - self.archived_bytes),
+                max_alloc_bytes: self
+                    .limits
+                    .max_alloc_bytes
+                    .map(|max| max - self.alloc_bytes),
+                ..self.limits
+            },
+            total_elements: 0,
+            archived_bytes: 0,
+            alloc_bytes: 0,
+            string_table: self.string_table.clone(),
+        }
+    }
+
+    /// Joins forked validators back into this one, and moves the start of the
+    /// subtree range to `start`.
+    ///
+    /// Returns `false` without modifying the validator if the combined totals
+    /// of the forks exceed the limits.
+    #[cfg(feature = "parallel")]
+    pub(super) fn join<'a>(
+        &mut self,
+        start: usize,
+        forks: impl IntoIterator<Item = &'a Self>,
+    ) -> bool {
+        let mut total_elements = self.total_elements;
+        let mut archived_bytes = self.archived_bytes;
+        let mut alloc_bytes = self.alloc_bytes;
+        for fork in forks {
+            total_elements = total_elements.saturating_add(fork.total_elements);
+            archived_bytes = archived_bytes.saturating_add(fork.archived_bytes);
+            alloc_bytes = alloc_bytes.saturating_add(fork.alloc_bytes);
+        }
+
+        if self
+            .limits
+            .max_total_elements
+            .is_some_and(|max| total_elements > max)
+            || self
+                .limits
+                .max_archived_bytes
+                .is_some_and(|max| archived_bytes > max)
+            || self
+                .limits
+                .max_alloc_bytes
+                .is_some_and(|max| alloc_bytes > max)
+        {
+            return false;
+        }
+
+        self.subtree_range.start = start;
+        self.total_elements = total_elements;
+        self.archived_bytes = archived_bytes;
+        self.alloc_bytes = alloc_bytes;
+        true
+    }
 }
 
 unsafe impl<E: Error> ArchiveContext<E> for ArchiveValidator {
@@ -196,4 +487,53 @@ unsafe impl<E: Error> ArchiveContext<E> for ArchiveValidator {
         }
         Ok(())
     }
+
+    #[inline]
+    fn check_collection(&mut self, len: usize, size: usize) -> Result<(), E> {
+        if let Some(max) = self.limits.max_collection_len {
+            if len > max {
+                fail!(ArchiveError::ExceededMaximumCollectionLength {
+                    len,
+                    max,
+                });
+            }
+        }
+
+        self.total_elements = self.total_elements.saturating_add(len);
+        if let Some(max) = self.limits.max_total_elements {
+            if self.total_elements > max {
+                fail!(ArchiveError::ExceededMaximumTotalElements { max });
+            }
+        }
+
+        self.archived_bytes = self.archived_bytes.saturating_add(size);
+        if let Some(max) = self.limits.max_archived_bytes {
+            if self.archived_bytes > max {
+                fail!(ArchiveError::ExceededMaximumArchivedBytes { max });
+            }
+        }
+
+        self.alloc_bytes =
+            self.alloc_bytes.saturating_add(estimate_alloc_bytes(size));
+        if let Some(max) = self.limits.max_alloc_bytes {
+            if self.alloc_bytes > max {
+                fail!(ArchiveError::ExceededMaximumAllocBytes { max });
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn string_table(&self) -> Option<Range<usize>> {
+        self.string_table.clone()
+    }
+
+    #[inline]
+    unsafe fn set_string_table(
+        &mut self,
+        table: Option<Range<usize>>,
+    ) -> Option<Range<usize>> {
+        mem::replace(&mut self.string_table, table)
+    }
 }
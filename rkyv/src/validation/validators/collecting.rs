@@ -0,0 +1,250 @@
+//! A validator which collects every error instead of stopping at the first.
+
+use core::{alloc::Layout, any::TypeId, ops::Range};
+use std::vec::Vec;
+
+use rancor::Strategy;
+
+use crate::validation::{
+    path::{PathSegment, ValidationError},
+    validators::{ArchiveValidator, SharedValidator, ValidationLimits},
+    ArchiveContext, CheckFields, SharedContext, SharedPointerKind,
+};
+
+/// A validator which records the errors of invalid collection elements and
+/// continues checking the remaining elements.
+///
+/// When an element fails to validate, the subtree range of the validator is
+/// restored to what it was before the element was checked, and checking
+/// continues with the next element. The errors of all of the elements are
+/// returned together, along with the indices of the elements which were
+/// intact. The fields of a struct which implements [`CheckFields`] can be
+/// checked the same way with [`check_fields`](Self::check_fields). Use
+/// [`check_with_report`] to turn them into a [`ValidationReport`].
+///
+/// Validation after the first error is only useful for diagnostics. Subtrees
+/// claimed by invalid elements are released, so later elements may claim the
+/// same memory. Shared pointers registered by invalid elements also stay
+/// registered, so other pointers to them are not checked again.
+///
+/// [`check_with_report`]: crate::validation::util::check_with_report
+/// [`ValidationReport`]: crate::validation::path::ValidationReport
+#[derive(Debug)]
+pub struct CollectingValidator {
+    archive: ArchiveValidator,
+    shared: SharedValidator,
+    // Errors of elements which were recorded by `check_element` and have not
+    // been collected by the enclosing `check_elements` yet.
+    pending: Vec<ValidationError>,
+    element_depth: usize,
+}
+
+impl CollectingValidator {
+    /// Creates a new collecting validator from a byte range.
+    #[inline]
+    pub fn new(bytes: &[u8]) -> Self {
+        Self::with_limits(bytes, ValidationLimits::default())
+    }
+
+    /// Creates a new collecting validator from a byte range which enforces
+    /// the given resource limits.
+    #[inline]
+    pub fn with_limits(bytes: &[u8], limits: ValidationLimits) -> Self {
+        Self {
+            archive: ArchiveValidator::with_limits(bytes, limits),
+            shared: SharedValidator::new(),
+            pending: Vec::new(),
+            element_depth: 0,
+        }
+    }
+
+    /// Checks the fields of a struct one at a time, and continues checking
+    /// the remaining fields after an invalid one.
+    ///
+    /// The errors of all of the invalid fields are returned together, along
+    /// with the names of the fields which were intact.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be aligned and point to enough bytes to represent a `T`.
+    pub unsafe fn check_fields<T>(
+        &mut self,
+        value: *const T,
+    ) -> Result<(), ValidationError>
+    where
+        T: CheckFields<Strategy<Self, ValidationError>> + ?Sized,
+    {
+        let mut errors = Vec::new();
+        let mut intact = Vec::new();
+        for (index, name) in T::FIELDS.iter().enumerate() {
+            let result = self.try_check(|this| unsafe {
+                T::check_field(value, index, Strategy::wrap(this))
+            });
+            match result {
+                Ok(()) => intact.push(PathSegment::Field(name)),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::collected(errors, intact))
+        }
+    }
+
+    // Runs `check`, and restores the subtree range and shared pointers being
+    // checked if it fails.
+    fn try_check(
+        &mut self,
+        check: impl FnOnce(&mut Self) -> Result<(), ValidationError>,
+    ) -> Result<(), ValidationError> {
+        let state = self.archive.save_subtree();
+        let depth = self.shared.checking_depth();
+        let result = check(self);
+        if result.is_err() {
+            self.archive.restore_subtree(state);
+            self.shared.truncate_checking(depth);
+        }
+        result
+    }
+}
+
+unsafe impl ArchiveContext<ValidationError> for CollectingValidator {
+    #[inline]
+    fn check_subtree_ptr(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+    ) -> Result<(), ValidationError> {
+        self.archive.check_subtree_ptr(ptr, layout)
+    }
+
+    #[inline]
+    unsafe fn push_prefix_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, ValidationError> {
+        unsafe { self.archive.push_prefix_subtree_range(root, end) }
+    }
+
+    #[inline]
+    unsafe fn push_suffix_subtree_range(
+        &mut self,
+        start: *const u8,
+        root: *const u8,
+    ) -> Result<Range<usize>, ValidationError> {
+        unsafe { self.archive.push_suffix_subtree_range(start, root) }
+    }
+
+    #[inline]
+    unsafe fn pop_subtree_range(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), ValidationError> {
+        unsafe { self.archive.pop_subtree_range(range) }
+    }
+
+    #[inline]
+    fn check_collection(
+        &mut self,
+        len: usize,
+        size: usize,
+    ) -> Result<(), ValidationError> {
+        self.archive.check_collection(len, size)
+    }
+
+    fn check_elements(
+        &mut self,
+        len: usize,
+        check: &(dyn Fn(&mut Self, Range<usize>) -> Result<(), ValidationError>
+              + Sync),
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+        let mut intact = Vec::new();
+        self.element_depth += 1;
+        for index in 0..len {
+            let count = errors.len();
+            let pending = self.pending.len();
+            let result = self.try_check(|this| check(this, index..index + 1));
+            // Errors recorded by `check_element` belong to this index too
+            errors.extend(self.pending.drain(pending..));
+            match result {
+                Ok(()) if errors.len() == count => {
+                    intact.push(PathSegment::Index(index));
+                }
+                Ok(()) => (),
+                Err(error) => errors.push(error),
+            }
+        }
+        self.element_depth -= 1;
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::collected(errors, intact))
+        }
+    }
+
+    fn check_element(
+        &mut self,
+        check: &mut dyn FnMut(&mut Self) -> Result<(), ValidationError>,
+    ) -> Result<(), ValidationError> {
+        if self.element_depth == 0 {
+            return check(self);
+        }
+
+        if let Err(error) = self.try_check(check) {
+            self.pending.push(error);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn string_table(&self) -> Option<Range<usize>> {
+        ArchiveContext::<ValidationError>::string_table(&self.archive)
+    }
+
+    #[inline]
+    unsafe fn set_string_table(
+        &mut self,
+        table: Option<Range<usize>>,
+    ) -> Option<Range<usize>> {
+        ArchiveContext::<ValidationError>::set_string_table(
+            &mut self.archive,
+            table,
+        )
+    }
+}
+
+impl SharedContext<ValidationError> for CollectingValidator {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<bool, ValidationError> {
+        self.shared.register_shared_ptr(address, type_id)
+    }
+
+    #[inline]
+    fn register_shared_value(
+        &mut self,
+        address: usize,
+        size: usize,
+        type_id: TypeId,
+        kind: SharedPointerKind,
+    ) -> Result<bool, ValidationError> {
+        self.shared
+            .register_shared_value(address, size, type_id, kind)
+    }
+
+    #[inline]
+    fn finish_shared_value(
+        &mut self,
+        address: usize,
+    ) -> Result<(), ValidationError> {
+        self.shared.finish_shared_value(address)
+    }
+}
@@ -1,14 +1,22 @@
 //! Validators that can check archived types.
 
 mod archive;
+#[cfg(feature = "std")]
+mod collecting;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod shared;
 
 use core::{any::TypeId, ops::Range};
 
 pub use archive::*;
+#[cfg(feature = "std")]
+pub use collecting::*;
+#[cfg(feature = "parallel")]
+pub use parallel::*;
 pub use shared::*;
 
-use crate::validation::{ArchiveContext, SharedContext};
+use crate::validation::{ArchiveContext, SharedContext, SharedPointerKind};
 
 /// The default validator.
 #[derive(Debug)]
@@ -27,6 +35,16 @@ impl DefaultValidator {
         }
     }
 
+    /// Creates a new validator from a byte range which enforces the given
+    /// resource limits.
+    #[inline]
+    pub fn with_limits(bytes: &[u8], limits: ValidationLimits) -> Self {
+        Self {
+            archive: ArchiveValidator::with_limits(bytes, limits),
+            shared: SharedValidator::new(),
+        }
+    }
+
     /// Create a new validator from a byte range with specific capacity.
     #[inline]
     pub fn with_capacity(bytes: &[u8], capacity: usize) -> Self {
@@ -35,6 +53,39 @@ impl DefaultValidator {
             shared: SharedValidator::with_capacity(capacity),
         }
     }
+
+    /// Returns the archive validator, which holds the collection totals.
+    #[inline]
+    pub fn archive(&self) -> &ArchiveValidator {
+        &self.archive
+    }
+
+    /// Returns the shared memory validator, which holds the shared values found
+    /// during validation.
+    #[inline]
+    pub fn shared(&self) -> &SharedValidator {
+        &self.shared
+    }
+
+    /// Returns a mutable reference to the shared memory validator.
+    #[inline]
+    pub fn shared_mut(&mut self) -> &mut SharedValidator {
+        &mut self.shared
+    }
+    /// Prepares the validator to check another subtree of the given bytes.
+    ///
+    /// The collection totals and shared values found so far are kept, so
+    /// limits and shared pointers are enforced across every subtree checked.
+    #[inline]
+    pub(crate) fn reset_subtree(&mut self, bytes: &[u8]) {
+        self.archive.reset_subtree(bytes);
+    }
+
+    /// Forgets the shared values found so far after a check failed.
+    #[inline]
+    pub(crate) fn clear_shared(&mut self) {
+        self.shared.clear();
+    }
 }
 
 unsafe impl<E> ArchiveContext<E> for DefaultValidator
@@ -75,6 +126,23 @@ where
     ) -> Result<(), E> {
         unsafe { self.archive.pop_subtree_range(range) }
     }
+    #[inline]
+    fn check_collection(&mut self, len: usize, size: usize) -> Result<(), E> {
+        self.archive.check_collection(len, size)
+    }
+
+    #[inline]
+    fn string_table(&self) -> Option<Range<usize>> {
+        ArchiveContext::<E>::string_table(&self.archive)
+    }
+
+    #[inline]
+    unsafe fn set_string_table(
+        &mut self,
+        table: Option<Range<usize>>,
+    ) -> Option<Range<usize>> {
+        ArchiveContext::<E>::set_string_table(&mut self.archive, table)
+    }
 }
 
 impl<E> SharedContext<E> for DefaultValidator
@@ -89,4 +157,21 @@ where
     ) -> Result<bool, E> {
         self.shared.register_shared_ptr(address, type_id)
     }
+
+    #[inline]
+    fn register_shared_value(
+        &mut self,
+        address: usize,
+        size: usize,
+        type_id: TypeId,
+        kind: SharedPointerKind,
+    ) -> Result<bool, E> {
+        self.shared
+            .register_shared_value(address, size, type_id, kind)
+    }
+
+    #[inline]
+    fn finish_shared_value(&mut self, address: usize) -> Result<(), E> {
+        self.shared.finish_shared_value(address)
+    }
 }
@@ -0,0 +1,286 @@
+//! A validator which checks the elements of large collections in parallel.
+
+use core::{alloc::Layout, any::TypeId, fmt, ops::Range};
+
+use bytecheck::rancor::Error;
+use rancor::fail;
+use rayon::prelude::*;
+
+use crate::validation::{
+    validators::{ArchiveValidator, SharedValidator, ValidationLimits},
+    ArchiveContext, SharedContext, SharedPointerKind,
+};
+
+/// The default minimum number of elements a collection must have to be
+/// checked in parallel.
+pub const DEFAULT_MIN_PARALLEL_LEN: usize = 1024;
+
+/// The number of ranges to split each collection into per thread.
+const RANGES_PER_THREAD: usize = 4;
+
+/// The error returned when a fork finds a shared pointer.
+///
+/// It is never returned from validation because the elements are checked again
+/// sequentially.
+#[derive(Debug)]
+struct SharedPointerInFork;
+
+impl fmt::Display for SharedPointerInFork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "found a shared pointer while checking elements in parallel"
+        )
+    }
+}
+
+impl std::error::Error for SharedPointerInFork {}
+
+/// A validator which checks the elements of large collections in parallel.
+///
+/// When a collection with at least [`min_parallel_len`] elements is checked,
+/// its elements are split into ranges which are checked on the rayon thread
+/// pool. Each range is checked with a fork of the validator that claims
+/// subtrees from the same subtree range. After all of the ranges have been
+/// checked, the validator verifies that the subtrees claimed by each range
+/// come after those claimed by the previous range and do not overlap, exactly
+/// as if the elements had been checked in order.
+///
+/// Shared values are tracked by a [`SharedValidator`], which diagnoses cycles
+/// and overlapping shared values from the order that shared pointers are found
+/// in. So forks don't register shared pointers, and instead fail as soon as
+/// they find one. If the forks claimed overlapping subtrees, found
+/// a shared pointer, or any of them failed, the collection is checked again
+/// sequentially. This makes the result of validation identical to that of
+/// [`DefaultValidator`], including which error is returned. Collections which
+/// contain shared pointers are effectively always checked sequentially.
+///
+/// [`min_parallel_len`]: ParallelValidator::min_parallel_len
+/// [`DefaultValidator`]: crate::validation::validators::DefaultValidator
+#[derive(Debug)]
+pub struct ParallelValidator {
+    archive: ArchiveValidator,
+    shared: SharedValidator,
+    min_parallel_len: usize,
+    min_address: usize,
+    sequential: bool,
+    forked: bool,
+}
+
+impl ParallelValidator {
+    /// Creates a new parallel validator from a byte range.
+    #[inline]
+    pub fn new(bytes: &[u8]) -> Self {
+        Self::with_limits(bytes, ValidationLimits::default())
+    }
+
+    /// Creates a new parallel validator from a byte range which enforces the
+    /// given resource limits.
+    #[inline]
+    pub fn with_limits(bytes: &[u8], limits: ValidationLimits) -> Self {
+        Self {
+            archive: ArchiveValidator::with_limits(bytes, limits),
+            shared: SharedValidator::new(),
+            min_parallel_len: DEFAULT_MIN_PARALLEL_LEN,
+            min_address: usize::MAX,
+            sequential: false,
+            forked: false,
+        }
+    }
+
+    /// Returns the shared memory validator, which holds the shared values found
+    /// during validation.
+    #[inline]
+    pub fn shared(&self) -> &SharedValidator {
+        &self.shared
+    }
+
+    /// Returns a mutable reference to the shared memory validator.
+    #[inline]
+    pub fn shared_mut(&mut self) -> &mut SharedValidator {
+        &mut self.shared
+    }
+
+    /// Returns the minimum number of elements a collection must have to be
+    /// checked in parallel.
+    #[inline]
+    pub fn min_parallel_len(&self) -> usize {
+        self.min_parallel_len
+    }
+
+    /// Sets the minimum number of elements a collection must have to be
+    /// checked in parallel.
+    ///
+    /// Checking small collections in parallel is usually slower than checking
+    /// them sequentially. The default is [`DEFAULT_MIN_PARALLEL_LEN`].
+    #[inline]
+    pub fn set_min_parallel_len(&mut self, min_parallel_len: usize) {
+        self.min_parallel_len = min_parallel_len.max(1);
+    }
+
+    fn fork(&self) -> Self {
+        Self {
+            archive: self.archive.fork(),
+            shared: SharedValidator::new(),
+            min_parallel_len: self.min_parallel_len,
+            min_address: usize::MAX,
+            sequential: false,
+            forked: true,
+        }
+    }
+
+    /// Joins the given forks back into this validator.
+    ///
+    /// Returns `false` without modifying the validator if checking the forks
+    /// in order would not have produced the same result.
+    fn join(&mut self, forks: &[Self]) -> bool {
+        // Each fork may only touch memory after the subtrees claimed by the
+        // forks before it.
+        let mut start = self.archive.subtree_range().start;
+        let mut min_address = self.min_address;
+        for fork in forks.iter() {
+            if fork.min_address < start {
+                return false;
+            }
+            start = start.max(fork.archive.subtree_range().start);
+            min_address = min_address.min(fork.min_address);
+        }
+
+        if !self
+            .archive
+            .join(start, forks.iter().map(|fork| &fork.archive))
+        {
+            return false;
+        }
+        self.min_address = min_address;
+
+        true
+    }
+}
+
+unsafe impl<E: Error + Send> ArchiveContext<E> for ParallelValidator {
+    #[inline]
+    fn check_subtree_ptr(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+    ) -> Result<(), E> {
+        self.min_address = self.min_address.min(ptr as usize);
+        self.archive.check_subtree_ptr(ptr, layout)
+    }
+
+    #[inline]
+    unsafe fn push_prefix_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, E> {
+        self.min_address = self.min_address.min(root as usize);
+        unsafe { self.archive.push_prefix_subtree_range(root, end) }
+    }
+
+    #[inline]
+    unsafe fn push_suffix_subtree_range(
+        &mut self,
+        start: *const u8,
+        root: *const u8,
+    ) -> Result<Range<usize>, E> {
+        self.min_address = self.min_address.min(start as usize);
+        unsafe { self.archive.push_suffix_subtree_range(start, root) }
+    }
+
+    #[inline]
+    unsafe fn pop_subtree_range(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), E> {
+        unsafe { self.archive.pop_subtree_range(range) }
+    }
+
+    #[inline]
+    fn check_collection(&mut self, len: usize, size: usize) -> Result<(), E> {
+        self.archive.check_collection(len, size)
+    }
+
+    fn check_elements(
+        &mut self,
+        len: usize,
+        check: &(dyn Fn(&mut Self, Range<usize>) -> Result<(), E> + Sync),
+    ) -> Result<(), E> {
+        if self.sequential || len < self.min_parallel_len {
+            return check(self, 0..len);
+        }
+
+        let range_count =
+            (rayon::current_num_threads() * RANGES_PER_THREAD).min(len);
+        let range_len = len.div_ceil(range_count);
+        let mut forks = (0..len.div_ceil(range_len))
+            .map(|_| self.fork())
+            .collect::<Vec<_>>();
+
+        let result =
+            forks.par_iter_mut().enumerate().try_for_each(|(i, fork)| {
+                let start = i * range_len;
+                check(fork, start..usize::min(start + range_len, len))
+            });
+        if result.is_ok() && self.join(&forks) {
+            return Ok(());
+        }
+
+        // Check the elements again in order to get the same result as
+        // sequential validation. Nested collections are also checked
+        // sequentially so that malformed archives can't make validation take
+        // exponential time.
+        self.sequential = true;
+        let result = check(self, 0..len);
+        self.sequential = false;
+        result
+    }
+
+    #[inline]
+    fn string_table(&self) -> Option<Range<usize>> {
+        ArchiveContext::<E>::string_table(&self.archive)
+    }
+
+    #[inline]
+    unsafe fn set_string_table(
+        &mut self,
+        table: Option<Range<usize>>,
+    ) -> Option<Range<usize>> {
+        ArchiveContext::<E>::set_string_table(&mut self.archive, table)
+    }
+}
+
+impl<E: Error> SharedContext<E> for ParallelValidator {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<bool, E> {
+        if self.forked {
+            fail!(SharedPointerInFork);
+        }
+        self.shared.register_shared_ptr(address, type_id)
+    }
+
+    #[inline]
+    fn register_shared_value(
+        &mut self,
+        address: usize,
+        size: usize,
+        type_id: TypeId,
+        kind: SharedPointerKind,
+    ) -> Result<bool, E> {
+        if self.forked {
+            fail!(SharedPointerInFork);
+        }
+        self.shared
+            .register_shared_value(address, size, type_id, kind)
+    }
+
+    #[inline]
+    fn finish_shared_value(&mut self, address: usize) -> Result<(), E> {
+        self.shared.finish_shared_value(address)
+    }
+}
@@ -1,6 +1,7 @@
 //! Validators add validation capabilities by wrapping and extending basic
 //! validators.
 
+use alloc::{collections::BTreeMap, vec::Vec};
 use core::{any::TypeId, fmt};
 #[cfg(feature = "std")]
 use std::collections::HashMap;
@@ -10,7 +11,7 @@ use bytecheck::rancor::Error;
 use hashbrown::HashMap;
 use rancor::fail;
 
-use crate::validation::SharedContext;
+use crate::validation::{SharedContext, SharedPointerKind};
 
 /// Errors that can occur when checking shared memory.
 #[derive(Debug)]
@@ -22,6 +23,25 @@ pub enum SharedError {
         /// The current type that the location is checked as
         current: TypeId,
     },
+    /// Two shared values overlap, or two pointers to the same shared value
+    /// disagree on its size
+    Overlap {
+        /// The address of the shared value being registered
+        address: usize,
+        /// The size of the shared value being registered
+        size: usize,
+        /// The address of the shared value it overlaps
+        other_address: usize,
+        /// The size of the shared value it overlaps
+        other_size: usize,
+    },
+    /// A shared value is reachable from itself
+    Cycle {
+        /// The address of the shared value
+        address: usize,
+        /// Whether the cycle contains a weak pointer
+        weak: bool,
+    },
 }
 
 impl fmt::Display for SharedError {
@@ -33,6 +53,26 @@ impl fmt::Display for SharedError {
                 "the same memory region has been claimed as two different types ({:?} and {:?})",
                 previous, current
             ),
+            SharedError::Overlap {
+                address,
+                size,
+                other_address,
+                other_size,
+            } => write!(
+                f,
+                "shared value at {:#x} with size {} overlaps the shared value at {:#x} with size {}",
+                address, size, other_address, other_size,
+            ),
+            SharedError::Cycle { address, weak } => write!(
+                f,
+                "shared value at {:#x} is reachable from itself through {}",
+                address,
+                if *weak {
+                    "a weak pointer"
+                } else {
+                    "only strong pointers"
+                },
+            ),
         }
     }
 }
@@ -41,15 +81,117 @@ impl fmt::Display for SharedError {
 impl std::error::Error for SharedError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            SharedError::TypeMismatch { .. } => None,
+            SharedError::TypeMismatch { .. }
+            | SharedError::Overlap { .. }
+            | SharedError::Cycle { .. } => None,
         }
     }
 }
 
+/// A shared value found during validation.
+#[derive(Clone, Copy, Debug)]
+pub struct SharedNode {
+    address: usize,
+    size: usize,
+    type_id: TypeId,
+    strong_count: usize,
+    weak_count: usize,
+    checking: bool,
+}
+
+impl SharedNode {
+    /// Returns the address of the shared value.
+    #[inline]
+    pub fn address(&self) -> usize {
+        self.address
+    }
+
+    /// Returns the size of the shared value in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the type ID of the pointers to the shared value.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the number of strong pointers to the shared value which were
+    /// checked.
+    #[inline]
+    pub fn strong_count(&self) -> usize {
+        self.strong_count
+    }
+
+    /// Returns the number of weak pointers to the shared value which were
+    /// checked.
+    #[inline]
+    pub fn weak_count(&self) -> usize {
+        self.weak_count
+    }
+}
+
+/// A pointer to a shared value found during validation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SharedEdge {
+    from: Option<usize>,
+    to: usize,
+    kind: SharedPointerKind,
+}
+
+impl SharedEdge {
+    /// Returns the address of the shared value which contains the pointer, or
+    /// `None` if the pointer is not inside of a shared value.
+    #[inline]
+    pub fn from(&self) -> Option<usize> {
+        self.from
+    }
+
+    /// Returns the address of the shared value which is pointed to.
+    #[inline]
+    pub fn to(&self) -> usize {
+        self.to
+    }
+
+    /// Returns the kind of the pointer.
+    #[inline]
+    pub fn kind(&self) -> SharedPointerKind {
+        self.kind
+    }
+}
+
 /// A validator that can verify shared memory.
-#[derive(Debug, Default)]
+///
+/// Besides checking that every pointer to a shared value has the same type,
+/// the validator rejects:
+///
+/// - Shared values which overlap other shared values.
+/// - Shared values which are reachable from themselves through only strong
+///   pointers. These can't be created by serializing, and deserializing them
+///   would never terminate.
+/// - Shared values which are reachable from themselves through weak pointers,
+///   if weak cycles are [forbidden](SharedValidator::set_allow_weak_cycles).
+///
+/// The shared values found are kept along with their reference counts, and the
+/// pointers between them can optionally be
+/// [recorded](SharedValidator::set_record_edges) to inspect the shape of the
+/// shared pointer graph.
+#[derive(Debug)]
 pub struct SharedValidator {
-    shared: HashMap<usize, TypeId>,
+    shared: HashMap<usize, SharedNode>,
+    regions: BTreeMap<usize, usize>,
+    checking: Vec<(usize, SharedPointerKind)>,
+    edges: Option<Vec<SharedEdge>>,
+    allow_weak_cycles: bool,
+}
+
+impl Default for SharedValidator {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SharedValidator {
@@ -60,6 +202,10 @@ impl SharedValidator {
             // TODO: consider deferring this to avoid the overhead of
             // constructing
             shared: HashMap::new(),
+            regions: BTreeMap::new(),
+            checking: Vec::new(),
+            edges: None,
+            allow_weak_cycles: true,
         }
     }
 
@@ -68,16 +214,116 @@ impl SharedValidator {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             shared: HashMap::with_capacity(capacity),
+            ..Self::new()
         }
     }
-}
 
-impl<E: Error> SharedContext<E> for SharedValidator {
+    /// Returns whether shared values may be reachable from themselves through
+    /// weak pointers.
     #[inline]
-    fn register_shared_ptr(
+    pub fn allow_weak_cycles(&self) -> bool {
+        self.allow_weak_cycles
+    }
+
+    /// Sets whether shared values may be reachable from themselves through
+    /// weak pointers.
+    ///
+    /// Weak cycles are allowed by default.
+    #[inline]
+    pub fn set_allow_weak_cycles(&mut self, allow_weak_cycles: bool) {
+        self.allow_weak_cycles = allow_weak_cycles;
+    }
+
+    /// Returns whether the pointers to shared values are recorded.
+    #[inline]
+    pub fn record_edges(&self) -> bool {
+        self.edges.is_some()
+    }
+
+    /// Sets whether the pointers to shared values are recorded.
+    ///
+    /// Pointers are not recorded by default. Disabling recording discards the
+    /// pointers recorded so far.
+    #[inline]
+    pub fn set_record_edges(&mut self, record_edges: bool) {
+        if record_edges != self.edges.is_some() {
+            self.edges = record_edges.then(Vec::new);
+        }
+    }
+
+    /// Returns the shared value at the given address, if one was found.
+    #[inline]
+    pub fn node(&self, address: usize) -> Option<&SharedNode> {
+        self.shared.get(&address)
+    }
+
+    /// Returns an iterator over the shared values found, in address order.
+    #[inline]
+    pub fn nodes(&self) -> impl Iterator<Item = &SharedNode> + '_ {
+        self.regions.keys().map(|address| &self.shared[address])
+    }
+
+    /// Returns the pointers to shared values which were recorded, in the order
+    /// they were checked.
+    ///
+    /// This is empty unless recording was enabled with
+    /// [`set_record_edges`](SharedValidator::set_record_edges).
+    #[inline]
+    pub fn edges(&self) -> &[SharedEdge] {
+        self.edges.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the number of shared values which are being checked.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(super) fn checking_depth(&self) -> usize {
+        self.checking.len()
+    }
+
+    /// Stops checking the shared values above the given depth.
+    ///
+    /// This is used to recover after one of them failed to validate.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(super) fn truncate_checking(&mut self, depth: usize) {
+        if depth < self.checking.len() {
+            self.finish(self.checking[depth].0);
+        }
+    }
+
+    /// Forgets all of the shared values found so far.
+    ///
+    /// This is used to recover after a shared value failed to validate, since
+    /// it may have been registered without being completely checked.
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.shared.clear();
+        self.regions.clear();
+        self.checking.clear();
+        if let Some(edges) = self.edges.as_mut() {
+            edges.clear();
+        }
+    }
+
+    fn finish(&mut self, address: usize) {
+        if let Some(index) =
+            self.checking.iter().rposition(|(a, _)| *a == address)
+        {
+            for (address, _) in self.checking.drain(index..) {
+                if let Some(node) = self.shared.get_mut(&address) {
+                    node.checking = false;
+                }
+            }
+        }
+    }
+
+    fn register<E: Error>(
         &mut self,
         address: usize,
+        size: usize,
         type_id: TypeId,
+        kind: SharedPointerKind,
+        track: bool,
     ) -> Result<bool, E> {
         #[cfg(feature = "std")]
         use std::collections::hash_map::Entry;
@@ -85,22 +331,141 @@ impl<E: Error> SharedContext<E> for SharedValidator {
         #[cfg(not(feature = "std"))]
         use hashbrown::hash_map::Entry;
 
-        match self.shared.entry(address) {
-            Entry::Occupied(previous_type_entry) => {
-                let previous_type_id = previous_type_entry.get();
-                if previous_type_id != &type_id {
+        let from = self.checking.last().map(|(address, _)| *address);
+        let is_new = match self.shared.entry(address) {
+            Entry::Occupied(mut entry) => {
+                let node = entry.get_mut();
+                if node.type_id != type_id {
                     fail!(SharedError::TypeMismatch {
-                        previous: *previous_type_id,
+                        previous: node.type_id,
                         current: type_id,
-                    })
-                } else {
-                    Ok(false)
+                    });
+                }
+                // Pointers to the same value must agree on its size, or the
+                // larger ones could reach past the checked value.
+                if track && node.size != size {
+                    fail!(SharedError::Overlap {
+                        address,
+                        size,
+                        other_address: address,
+                        other_size: node.size,
+                    });
                 }
+                if node.checking {
+                    check_cycle(
+                        &self.checking,
+                        address,
+                        kind,
+                        self.allow_weak_cycles,
+                    )?;
+                }
+                match kind {
+                    SharedPointerKind::Strong => node.strong_count += 1,
+                    SharedPointerKind::Weak => node.weak_count += 1,
+                }
+                false
             }
-            Entry::Vacant(ent) => {
-                ent.insert(type_id);
-                Ok(true)
+            Entry::Vacant(entry) => {
+                check_overlap(&self.regions, address, size)?;
+                entry.insert(SharedNode {
+                    address,
+                    size,
+                    type_id,
+                    strong_count: (kind == SharedPointerKind::Strong) as usize,
+                    weak_count: (kind == SharedPointerKind::Weak) as usize,
+                    checking: track,
+                });
+                self.regions.insert(address, size);
+                if track {
+                    self.checking.push((address, kind));
+                }
+                true
             }
+        };
+
+        if let Some(edges) = self.edges.as_mut() {
+            edges.push(SharedEdge {
+                from,
+                to: address,
+                kind,
+            });
         }
+
+        Ok(is_new)
+    }
+}
+
+fn check_cycle<E: Error>(
+    checking: &[(usize, SharedPointerKind)],
+    address: usize,
+    kind: SharedPointerKind,
+    allow_weak_cycles: bool,
+) -> Result<(), E> {
+    let index = checking
+        .iter()
+        .rposition(|(a, _)| *a == address)
+        .unwrap_or(0);
+    // The cycle is made of the pointers to each of the values being checked
+    // after this one, plus the new pointer.
+    let weak = kind == SharedPointerKind::Weak
+        || checking[index + 1..]
+            .iter()
+            .any(|(_, kind)| *kind == SharedPointerKind::Weak);
+    if !weak || !allow_weak_cycles {
+        fail!(SharedError::Cycle { address, weak });
+    }
+    Ok(())
+}
+
+fn check_overlap<E: Error>(
+    regions: &BTreeMap<usize, usize>,
+    address: usize,
+    size: usize,
+) -> Result<(), E> {
+    let end = address.saturating_add(size);
+    // Registered values never overlap, so only the values immediately before
+    // and after this one need to be checked.
+    let before = regions.range(..address).next_back();
+    let after = regions.range(address..).next();
+    for (&other_address, &other_size) in before.into_iter().chain(after) {
+        if other_address < end
+            && address < other_address.saturating_add(other_size)
+        {
+            fail!(SharedError::Overlap {
+                address,
+                size,
+                other_address,
+                other_size,
+            });
+        }
+    }
+    Ok(())
+}
+
+impl<E: Error> SharedContext<E> for SharedValidator {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<bool, E> {
+        self.register(address, 0, type_id, SharedPointerKind::Strong, false)
+    }
+
+    #[inline]
+    fn register_shared_value(
+        &mut self,
+        address: usize,
+        size: usize,
+        type_id: TypeId,
+        kind: SharedPointerKind,
+    ) -> Result<bool, E> {
+        self.register(address, size, type_id, kind, true)
+    }
+
+    #[inline]
+    fn finish_shared_value(&mut self, address: usize) -> Result<(), E> {
+        self.finish(address);
+        Ok(())
     }
 }
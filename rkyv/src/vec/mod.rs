@@ -1,6 +1,6 @@
 //! An archived version of `Vec`.
 
-// mod raw;
+mod raw;
 
 use core::{
     borrow::Borrow,
@@ -18,7 +18,7 @@ use crate::{
     Archive, Portable, RelPtr, Serialize, SerializeUnsized,
 };
 
-// pub use self::raw::*;
+pub use self::raw::*;
 
 /// An archived [`Vec`].
 ///
@@ -331,13 +331,18 @@ pub struct VecResolver {
 
 #[cfg(feature = "bytecheck")]
 mod verify {
+    use core::{mem::size_of, ops::Range};
+
     use bytecheck::{
-        rancor::{Error, Fallible},
+        rancor::{Error, Fallible, ResultExt as _, Strategy},
         CheckBytes, Verify,
     };
 
     use crate::{
-        validation::{ArchiveContext, ArchiveContextExt},
+        lazy::Checked,
+        validation::{
+            path::IndexCheckContext, ArchiveContext, ArchiveContextExt,
+        },
         vec::ArchivedVec,
     };
 
@@ -348,18 +353,39 @@ mod verify {
         C::Error: Error,
     {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let len = self.len.to_native() as usize;
+            context
+                .check_collection(len, size_of::<T>().saturating_mul(len))?;
+
             let ptr = unsafe {
                 context.bounds_check_subtree_base_offset::<[T]>(
                     self.ptr.base(),
                     self.ptr.offset(),
-                    self.len.to_native() as usize,
+                    len,
                 )?
             };
 
             let range = unsafe { context.push_prefix_subtree(ptr)? };
-            unsafe {
-                <[T]>::check_bytes(ptr, context)?;
-            }
+            // The base address is captured as an integer so that elements can
+            // be checked from multiple threads.
+            let base = ptr.cast::<T>() as usize;
+            context.check_elements(
+                len,
+                &|context: &mut C, indices: Range<usize>| {
+                    for index in indices {
+                        let element = unsafe { (base as *const T).add(index) };
+                        unsafe {
+                            T::check_bytes(element, context).with_trace(
+                                || IndexCheckContext {
+                                    index,
+                                    address: element as usize,
+                                },
+                            )?;
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
             unsafe {
                 context.pop_subtree_range(range)?;
             }
@@ -367,4 +393,74 @@ mod verify {
             Ok(())
         }
     }
+
+    impl<T> ArchivedVec<T> {
+        /// Checks that the elements of the archived vec are in bounds and
+        /// aligned, and returns a pointer to them. The elements are not
+        /// claimed or checked.
+        ///
+        /// # Safety
+        ///
+        /// `self` must be inside the archive the context was created for.
+        pub(crate) unsafe fn bounds_check_elements<C>(
+            &self,
+            context: &mut C,
+        ) -> Result<*const [T], C::Error>
+        where
+            C: Fallible + ArchiveContext + ?Sized,
+            C::Error: Error,
+        {
+            unsafe {
+                context.bounds_check_subtree_base_offset::<[T]>(
+                    self.ptr.base(),
+                    self.ptr.offset(),
+                    self.len(),
+                )
+            }
+        }
+    }
+
+    impl<'a, T> Checked<'a, ArchivedVec<T>> {
+        /// Returns the number of elements in the archived vec.
+        #[inline]
+        pub fn len(&self) -> usize {
+            // SAFETY: The fields of an `ArchivedVec` are valid for any bytes.
+            unsafe { (*self.as_ptr()).len() }
+        }
+
+        /// Returns whether the archived vec is empty.
+        #[inline]
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// Returns the element at the given index.
+        ///
+        /// Only the pointer to the elements is checked.
+        pub fn get<E: Error>(
+            self,
+            index: usize,
+        ) -> Result<Option<Checked<'a, T>>, E> {
+            let len = self.len();
+            if index >= len {
+                return Ok(None);
+            }
+
+            let access = self.access();
+            // SAFETY: The fields of an `ArchivedVec` are valid for any bytes.
+            let vec = unsafe { &*self.as_ptr() };
+            let ptr = access.with_context(|validator| unsafe {
+                Strategy::<_, E>::wrap(validator)
+                    .bounds_check_subtree_base_offset::<[T]>(
+                        vec.ptr.base(),
+                        vec.ptr.offset(),
+                        len,
+                    )
+            })?;
+
+            Ok(Some(unsafe {
+                Checked::new(access, ptr.cast::<T>().add(index))
+            }))
+        }
+    }
 }
@@ -1,21 +1,76 @@
-use crate::{
-    ser::Serializer,
-    vec::{ArchivedVec, VecResolver},
-    Archive, Serialize,
-};
 use core::{
     borrow::Borrow,
-    cmp,
+    cmp, fmt, hash,
     ops::{Deref, Index, IndexMut},
     pin::Pin,
     slice::SliceIndex,
 };
 
-/// An archived [`Vec`].
+use rancor::Fallible;
+
+use crate::{
+    rend::{
+        f32_be, f32_le, f64_be, f64_le, i128_be, i128_le, i16_be, i16_le,
+        i32_be, i32_le, i64_be, i64_le, u128_be, u128_le, u16_be, u16_le,
+        u32_be, u32_le, u64_be, u64_le,
+    },
+    ser::Writer,
+    vec::{ArchivedVec, VecResolver},
+    Archive, Portable, Serialize,
+};
+
+/// A type which can be copied into and out of a [`RawArchivedVec`] as raw
+/// bytes.
+///
+/// This is implemented for bytes, integers, floats, and arrays of them.
+/// Multibyte integers and floats only implement it when the target has the
+/// same endianness as the archive.
+///
+/// # Safety
+///
+/// - Every bit pattern must be a valid value of the type.
+/// - The type must not contain any padding bytes.
+/// - The archived type must have the same size and representation as the type
+///   itself. It may have a smaller alignment.
+pub unsafe trait RawCopy: Archive {}
+
+macro_rules! impl_raw_copy {
+    ($($type:ty),* $(,)?) => {
+        $(unsafe impl RawCopy for $type {})*
+    };
+}
+
+impl_raw_copy!((), i8, u8);
+
+impl_raw_copy!(
+    i16_le, i32_le, i64_le, i128_le, u16_le, u32_le, u64_le, u128_le, f32_le,
+    f64_le, i16_be, i32_be, i64_be, i128_be, u16_be, u32_be, u64_be, u128_be,
+    f32_be, f64_be,
+);
+
+// Multibyte integers and floats have the same representation as their archived
+// types if the target matches the archive endianness
+#[cfg(any(
+    all(target_endian = "little", not(feature = "big_endian")),
+    all(target_endian = "big", feature = "big_endian"),
+))]
+impl_raw_copy!(i16, i32, i64, i128, u16, u32, u64, u128, f32, f64);
+
+unsafe impl<T: RawCopy, const N: usize> RawCopy for [T; N] {}
+
+/// An archived [`Vec`] of raw data.
 ///
-/// This uses a [`RelPtr`](crate::rel_ptr::RelPtr) to a `[T]` under the hood. Unlike
-/// [`ArchivedString`](crate::string::ArchivedString), it does not have an inline representation.
-#[derive(Hash, Eq, Debug)]
+/// This has the same representation as an [`ArchivedVec`], but its elements
+/// are copied directly when serializing and are not checked when validating.
+/// Validation only checks that the elements are in bounds and properly
+/// aligned.
+///
+/// This is produced by serializing a `Vec` with [`Raw`](crate::with::Raw).
+/// It's intended for large arrays of types that don't need to be validated,
+/// like bytes, integers, and floats. The elements must implement [`RawCopy`]
+/// so that every bit pattern is a valid element.
+#[derive(Portable)]
+#[archive(crate)]
 #[repr(transparent)]
 pub struct RawArchivedVec<T> {
     inner: ArchivedVec<T>,
@@ -55,7 +110,8 @@ impl<T> RawArchivedVec<T> {
     // This method can go away once pinned slices have indexing support
     // https://github.com/rust-lang/rust/pull/78370
 
-    /// Gets the element at the given index to this archived vec as a pinned mutable reference.
+    /// Gets the element at the given index to this archived vec as a pinned
+    /// mutable reference.
     #[inline]
     pub fn index_pin<I>(
         self: Pin<&mut Self>,
@@ -84,25 +140,27 @@ impl<T> RawArchivedVec<T> {
         ArchivedVec::resolve_from_slice(slice, pos, resolver, out.cast());
     }
 
-    /// Serializes an archived `Vec` from a given slice by directly copying bytes.
+    /// Serializes an archived `Vec` from a given slice by directly copying
+    /// bytes.
     ///
     /// # Safety
     ///
-    /// The type being serialized must be copy-safe. Copy-safe types must be trivially copyable
-    /// (have the same archived and unarchived representations) and contain no padding bytes. In
-    /// situations where copying uninitialized bytes the output is acceptable, this function may be
-    /// used with types that contain padding bytes.
+    /// The type being serialized must be copy-safe. Copy-safe types must be
+    /// trivially copyable (have the same archived and unarchived
+    /// representations) and contain no padding bytes. In situations where
+    /// copying uninitialized bytes the output is acceptable, this function may
+    /// be used with types that contain padding bytes.
     ///
-    /// Additionally, the type being serialized must not require any validation. All bit patterns
-    /// must represent valid values.
+    /// Additionally, the type being serialized must not require any
+    /// validation. All bit patterns must represent valid values.
     #[inline]
     pub unsafe fn serialize_copy_from_slice<U, S>(
         slice: &[U],
         serializer: &mut S,
-    ) -> Result<VecResolver, E>
+    ) -> Result<VecResolver, S::Error>
     where
         U: Serialize<S, Archived = T>,
-        S: Serializer + ?Sized,
+        S: Fallible + Writer + ?Sized,
     {
         ArchivedVec::serialize_copy_from_slice(slice, serializer)
     }
@@ -122,6 +180,12 @@ impl<T> Borrow<[T]> for RawArchivedVec<T> {
     }
 }
 
+impl<T: fmt::Debug> fmt::Debug for RawArchivedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
 impl<T> Deref for RawArchivedVec<T> {
     type Target = [T];
 
@@ -131,6 +195,15 @@ impl<T> Deref for RawArchivedVec<T> {
     }
 }
 
+impl<T: Eq> Eq for RawArchivedVec<T> {}
+
+impl<T: hash::Hash> hash::Hash for RawArchivedVec<T> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state)
+    }
+}
+
 impl<T, I: SliceIndex<[T]>> Index<I> for RawArchivedVec<T> {
     type Output = <[T] as Index<I>>::Output;
 
@@ -161,7 +234,7 @@ impl<T: PartialEq<U>, U, const N: usize> PartialEq<RawArchivedVec<T>>
 {
     #[inline]
     fn eq(&self, other: &RawArchivedVec<T>) -> bool {
-        self.eq(&other.inner)
+        other.eq(self)
     }
 }
 
@@ -175,7 +248,7 @@ impl<T: PartialEq<U>, U> PartialEq<[U]> for RawArchivedVec<T> {
 impl<T: PartialEq<U>, U> PartialEq<RawArchivedVec<U>> for [T] {
     #[inline]
     fn eq(&self, other: &RawArchivedVec<U>) -> bool {
-        self.eq(&other.inner)
+        self.eq(other.as_slice())
     }
 }
 
@@ -203,34 +276,66 @@ impl<T: PartialOrd> PartialOrd<[T]> for RawArchivedVec<T> {
 impl<T: PartialOrd> PartialOrd<RawArchivedVec<T>> for [T] {
     #[inline]
     fn partial_cmp(&self, other: &RawArchivedVec<T>) -> Option<cmp::Ordering> {
-        self.partial_cmp(&other.inner)
+        self.partial_cmp(other.as_slice())
     }
 }
 
 #[cfg(feature = "bytecheck")]
-const _: () = {
-    use crate::validation::{owned::CheckOwnedPointerError, ArchiveContext};
-    use bytecheck::{CheckBytes, Error};
+mod verify {
+    use core::{mem::size_of, ptr::addr_of};
 
-    impl<T, C> CheckBytes<C> for RawArchivedVec<T>
+    use bytecheck::{
+        rancor::{Error, Fallible},
+        CheckBytes,
+    };
+
+    use super::{RawArchivedVec, RawCopy};
+    use crate::{
+        primitive::ArchivedUsize,
+        validation::{ArchiveContext, ArchiveContextExt},
+        vec::ArchivedVec,
+        RelPtr,
+    };
+
+    unsafe impl<T, C> CheckBytes<C> for RawArchivedVec<T>
     where
-        T: CheckBytes<C>,
-        C: ArchiveContext + ?Sized,
+        T: RawCopy,
+        C: Fallible + ArchiveContext + ?Sized,
         C::Error: Error,
     {
-        type Error = CheckOwnedPointerError<[T], C>;
-
-        #[inline]
-        unsafe fn check_bytes<'a>(
+        unsafe fn check_bytes(
             value: *const Self,
             context: &mut C,
-        ) -> Result<&'a Self, Self::Error> {
-            ArchivedVec::<T>::check_bytes_with::<C, _>(
-                value.cast(),
-                context,
-                |_, _| Ok(()),
-            )?;
-            Ok(&*value)
+        ) -> Result<(), C::Error> {
+            // `RawArchivedVec` is a transparent wrapper around `ArchivedVec`.
+            // Its fields are checked without checking the elements.
+            let inner = value.cast::<ArchivedVec<T>>();
+            unsafe {
+                RelPtr::<T>::check_bytes(addr_of!((*inner).ptr), context)?;
+                ArchivedUsize::check_bytes(addr_of!((*inner).len), context)?;
+            }
+
+            let inner = unsafe { &*inner };
+            let len = inner.len();
+            context
+                .check_collection(len, size_of::<T>().saturating_mul(len))?;
+
+            let ptr = unsafe {
+                context.bounds_check_subtree_base_offset::<[T]>(
+                    inner.ptr.base(),
+                    inner.ptr.offset(),
+                    len,
+                )?
+            };
+
+            // The elements are claimed so that nothing else can overlap them,
+            // but they are not checked.
+            let range = unsafe { context.push_prefix_subtree(ptr)? };
+            unsafe {
+                context.pop_subtree_range(range)?;
+            }
+
+            Ok(())
         }
     }
-};
+}
@@ -5,7 +5,7 @@ use alloc::{
     collections::{BTreeMap, BTreeSet},
     vec::Vec,
 };
-use core::marker::PhantomData;
+use core::{hash::Hash, marker::PhantomData};
 #[cfg(feature = "std")]
 use std::{
     borrow::Cow,
@@ -13,18 +13,22 @@ use std::{
     collections::{BTreeMap, BTreeSet},
 };
 
-use rancor::Fallible;
+use rancor::{Error, Fallible};
 
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
-    collections::util::Entry,
+    collections::{
+        perfect_hash_map::{ArchivedPerfectHashMap, PerfectHashMapResolver},
+        sorted_vec_map::{ArchivedSortedVecMap, SortedVecMapResolver},
+        util::Entry,
+    },
     niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
     ser::{Allocator, Writer},
     string::{ArchivedString, StringResolver},
-    vec::{ArchivedVec, VecResolver},
+    vec::{ArchivedVec, RawArchivedVec, RawCopy, VecResolver},
     with::{
-        ArchiveWith, AsOwned, AsVec, BoxedInline, CopyOptimize,
-        DeserializeWith, Map, Niche, SerializeWith, With,
+        ArchiveWith, AsOwned, AsPerfectHash, AsSortedVec, AsVec, BoxedInline,
+        CopyOptimize, DeserializeWith, Map, Niche, Raw, SerializeWith, With,
     },
     Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized,
     Serialize, SerializeUnsized,
@@ -400,6 +404,138 @@ where
     }
 }
 
+// AsPerfectHash
+
+impl<K, V> ArchiveWith<BTreeMap<K, V>> for AsPerfectHash
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+{
+    type Archived = ArchivedPerfectHashMap<K::Archived, V::Archived>;
+    type Resolver = PerfectHashMapResolver;
+
+    unsafe fn resolve_with(
+        field: &BTreeMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedPerfectHashMap::resolve_from_len(
+            field.len(),
+            pos,
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, V>, S> for AsPerfectHash
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Error,
+{
+    fn serialize_with(
+        field: &BTreeMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedPerfectHashMap::<K::Archived, V::Archived>::serialize_from_iter(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D>
+    DeserializeWith<
+        ArchivedPerfectHashMap<K::Archived, V::Archived>,
+        BTreeMap<K, V>,
+        D,
+    > for AsPerfectHash
+where
+    K: Archive + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedPerfectHashMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, V>, D::Error> {
+        let mut result = BTreeMap::new();
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// AsSortedVec
+
+impl<K: Archive, V: Archive> ArchiveWith<BTreeMap<K, V>> for AsSortedVec {
+    type Archived = ArchivedSortedVecMap<K::Archived, V::Archived>;
+    type Resolver = SortedVecMapResolver;
+
+    unsafe fn resolve_with(
+        field: &BTreeMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSortedVecMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, V>, S> for AsSortedVec
+where
+    K: Serialize<S>,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &BTreeMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedVecMap::<K::Archived, V::Archived>::serialize_from_sorted_iter(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D>
+    DeserializeWith<
+        ArchivedSortedVecMap<K::Archived, V::Archived>,
+        BTreeMap<K, V>,
+        D,
+    > for AsSortedVec
+where
+    K: Archive + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedVecMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, V>, D::Error> {
+        let mut result = BTreeMap::new();
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
 // Niche
 
 impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Option<Box<T>>> for Niche
@@ -625,72 +761,71 @@ where
     }
 }
 
-// // Raw
-
-// impl<T: Archive> ArchiveWith<Vec<T>> for Raw {
-//     type Archived = RawArchivedVec<T::Archived>;
-//     type Resolver = VecResolver;
-
-//     unsafe fn resolve_with(
-//         field: &Vec<T>,
-//         pos: usize,
-//         resolver: Self::Resolver,
-//         out: *mut Self::Archived,
-//     ) {
-//         RawArchivedVec::resolve_from_slice(
-//             field.as_slice(),
-//             pos,
-//             resolver,
-//             out,
-//         );
-//     }
-// }
-
-// impl<T, S> SerializeWith<Vec<T>, S> for Raw
-// where
-//     T: Serialize<S>,
-//     S: Serializer + ?Sized,
-// {
-//     fn serialize_with(
-//         field: &Vec<T>,
-//         serializer: &mut S,
-//     ) -> Result<Self::Resolver, S::Error> {
-//         use core::mem::size_of;
-
-//         // Basic debug assert that T and T::Archived are at least the same
-// size         debug_assert_eq!(size_of::<T>(), size_of::<T::Archived>());
-
-//         unsafe {
-//             ArchivedVec::serialize_copy_from_slice(field.as_slice(),
-// serializer)         }
-//     }
-// }
-
-// impl<T, D> DeserializeWith<RawArchivedVec<T::Archived>, Vec<T>, D> for Raw
-// where
-//     T: Archive,
-//     T::Archived: Deserialize<T, D>,
-//     D: Fallible + ?Sized,
-// {
-//     fn deserialize_with(
-//         field: &RawArchivedVec<T::Archived>,
-//         _: &mut D,
-//     ) -> Result<Vec<T>, E> {
-//         use core::{mem::size_of, ptr::copy_nonoverlapping};
-
-//         // Basic debug assert that T and T::Archived are at least the same
-// size         debug_assert_eq!(size_of::<T>(), size_of::<T::Archived>());
-
-//         let mut result = Vec::with_capacity(field.len());
-//         unsafe {
-//             copy_nonoverlapping(
-//                 field.as_ptr().cast(),
-//                 result.as_mut_ptr(),
-//                 field.len(),
-//             );
-//             result.set_len(field.len());
-//         }
-
-//         Ok(result)
-//     }
-// }
+// Raw
+
+impl<T: RawCopy> ArchiveWith<Vec<T>> for Raw {
+    type Archived = RawArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        RawArchivedVec::resolve_from_slice(
+            field.as_slice(),
+            pos,
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for Raw
+where
+    T: RawCopy + Serialize<S>,
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        // SAFETY: `RawCopy` types have the same representation archived and
+        // unarchived, contain no padding, and accept any bit pattern.
+        unsafe {
+            RawArchivedVec::serialize_copy_from_slice(
+                field.as_slice(),
+                serializer,
+            )
+        }
+    }
+}
+
+impl<T, D> DeserializeWith<RawArchivedVec<T::Archived>, Vec<T>, D> for Raw
+where
+    T: RawCopy,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &RawArchivedVec<T::Archived>,
+        _: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        use core::{mem::size_of, ptr::copy_nonoverlapping};
+
+        let mut result = Vec::<T>::with_capacity(field.len());
+        // SAFETY: `RawCopy` types have the same representation archived and
+        // unarchived. The archived elements may be less aligned, so they are
+        // copied as bytes.
+        unsafe {
+            copy_nonoverlapping(
+                field.as_ptr().cast::<u8>(),
+                result.as_mut_ptr().cast::<u8>(),
+                field.len() * size_of::<T>(),
+            );
+            result.set_len(field.len());
+        }
+
+        Ok(result)
+    }
+}
@@ -10,17 +10,21 @@ use rancor::Fallible;
 
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
+    lazy::{ArchivedLazy, LazyResolver},
     niche::option_nonzero::{
         ArchivedOptionNonZeroIsize, ArchivedOptionNonZeroUsize,
     },
     option::ArchivedOption,
     primitive::{FixedNonZeroIsize, FixedNonZeroUsize},
+    string::symbol::{ArchivedSymbol, SymbolResolver},
     with::{
-        ArchiveWith, Boxed, BoxedInline, DeserializeWith, Inline, Map, Niche,
-        SerializeWith, Skip, Unsafe,
+        ArchiveWith, Boxed, BoxedInline, DeserializeWith, Inline, Intern, Lazy,
+        Map, Niche, SerializeWith, Skip, Unsafe,
     },
     Archive, ArchiveUnsized, Deserialize, Serialize, SerializeUnsized,
 };
+#[cfg(feature = "alloc")]
+use crate::{ser::Writer, string::symbol::StringPool};
 
 // Map for Options
 
@@ -213,6 +217,81 @@ where
     }
 }
 
+// Lazy
+
+impl<F: ArchiveUnsized + ?Sized> ArchiveWith<F> for Lazy {
+    type Archived = ArchivedLazy<F::Archived>;
+    type Resolver = LazyResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &F,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedLazy::resolve_from_ref(field, pos, resolver, out);
+    }
+}
+
+impl<F: SerializeUnsized<S> + ?Sized, S: Fallible + ?Sized> SerializeWith<F, S>
+    for Lazy
+{
+    #[inline]
+    fn serialize_with(
+        field: &F,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedLazy::serialize_from_ref(field, serializer)
+    }
+}
+
+// Intern
+
+impl<F: AsRef<str>> ArchiveWith<F> for Intern {
+    type Archived = ArchivedSymbol;
+    type Resolver = SymbolResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        _: &F,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSymbol::emplace(pos, resolver, out);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<F, S> SerializeWith<F, S> for Intern
+where
+    F: AsRef<str>,
+    S: Fallible + Writer + StringPool + ?Sized,
+{
+    #[inline]
+    fn serialize_with(
+        field: &F,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSymbol::serialize_from_str(field.as_ref(), serializer)
+    }
+}
+
+impl<F, D> DeserializeWith<ArchivedSymbol, F, D> for Intern
+where
+    F: for<'a> From<&'a str>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedSymbol,
+        _: &mut D,
+    ) -> Result<F, D::Error> {
+        Ok(F::from(field.as_str()))
+    }
+}
+
 // Niche
 
 impl ArchiveWith<Option<NonZeroIsize>> for Niche {
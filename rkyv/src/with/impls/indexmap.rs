@@ -0,0 +1,78 @@
+use core::hash::{BuildHasher, Hash};
+
+use indexmap::IndexMap;
+use rancor::Fallible;
+
+use crate::{
+    collections::sorted_vec_map::{ArchivedSortedVecMap, SortedVecMapResolver},
+    ser::{Allocator, Writer},
+    with::{ArchiveWith, AsSortedVec, DeserializeWith, SerializeWith},
+    Archive, Deserialize, Serialize,
+};
+
+// AsSortedVec
+
+impl<K, V, B> ArchiveWith<IndexMap<K, V, B>> for AsSortedVec
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedSortedVecMap<K::Archived, V::Archived>;
+    type Resolver = SortedVecMapResolver;
+
+    unsafe fn resolve_with(
+        field: &IndexMap<K, V, B>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSortedVecMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, B, S> SerializeWith<IndexMap<K, V, B>, S> for AsSortedVec
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &IndexMap<K, V, B>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedVecMap::<K::Archived, V::Archived>::serialize_from_iter(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, B, D>
+    DeserializeWith<
+        ArchivedSortedVecMap<K::Archived, V::Archived>,
+        IndexMap<K, V, B>,
+        D,
+    > for AsSortedVec
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    B: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedVecMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<IndexMap<K, V, B>, D::Error> {
+        let mut result =
+            IndexMap::with_capacity_and_hasher(field.len(), B::default());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
@@ -2,5 +2,7 @@
 mod alloc;
 mod atomic;
 mod core;
+#[cfg(all(feature = "alloc", feature = "indexmap"))]
+mod indexmap;
 #[cfg(feature = "std")]
 mod std;
@@ -1,4 +1,7 @@
-use core::{hash::Hash, str::FromStr};
+use core::{
+    hash::{BuildHasher, Hash},
+    str::FromStr,
+};
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsString,
@@ -10,14 +13,23 @@ use std::{
 use rancor::{Error, Fallible, OptionExt, ResultExt};
 
 use crate::{
-    collections::util::Entry,
+    collections::{
+        perfect_hash_map::{ArchivedPerfectHashMap, PerfectHashMapResolver},
+        sorted_vec_map::{ArchivedSortedVecMap, SortedVecMapResolver},
+        swiss_table::{
+            ArchivedHashMap, ArchivedHashSet, HashMapResolver, HashSetResolver,
+        },
+        util::Entry,
+    },
+    hash::{ArchivedHasher, HashSeed},
     ser::{Allocator, Writer},
     string::{ArchivedString, StringResolver},
     time::ArchivedDuration,
     vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsString, AsVec, DeserializeWith, Immutable, InvalidStr,
-        Lock, Poisoned, SerializeWith, UnixTimestamp,
+        ArchiveWith, AsPerfectHash, AsSortedVec, AsString, AsVec,
+        DeserializeWith, HashWith, Immutable, InvalidStr, Lock, Poisoned,
+        SerializeWith, UnixTimestamp,
     },
     Archive, Deserialize, Serialize, SerializeUnsized,
 };
@@ -353,6 +365,296 @@ where
     }
 }
 
+// AsPerfectHash
+
+impl<K, V> ArchiveWith<HashMap<K, V>> for AsPerfectHash
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+{
+    type Archived = ArchivedPerfectHashMap<K::Archived, V::Archived>;
+    type Resolver = PerfectHashMapResolver;
+
+    unsafe fn resolve_with(
+        field: &HashMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedPerfectHashMap::resolve_from_len(
+            field.len(),
+            pos,
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<K, V, S> SerializeWith<HashMap<K, V>, S> for AsPerfectHash
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Error,
+{
+    fn serialize_with(
+        field: &HashMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedPerfectHashMap::<K::Archived, V::Archived>::serialize_from_iter(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D>
+    DeserializeWith<
+        ArchivedPerfectHashMap<K::Archived, V::Archived>,
+        HashMap<K, V>,
+        D,
+    > for AsPerfectHash
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedPerfectHashMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V>, D::Error> {
+        let mut result = HashMap::with_capacity(field.len());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// AsSortedVec
+
+impl<K, V, B> ArchiveWith<HashMap<K, V, B>> for AsSortedVec
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedSortedVecMap<K::Archived, V::Archived>;
+    type Resolver = SortedVecMapResolver;
+
+    unsafe fn resolve_with(
+        field: &HashMap<K, V, B>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSortedVecMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, B, S> SerializeWith<HashMap<K, V, B>, S> for AsSortedVec
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &HashMap<K, V, B>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedVecMap::<K::Archived, V::Archived>::serialize_from_iter(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, B, D>
+    DeserializeWith<
+        ArchivedSortedVecMap<K::Archived, V::Archived>,
+        HashMap<K, V, B>,
+        D,
+    > for AsSortedVec
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    B: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedVecMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, B>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.len(), B::default());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// HashWith
+
+impl<K, V, B, H, R> ArchiveWith<HashMap<K, V, B>> for HashWith<H, R>
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    H: ArchivedHasher,
+{
+    type Archived = ArchivedHashMap<K::Archived, V::Archived, H>;
+    type Resolver = HashMapResolver<H::Seed>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &HashMap<K, V, B>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedHashMap::resolve_from_len(
+            field.len(),
+            (7, 8),
+            pos,
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<K, V, B, H, R, S> SerializeWith<HashMap<K, V, B>, S> for HashWith<H, R>
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    H: ArchivedHasher,
+    R: HashSeed<H>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Error,
+{
+    #[inline]
+    fn serialize_with(
+        field: &HashMap<K, V, B>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedHashMap::<K::Archived, V::Archived, H>::serialize_from_iter_with_seed(
+            field.iter(),
+            (7, 8),
+            R::seed(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, B, H, R, D>
+    DeserializeWith<
+        ArchivedHashMap<K::Archived, V::Archived, H>,
+        HashMap<K, V, B>,
+        D,
+    > for HashWith<H, R>
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    B: Default + BuildHasher,
+    H: ArchivedHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedHashMap<K::Archived, V::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, B>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.len(), B::default());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+impl<K, B, H, R> ArchiveWith<HashSet<K, B>> for HashWith<H, R>
+where
+    K: Archive + Hash + Eq,
+    H: ArchivedHasher,
+{
+    type Archived = ArchivedHashSet<K::Archived, H>;
+    type Resolver = HashSetResolver<H::Seed>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &HashSet<K, B>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedHashSet::resolve_from_len(
+            field.len(),
+            (7, 8),
+            pos,
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<K, B, H, R, S> SerializeWith<HashSet<K, B>, S> for HashWith<H, R>
+where
+    K: Serialize<S> + Hash + Eq,
+    H: ArchivedHasher,
+    R: HashSeed<H>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Error,
+{
+    #[inline]
+    fn serialize_with(
+        field: &HashSet<K, B>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedHashSet::<K::Archived, H>::serialize_from_iter_with_seed(
+            field.iter(),
+            (7, 8),
+            R::seed(),
+            serializer,
+        )
+    }
+}
+
+impl<K, B, H, R, D>
+    DeserializeWith<ArchivedHashSet<K::Archived, H>, HashSet<K, B>, D>
+    for HashWith<H, R>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    B: Default + BuildHasher,
+    H: ArchivedHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedHashSet<K::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashSet<K, B>, D::Error> {
+        let mut result =
+            HashSet::with_capacity_and_hasher(field.len(), B::default());
+        for key in field.iter() {
+            result.insert(key.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
 // UnixTimestamp
 
 impl ArchiveWith<SystemTime> for UnixTimestamp {
@@ -9,7 +9,7 @@ use core::{fmt, marker::PhantomData, mem::transmute, ops::Deref};
 
 use rancor::Fallible;
 
-use crate::{Archive, Deserialize, Portable, Serialize};
+use crate::{hash::DefaultSeed, Archive, Deserialize, Portable, Serialize};
 
 // TODO: Gate unsafe wrappers behind Unsafe.
 
@@ -429,6 +429,34 @@ pub struct Boxed;
 #[derive(Debug)]
 pub struct BoxedInline;
 
+/// A wrapper that serializes a field out-of-line and only validates it when it
+/// is accessed.
+///
+/// Fields serialized with `Lazy` are archived as an
+/// [`ArchivedLazy`](crate::lazy::ArchivedLazy). Validating the containing
+/// archive only checks that the field's pointer is in bounds, and the field is
+/// validated the first time it is accessed through a
+/// [`LazyAccess`](crate::lazy::LazyAccess).
+///
+/// Fields serialized with `Lazy` cannot be deserialized directly because they
+/// may not have been validated.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::Lazy};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(Lazy)]
+///     a: Vec<i32>,
+///     #[with(Lazy)]
+///     b: str,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Lazy;
+
 /// A wrapper that attempts to convert a type to and from UTF-8.
 ///
 /// Types like `OsString` and `PathBuf` aren't guaranteed to be encoded as
@@ -468,6 +496,37 @@ impl fmt::Display for InvalidStr {
 #[cfg(feature = "std")]
 impl ::std::error::Error for InvalidStr {}
 
+/// A wrapper that interns a string in the string table of the archive.
+///
+/// This archives a string as an
+/// [`ArchivedSymbol`](crate::string::symbol::ArchivedSymbol), which points to
+/// an entry of the string table and derefs to a `str`. The string table is
+/// written by wrapping the root of the archive in
+/// [`Interned`](crate::string::symbol::Interned), and serializing a field with
+/// `Intern` outside of an `Interned` fails. It can be used with any type that
+/// implements `AsRef<str>` and can be created from a `&str`, like `String`,
+/// `Box<str>`, and `Rc<str>`.
+///
+/// The serializer must be a
+/// [`StringPool`](crate::string::symbol::StringPool). The default serializer
+/// writes each distinct string to the string table once.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::Intern};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(Intern)]
+///     name: String,
+///     #[with(Intern)]
+///     kind: Box<str>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Intern;
+
 /// A wrapper that locks a lock and serializes the value immutably.
 ///
 /// This wrapper can panic under very specific circumstances when:
@@ -556,6 +615,100 @@ pub struct AsOwned;
 #[derive(Debug)]
 pub struct AsVec;
 
+/// A wrapper that serializes a map as an
+/// [`ArchivedPerfectHashMap`](crate::collections::perfect_hash_map::ArchivedPerfectHashMap).
+///
+/// A minimal perfect hash function is built for the keys of the map when it is
+/// serialized. Looking up a key in the archived map takes exactly one hash and
+/// one key comparison, and the archived map has no empty slots. Building the
+/// perfect hash function makes serializing slower than serializing a regular
+/// hash map, so this is best suited for lookup tables which are written once
+/// and read many times.
+///
+/// Serialization fails if two keys have the same hash.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{Archive, with::AsPerfectHash};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AsPerfectHash)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsPerfectHash;
+
+/// A wrapper that serializes a map as an
+/// [`ArchivedSortedVecMap`](crate::collections::sorted_vec_map::ArchivedSortedVecMap).
+///
+/// The keys and values of the map are sorted by key and stored in two
+/// contiguous vectors, without any of the space overhead of a B-tree or hash
+/// table. Lookups are binary searches. This can be used with `BTreeMap`,
+/// `HashMap`, and `IndexMap`. An `IndexMap` deserialized from a sorted vec map
+/// is in sorted order instead of insertion order.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::{BTreeMap, HashMap};
+/// use rkyv::{Archive, with::AsSortedVec};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AsSortedVec)]
+///     names: BTreeMap<u32, String>,
+///     #[with(AsSortedVec)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsSortedVec;
+
+/// A wrapper that archives a hash map or set with a specific hasher.
+///
+/// By default, hash maps and sets are archived with
+/// [`FxHasher64`](crate::hash::FxHasher64), which is fast but makes it easy to
+/// find keys which collide. When keys may be chosen by an attacker, archiving
+/// them with a keyed hasher like [`SipHasher13`](crate::hash::SipHasher13)
+/// protects lookups from hash flooding. The seed of the hasher is chosen by `S`
+/// when the map is serialized and stored in the archived map. By default, `S`
+/// is [`DefaultSeed`](crate::hash::DefaultSeed), which always chooses the same
+/// seed so that archives are reproducible. Use
+/// [`RandomSeed`](crate::hash::RandomSeed) to opt into a new random seed for
+/// every map, or implement [`HashSeed`](crate::hash::HashSeed) to supply a seed
+/// of your own.
+///
+/// The hasher can also be chosen for every `std` hash map and set in a type
+/// with `#[archive(hasher = ...)]`, and their seed with
+/// `#[archive(hash_seed = ...)]`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::{HashMap, HashSet};
+/// use rkyv::{
+///     Archive,
+///     hash::{RandomSeed, SeededFxHasher64, SipHasher13},
+///     with::HashWith,
+/// };
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(HashWith<SipHasher13, RandomSeed>)]
+///     values: HashMap<String, u32>,
+///     #[with(HashWith<SeededFxHasher64>)]
+///     names: HashSet<String>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct HashWith<H, S = DefaultSeed> {
+    _phantom: PhantomData<(H, S)>,
+}
+
 /// A wrapper that niches some type combinations.
 ///
 /// A common type combination is `Option<Box<T>>`. By using a null pointer, the
@@ -659,22 +812,12 @@ impl ::std::error::Error for UnixTimestampError {}
 /// A wrapper that provides an optimized bulk data array. This is primarily
 /// intended for large amounts of raw data, like bytes, floats, or integers.
 ///
-/// This wrapper can be used with containers like `Vec`, but care must be taken
-/// to ensure that they contain copy-safe types. Copy-safe types must be
-/// trivially copyable (have the same archived and unarchived representations)
-/// and contain no padding bytes. In situations where copying uninitialized
-/// bytes the output is acceptable, this wrapper may be used with containers of
-/// types that contain padding bytes.
-///
-/// Unlike [`CopyOptimize`], this wrapper will also skip validation for its
-/// elements. If the elements of the container can have any invalid bit patterns
-/// (e.g. `char`, `bool`, complex containers, etc.), then using `Raw` in an
-/// insecure setting can lead to undefined behavior. Take great caution!
-///
-/// # Safety
-///
-/// Using this wrapper with containers containing non-copy-safe types or types
-/// that require validation may result in undefined behavior.
+/// This wrapper can be used with `Vec`s of [`RawCopy`](crate::vec::RawCopy)
+/// types. Their elements are copied directly into and out of the archive, and
+/// validation only checks that the elements are in bounds and properly
+/// aligned. `RawCopy` types accept any bit pattern and have the same archived
+/// and unarchived representations, so skipping the per-element checks is
+/// sound.
 ///
 /// # Example
 ///
@@ -689,6 +832,28 @@ impl ::std::error::Error for UnixTimestampError {}
 ///     vertices: Vec<[f32; 3]>,
 /// }
 /// ```
+///
+/// Types with invalid bit patterns or which own memory are rejected:
+///
+/// ```compile_fail
+/// use rkyv::{Archive, with::Raw};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(Raw)]
+///     strings: Vec<String>,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use rkyv::{Archive, with::Raw};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(Raw)]
+///     flags: Vec<bool>,
+/// }
+/// ```
 #[derive(Debug)]
 pub struct Raw;
 
@@ -52,8 +52,8 @@ fn derive_archive_impl(
     let where_clause = where_clause.unwrap();
 
     let rkyv_path = attributes.rkyv_path();
-    let with_ty = make_with_ty(&rkyv_path);
-    let with_cast = make_with_cast(&rkyv_path);
+    let with_ty = make_with_ty(attributes);
+    let with_cast = make_with_cast(attributes);
 
     let derive_check_bytes = if attributes.check_bytes.is_some() {
         let path = quote!(#rkyv_path::bytecheck).to_string();
@@ -272,6 +272,73 @@ fn derive_archive_impl(
                         None
                     };
 
+                    let check_fields_impl = if attributes.check_bytes.is_some()
+                        && attributes.archive_as.is_none()
+                    {
+                        let mut check_fields_generics = generics.clone();
+                        check_fields_generics.params.push(parse_quote! {
+                            __C: #rkyv_path::rancor::Fallible + ?Sized
+                        });
+                        let (check_fields_impl_generics, _, _) =
+                            check_fields_generics.split_for_impl();
+
+                        let mut check_fields_where = archive_where.clone();
+                        for field in fields.named.iter().filter(is_not_omitted)
+                        {
+                            let ty = with_ty(field).unwrap();
+                            check_fields_where.predicates.push(parse_quote! {
+                                Archived<#ty>: #rkyv_path::bytecheck::CheckBytes<__C>
+                            });
+                        }
+                        check_fields_where.predicates.push(parse_quote! {
+                            <__C as #rkyv_path::rancor::Fallible>::Error: #rkyv_path::rancor::Trace
+                        });
+
+                        let field_names = fields.named.iter().map(|f| {
+                            let field = &f.ident;
+                            quote! { ::core::stringify!(#field) }
+                        });
+                        let field_checks =
+                            fields.named.iter().enumerate().map(|(i, f)| {
+                                let field = &f.ident;
+                                let ty = with_ty(f).unwrap();
+                                quote! {
+                                    #i => <Archived<#ty> as #rkyv_path::bytecheck::CheckBytes<__C>>::check_bytes(
+                                        ::core::ptr::addr_of!((*value).#field),
+                                        context,
+                                    ).map_err(|e| {
+                                        <<__C as #rkyv_path::rancor::Fallible>::Error as #rkyv_path::rancor::Trace>::trace(
+                                            e,
+                                            #rkyv_path::bytecheck::StructCheckContext {
+                                                struct_name: ::core::stringify!(#archived_name),
+                                                field_name: ::core::stringify!(#field),
+                                            },
+                                        )
+                                    })
+                                }
+                            });
+
+                        Some(quote! {
+                            // SAFETY: Each field is checked with its `CheckBytes` impl, which is the same way that the derived `CheckBytes` impl checks it.
+                            unsafe impl #check_fields_impl_generics #rkyv_path::validation::CheckFields<__C> for #archived_name #ty_generics #check_fields_where {
+                                const FIELDS: &'static [&'static str] = &[#(#field_names,)*];
+
+                                unsafe fn check_field(
+                                    value: *const Self,
+                                    index: usize,
+                                    context: &mut __C,
+                                ) -> ::core::result::Result<(), <__C as #rkyv_path::rancor::Fallible>::Error> {
+                                    match index {
+                                        #(#field_checks,)*
+                                        _ => ::core::result::Result::Ok(()),
+                                    }
+                                }
+                            }
+                        })
+                    } else {
+                        None
+                    };
+
                     (
                         quote! {
                             #archived_def
@@ -298,6 +365,7 @@ fn derive_archive_impl(
                             #partial_eq_impl
                             #partial_ord_impl
                             #copy_safe_impl
+                            #check_fields_impl
                         },
                     )
                 }
@@ -2,7 +2,7 @@ use quote::ToTokens;
 use syn::{
     meta::ParseNestedMeta, parenthesized, parse::Parse, parse_quote,
     punctuated::Punctuated, AttrStyle, DeriveInput, Error, Ident, LitStr, Meta,
-    Path, Token, WherePredicate,
+    Path, Token, Type, WherePredicate,
 };
 
 fn try_set_attribute<T: ToTokens>(
@@ -33,6 +33,8 @@ pub struct Attributes {
     pub deserialize_bounds: Option<Punctuated<WherePredicate, Token![,]>>,
     pub check_bytes: Option<Path>,
     pub copy_safe: Option<Path>,
+    pub hasher: Option<Type>,
+    pub hash_seed: Option<Type>,
     rkyv_path: Option<Path>,
 }
 
@@ -97,6 +99,18 @@ impl Attributes {
                 meta.value()?.parse()?,
                 "resolver",
             )
+        } else if meta.path.is_ident("hasher") {
+            try_set_attribute(
+                &mut self.hasher,
+                meta.value()?.parse()?,
+                "hasher",
+            )
+        } else if meta.path.is_ident("hash_seed") {
+            try_set_attribute(
+                &mut self.hash_seed,
+                meta.value()?.parse()?,
+                "hash_seed",
+            )
         } else if meta.path.is_ident("as") {
             try_set_attribute(
                 &mut self.archive_as,
@@ -140,6 +154,13 @@ impl Attributes {
             }
         }
 
+        if let (Some(hash_seed), None) = (&result.hash_seed, &result.hasher) {
+            return Err(Error::new_spanned(
+                hash_seed,
+                "hash_seed requires a hasher to be specified",
+            ));
+        }
+
         Ok(result)
     }
 
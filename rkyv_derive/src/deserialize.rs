@@ -21,7 +21,7 @@ fn derive_deserialize_impl(
     attributes: &Attributes,
 ) -> Result<TokenStream, Error> {
     let rkyv_path = attributes.rkyv_path();
-    let with_ty = make_with_ty(&rkyv_path);
+    let with_ty = make_with_ty(attributes);
 
     let where_clause = input.generics.make_where_clause();
     if let Some(ref bounds) = attributes.archive_bounds {
@@ -71,6 +71,7 @@ fn derive_deserialize_impl(
                     let name = &f.ident;
                     let ty = with_ty(f).unwrap();
                     let value = with_inner(
+                        attributes,
                         f,
                         parse_quote! {
                             #rkyv_path::Deserialize::<#ty, __D>::deserialize(
@@ -111,6 +112,7 @@ fn derive_deserialize_impl(
                         let index = Index::from(i);
                         let ty = with_ty(f).unwrap();
                         let value = with_inner(
+                            attributes,
                             f,
                             parse_quote! {
                                 #rkyv_path::Deserialize::<#ty, __D>::deserialize(
@@ -188,6 +190,7 @@ fn derive_deserialize_impl(
                             let name = &f.ident;
                             let ty = with_ty(f).unwrap();
                             let value = with_inner(
+                                attributes,
                                 f,
                                 parse_quote! {
                                     #rkyv_path::Deserialize::<#ty, __D>::deserialize(
@@ -212,6 +215,7 @@ fn derive_deserialize_impl(
                             let binding = Ident::new(&format!("_{}", i), f.span());
                             let ty = with_ty(f).unwrap();
                             let value = with_inner(
+                                attributes,
                                 f,
                                 parse_quote! {
                                     #rkyv_path::Deserialize::<#ty, __D>::deserialize(
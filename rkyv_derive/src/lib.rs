@@ -72,6 +72,15 @@ pub fn derive_portable(
 /// - `copy_safe`: States that the archived type is tightly packed with no
 ///   padding bytes. This qualifies it for copy optimizations. (requires
 ///   nightly)
+/// - `hasher = ...`: Archives every `HashMap` and `HashSet` field which doesn't
+///   have a `#[with(...)]` attribute with `HashWith<...>`, using the given
+///   hasher. Only fields whose type is written as a bare `HashMap`/`HashSet` or
+///   a path through `std::collections` are recognized. Type aliases and
+///   collections from other crates need an explicit `#[with(...)]`.
+/// - `hash_seed = ...`: Chooses the `HashSeed` used with `hasher = ...`. This
+///   defaults to `DefaultSeed`, which uses the same public seed for every map.
+///   Use `RandomSeed` for keyed hashers like `SipHasher13` when keys may be
+///   chosen by an attacker.
 /// - `as = "..."`: Instead of generating a separate archived type, this type
 ///   will archive as the named type. This is useful for types which are generic
 ///   over their parameters.
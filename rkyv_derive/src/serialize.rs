@@ -21,8 +21,8 @@ fn derive_serialize_impl(
     attributes: &Attributes,
 ) -> Result<TokenStream, Error> {
     let rkyv_path = attributes.rkyv_path();
-    let with_ty = make_with_ty(&rkyv_path);
-    let with_cast = make_with_cast(&rkyv_path);
+    let with_ty = make_with_ty(attributes);
+    let with_cast = make_with_cast(attributes);
 
     let where_clause = input.generics.make_where_clause();
     if let Some(ref bounds) = attributes.archive_bounds {
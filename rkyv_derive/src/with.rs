@@ -1,15 +1,56 @@
 use syn::{
     parse_quote, punctuated::Punctuated, token::Comma, Error, Expr, Field,
-    Meta, Path, Type,
+    Meta, Type,
 };
 
+use crate::attributes::Attributes;
+
+/// Returns whether the given type is a `HashMap` or `HashSet` from
+/// `std::collections`.
+///
+/// `HashWith` is only implemented for the standard library collections, so
+/// only bare `HashMap` and `HashSet` idents and paths through
+/// `std::collections` are recognized. Paths to collections from other crates
+/// (e.g. `hashbrown::HashMap`) are left alone, but bare idents are assumed to
+/// name the standard library collections.
+fn is_hash_collection(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return false,
+    };
+
+    let module = match path.segments.last() {
+        Some(last) if last.ident == "HashMap" => "hash_map",
+        Some(last) if last.ident == "HashSet" => "hash_set",
+        _ => return false,
+    };
+
+    // Generic arguments are only allowed on the collection itself
+    let prefix = path.segments.iter().take(path.segments.len() - 1);
+    if prefix.clone().any(|segment| !segment.arguments.is_empty()) {
+        return false;
+    }
+    let prefix = prefix
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    let prefix = prefix.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match prefix.as_slice() {
+        [] => path.leading_colon.is_none(),
+        ["std", "collections"] => true,
+        ["std", "collections", m] => *m == module,
+        _ => false,
+    }
+}
+
 #[inline]
 pub fn with<B, F: FnMut(B, &Type) -> B>(
+    attributes: &Attributes,
     field: &Field,
     init: B,
     f: F,
 ) -> Result<B, Error> {
-    let fields = field
+    let mut wrappers = field
         .attrs
         .iter()
         .filter_map(|attr| {
@@ -25,16 +66,35 @@ pub fn with<B, F: FnMut(B, &Type) -> B>(
                 None
             }
         })
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(fields.iter().flatten().rev().fold(init, f))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    // Hash maps and sets without wrappers use the hasher of the type
+    if wrappers.is_empty() && is_hash_collection(&field.ty) {
+        if let Some(ref hasher) = attributes.hasher {
+            let rkyv_path = attributes.rkyv_path();
+            let seed = attributes.hash_seed.clone().unwrap_or_else(
+                || parse_quote! { #rkyv_path::hash::DefaultSeed },
+            );
+            wrappers.push(
+                parse_quote! { #rkyv_path::with::HashWith<#hasher, #seed> },
+            );
+        }
+    }
+
+    Ok(wrappers.iter().rev().fold(init, f))
 }
 
 #[inline]
 pub fn make_with_ty(
-    rkyv_path: &Path,
+    attributes: &Attributes,
 ) -> impl '_ + Fn(&Field) -> Result<Type, Error> {
+    let rkyv_path = attributes.rkyv_path();
     move |field| {
         with(
+            attributes,
             field,
             field.ty.clone(),
             |ty, wrapper| parse_quote! { #rkyv_path::with::With<#ty, #wrapper> },
@@ -44,10 +104,12 @@ pub fn make_with_ty(
 
 #[inline]
 pub fn make_with_cast(
-    rkyv_path: &Path,
+    attributes: &Attributes,
 ) -> impl '_ + Fn(&Field, Expr) -> Result<Expr, Error> {
+    let rkyv_path = attributes.rkyv_path();
     move |field, expr| {
         with(
+            attributes,
             field,
             expr,
             |expr, wrapper| parse_quote! { #rkyv_path::with::With::<_, #wrapper>::cast(#expr) },
@@ -56,6 +118,15 @@ pub fn make_with_cast(
 }
 
 #[inline]
-pub fn with_inner(field: &Field, expr: Expr) -> Result<Expr, Error> {
-    with(field, expr, |expr, _| parse_quote! { #expr.into_inner() })
+pub fn with_inner(
+    attributes: &Attributes,
+    field: &Field,
+    expr: Expr,
+) -> Result<Expr, Error> {
+    with(
+        attributes,
+        field,
+        expr,
+        |expr, _| parse_quote! { #expr.into_inner() },
+    )
 }
@@ -1116,6 +1116,94 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_size_matches_to_bytes() {
+        use rkyv::util::archived_size;
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: Vec<String>,
+            b: Option<Box<[u32]>>,
+            c: Rc<str>,
+            d: Rc<str>,
+        }
+
+        let shared = Rc::<str>::from("shared");
+        let value = Test {
+            a: vec!["hello".to_string(), "world".to_string()],
+            b: Some(vec![1, 2, 3].into_boxed_slice()),
+            c: shared.clone(),
+            d: shared,
+        };
+
+        let size = archived_size::<_, Failure>(&value).unwrap();
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        assert_eq!(size.len, bytes.len());
+        assert!(size.max_scratch <= size.min_scratch_buffer);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn chunked_writer() {
+        use rkyv::ser::{
+            allocator::GlobalAllocator, sharing::Unify, writer::ChunkedWriter,
+            Composite,
+        };
+
+        const CHUNK_SIZE: usize = 64;
+
+        let value = (0..100).map(|i| i.to_string()).collect::<Vec<_>>();
+        let expected = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let mut chunks = Vec::new();
+        let mut serializer = Composite::new(
+            ChunkedWriter::new(
+                AlignedBytes([0u8; CHUNK_SIZE]),
+                |chunk: &[u8]| {
+                    assert_eq!(chunk.as_ptr() as usize % 16, 0);
+                    chunks.push(chunk.to_vec());
+                    Ok::<_, Failure>(())
+                },
+            ),
+            GlobalAllocator::new(),
+            Unify::new(),
+        );
+        rkyv::util::serialize::<_, _, Failure>(&value, &mut serializer)
+            .unwrap();
+        serializer.into_writer().finish::<Failure>().unwrap();
+
+        let (last, full) = chunks.split_last().unwrap();
+        assert!(full.iter().all(|chunk| chunk.len() == CHUNK_SIZE));
+        assert!(!last.is_empty() && last.len() <= CHUNK_SIZE);
+        assert_eq!(chunks.concat(), expected.as_slice());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serializer_arena() {
+        use rkyv::{ser::SerializerArena, util::to_bytes_in};
+
+        let mut arena = SerializerArena::<64>::new();
+
+        let shared = Rc::new(42u32);
+        let first = (shared.clone(), shared.clone(), "first".to_string());
+        let bytes = to_bytes_in::<_, 64, Failure>(&first, &mut arena).unwrap();
+        let expected = to_bytes::<_, 64, Failure>(&first).unwrap();
+        assert_eq!(bytes, expected.as_slice());
+
+        // The shared pointer table must be cleared between uses, otherwise
+        // the shared value would refer to its position in the first archive.
+        let second = ("second".to_string(), shared.clone(), shared);
+        let bytes =
+            to_bytes_in::<_, 64, Failure>(&second, &mut arena).unwrap();
+        let expected = to_bytes::<_, 64, Failure>(&second).unwrap();
+        assert_eq!(bytes, expected.as_slice());
+
+        let serializer = arena.into_serializer();
+        assert!(serializer.writer.capacity() >= expected.len());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn check_util_bounds() {
@@ -2131,6 +2219,319 @@ mod tests {
         assert_eq!(value, deserialized);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_interned_strings() {
+        use rkyv::{
+            string::symbol::Interned,
+            with::{Intern, Map},
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Clone, Debug, PartialEq)]
+        struct Test {
+            #[with(Intern)]
+            name: String,
+            #[with(Map<Intern>)]
+            idents: Vec<String>,
+            #[with(Map<Intern>)]
+            kind: Option<Box<str>>,
+        }
+
+        let value = Interned(Test {
+            name: "a long identifier".to_string(),
+            idents: vec![
+                "a long identifier".to_string(),
+                "x".to_string(),
+                "another long identifier".to_string(),
+                "x".to_string(),
+            ],
+            kind: Some("x".into()),
+        });
+
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Interned<Test>>(buf.as_ref()) };
+        let test = archived.value();
+
+        assert_eq!(test.name, "a long identifier");
+        assert_eq!(test.idents.len(), value.0.idents.len());
+        for (ident, native) in test.idents.iter().zip(value.0.idents.iter()) {
+            assert_eq!(ident.as_str(), native);
+        }
+        assert_eq!(test.kind.as_ref().map(|kind| kind.as_str()), Some("x"));
+
+        // Equal strings are written to the string table once
+        assert_eq!(archived.strings().len(), 3);
+        assert!(test.idents[0].ptr_eq(&test.name));
+        assert!(test.idents[1].ptr_eq(&test.idents[3]));
+        assert!(!test.idents[1].ptr_eq(&test.idents[2]));
+        assert!(test.kind.as_ref().unwrap().ptr_eq(&test.idents[1]));
+        assert_eq!(
+            archived.strings().iter().collect::<Vec<_>>(),
+            ["a long identifier", "x", "another long identifier"],
+        );
+
+        // Interned strings must be enclosed by exactly one `Interned`
+        to_bytes::<_, 256, Failure>(&value.0).unwrap_err();
+        to_bytes::<_, 256, Failure>(&Interned(value.clone())).unwrap_err();
+
+        let deserialized =
+            deserialize::<Interned<Test>, _, Failure>(archived, &mut ())
+                .unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_sorted_vec_map() {
+        use core::ops::Bound::{Excluded, Included, Unbounded};
+
+        use rkyv::with::AsSortedVec;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(AsSortedVec)]
+            map: BTreeMap<u32, String>,
+            #[with(AsSortedVec)]
+            empty: BTreeMap<String, u32>,
+        }
+
+        let value = Test {
+            map: (0..100).map(|i| (i * 3, i.to_string())).collect(),
+            empty: BTreeMap::new(),
+        };
+
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Test>(buf.as_ref()) };
+
+        let key = Archived::<u32>::from_native;
+        assert_eq!(archived.map.len(), 100);
+        assert_eq!(archived.map.keys()[10], key(30));
+        assert_eq!(archived.map.get(&key(42)).unwrap(), "14");
+        assert_eq!(archived.map[&key(297)], "99");
+        assert!(!archived.map.contains_key(&key(43)));
+        assert_eq!(archived.map.binary_search(&key(43)), Err(15));
+        let (first, _) = archived.map.first_key_value().unwrap();
+        assert_eq!(*first, key(0));
+        let (last, _) = archived.map.last_key_value().unwrap();
+        assert_eq!(*last, key(297));
+
+        for i in 0..300 {
+            let expected = archived.map.binary_search(&key(i));
+            let actual = archived
+                .map
+                .interpolation_search_by(i as u64, |k| k.to_native() as u64);
+            assert_eq!(actual, expected);
+        }
+
+        let range = archived
+            .map
+            .range((Included(key(10)), Excluded(key(30))))
+            .map(|(k, v)| (k.to_native(), v.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            range,
+            [
+                (12, "4"),
+                (15, "5"),
+                (18, "6"),
+                (21, "7"),
+                (24, "8"),
+                (27, "9")
+            ]
+        );
+        let range = archived
+            .map
+            .range((Excluded(key(288)), Unbounded))
+            .rev()
+            .map(|(k, _)| k.to_native())
+            .collect::<Vec<_>>();
+        assert_eq!(range, [297, 294, 291]);
+        assert_eq!(archived.map.range(key(400)..).count(), 0);
+
+        assert!(archived.empty.is_empty());
+        assert_eq!(archived.empty.get("a"), None);
+        assert_eq!(archived.empty.range::<str, _>(..).count(), 0);
+        assert_eq!(archived.empty.interpolation_search_by(0, |_| 0), Err(0));
+
+        let deserialized =
+            deserialize::<Test, _, Failure>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    // This test creates structures too big to fit in 16-bit offsets
+    #[cfg(not(feature = "size_16"))]
+    fn archive_btree_map_range() {
+        use core::ops::Bound::{self, Excluded, Included, Unbounded};
+
+        fn to_archived(bound: Bound<u32>) -> Bound<Archived<u32>> {
+            match bound {
+                Included(x) => Included(Archived::<u32>::from_native(x)),
+                Excluded(x) => Excluded(Archived::<u32>::from_native(x)),
+                Unbounded => Unbounded,
+            }
+        }
+
+        fn to_native((k, v): (&Archived<u32>, &Archived<u64>)) -> (u32, u64) {
+            (k.to_native(), v.to_native())
+        }
+
+        // Large enough to need several leaf nodes and an inner node
+        let value = (0..3_000u32)
+            .map(|i| (i * 2, i as u64))
+            .collect::<BTreeMap<_, _>>();
+
+        let result =
+            serialize_into::<_, _, Failure>(&value, AlignedVec::new()).unwrap();
+        let archived = unsafe {
+            access_unchecked::<BTreeMap<u32, u64>>(result.as_slice())
+        };
+
+        assert_eq!(archived.first_key_value().map(to_native), Some((0, 0)));
+        assert_eq!(
+            archived.last_key_value().map(to_native),
+            Some((5_998, 2_999)),
+        );
+
+        let expected = value.iter().map(|(k, v)| (*k, *v));
+        assert!(archived.iter().rev().map(to_native).eq(expected.rev()));
+        assert!(archived
+            .keys()
+            .rev()
+            .map(|k| k.to_native())
+            .eq(value.keys().rev().copied()));
+        assert!(archived
+            .values()
+            .rev()
+            .map(|v| v.to_native())
+            .eq(value.values().rev().copied()));
+
+        let bounds = [
+            (Unbounded, Unbounded),
+            (Included(0), Excluded(0)),
+            (Included(1), Excluded(2)),
+            (Included(100), Included(100)),
+            (Excluded(99), Excluded(101)),
+            (Included(1_001), Excluded(4_000)),
+            (Excluded(1_000), Unbounded),
+            (Unbounded, Included(3_333)),
+            (Included(5_998), Unbounded),
+            (Excluded(5_998), Included(10_000)),
+        ];
+        for (start, end) in bounds {
+            let archived_range = (to_archived(start), to_archived(end));
+            let expected = value.range((start, end)).map(|(k, v)| (*k, *v));
+
+            let actual = archived.range(archived_range).map(to_native);
+            assert!(actual.eq(expected.clone()), "{:?}", (start, end));
+            let actual = archived.range(archived_range).rev().map(to_native);
+            assert!(actual.eq(expected.rev()), "{:?}", (start, end));
+        }
+
+        // Bounds find the nearest entries, including across leaf nodes
+        for key in [0, 1, 2, 999, 1_000, 1_001, 3_333, 5_997, 5_998, 6_000] {
+            let archived_key = Archived::<u32>::from_native(key);
+            for bound in [Included(key), Excluded(key), Unbounded] {
+                let archived_bound = match bound {
+                    Included(_) => Included(&archived_key),
+                    Excluded(_) => Excluded(&archived_key),
+                    Unbounded => Unbounded,
+                };
+                assert_eq!(
+                    archived.lower_bound(archived_bound).map(to_native),
+                    value
+                        .range((bound, Unbounded))
+                        .next()
+                        .map(|(k, v)| (*k, *v)),
+                    "{:?}",
+                    bound,
+                );
+                assert_eq!(
+                    archived.upper_bound(archived_bound).map(to_native),
+                    value
+                        .range((Unbounded, bound))
+                        .next_back()
+                        .map(|(k, v)| (*k, *v)),
+                    "{:?}",
+                    bound,
+                );
+            }
+        }
+
+        // Iterating from both ends meets in the middle
+        let bounds = (Included(1_000), Included(4_999));
+        let mut range =
+            archived.range((to_archived(bounds.0), to_archived(bounds.1)));
+        let mut expected = value.range(bounds).map(|(k, v)| (*k, *v));
+        let mut count = 0;
+        loop {
+            let next = if count % 3 == 0 {
+                (range.next_back().map(to_native), expected.next_back())
+            } else {
+                (range.next().map(to_native), expected.next())
+            };
+            match next {
+                (None, None) => break,
+                (actual, wanted) => assert_eq!(actual, wanted),
+            }
+            count += 1;
+        }
+        assert_eq!(count, 2_000);
+        assert!(range.next().is_none());
+        assert!(range.next_back().is_none());
+
+        let empty = BTreeMap::<u32, u64>::new();
+        let result =
+            serialize_into::<_, _, Failure>(&empty, AlignedVec::new()).unwrap();
+        let archived = unsafe {
+            access_unchecked::<BTreeMap<u32, u64>>(result.as_slice())
+        };
+        assert!(archived.first_key_value().is_none());
+        assert!(archived.last_key_value().is_none());
+        assert!(archived.iter().next_back().is_none());
+        assert!(archived.lower_bound::<Archived<u32>>(Unbounded).is_none());
+        assert!(archived.upper_bound::<Archived<u32>>(Unbounded).is_none());
+        assert!(archived
+            .range((to_archived(Included(1)), to_archived(Excluded(10))))
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_set_range() {
+        use core::ops::Bound::{Excluded, Included, Unbounded};
+
+        let value = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<BTreeSet<_>>();
+
+        let result =
+            serialize_into::<_, _, Failure>(&value, AlignedVec::new()).unwrap();
+        let archived =
+            unsafe { access_unchecked::<BTreeSet<String>>(result.as_slice()) };
+
+        assert_eq!(archived.first().map(|s| s.as_str()), Some("a"));
+        assert_eq!(archived.last().map(|s| s.as_str()), Some("e"));
+        assert!(archived
+            .range::<str, _>((Included("b"), Excluded("d")))
+            .map(|s| s.as_str())
+            .eq(["b", "c"]));
+        assert!(archived
+            .range::<str, _>((Excluded("bb"), Unbounded))
+            .rev()
+            .map(|s| s.as_str())
+            .eq(["e", "d", "c"]));
+        assert!(archived
+            .iter()
+            .rev()
+            .map(|s| s.as_str())
+            .eq(value.iter().rev().map(|s| s.as_str())));
+    }
+
     #[test]
     // This test is unfortunately too slow to run through miri
     #[cfg_attr(miri, ignore)]
@@ -2264,6 +2665,77 @@ mod tests {
         assert_ne!(tracker.min_buffer_size(), 0);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn global_allocator_reuse() {
+        use core::{alloc::Layout, ptr::NonNull};
+
+        use rkyv::ser::{allocator::GlobalAllocator, Allocator};
+
+        fn push(
+            allocator: &mut GlobalAllocator,
+            layout: Layout,
+        ) -> NonNull<u8> {
+            unsafe {
+                Allocator::<Failure>::push_alloc(allocator, layout)
+                    .unwrap()
+                    .cast()
+            }
+        }
+
+        fn pop(
+            allocator: &mut GlobalAllocator,
+            ptr: NonNull<u8>,
+            layout: Layout,
+        ) {
+            unsafe {
+                Allocator::<Failure>::pop_alloc(allocator, ptr, layout)
+                    .unwrap();
+            }
+        }
+
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let similar_layout = Layout::from_size_align(60, 8).unwrap();
+        let other_layout = Layout::from_size_align(32, 8).unwrap();
+
+        // By default, popped allocations are freed
+        let mut allocator = GlobalAllocator::new();
+        let first = push(&mut allocator, layout);
+        pop(&mut allocator, first, layout);
+        assert_eq!(allocator.free_allocations(), 0);
+
+        // Allocations in the same size class are reused
+        let mut allocator = GlobalAllocator::with_free_list(96);
+        let first = push(&mut allocator, layout);
+        pop(&mut allocator, first, layout);
+        assert_eq!(allocator.free_allocations(), 1);
+        assert_eq!(allocator.free_bytes(), 64);
+
+        let other = push(&mut allocator, other_layout);
+        assert_ne!(other, first);
+        let second = push(&mut allocator, similar_layout);
+        assert_eq!(second, first);
+        assert_eq!(allocator.free_allocations(), 0);
+
+        // The free list never holds more than its limit
+        let third = push(&mut allocator, layout);
+        pop(&mut allocator, third, layout);
+        pop(&mut allocator, second, similar_layout);
+        assert_eq!(allocator.free_bytes(), 64);
+        allocator.clear();
+        assert_eq!(allocator.free_bytes(), 96);
+
+        // Trimming frees layouts which weren't used since the last trim
+        allocator.trim();
+        assert_eq!(allocator.free_bytes(), 96);
+        let fourth = push(&mut allocator, layout);
+        pop(&mut allocator, fourth, layout);
+        allocator.trim();
+        assert_eq!(allocator.free_bytes(), 64);
+        allocator.release();
+        assert_eq!(allocator.free_allocations(), 0);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_manually_drop() {
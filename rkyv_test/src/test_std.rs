@@ -27,6 +27,172 @@ mod tests {
             .expect_err("serialized to an undersized buffer must fail");
     }
 
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn mmap_writer() {
+        use std::fs::OpenOptions;
+
+        use rkyv::ser::writer::MmapWriter;
+
+        // Use a unique name so that concurrent test runs don't collide
+        let path = std::env::temp_dir()
+            .join(format!("rkyv_test_mmap_writer_{}", std::process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+
+        // Large enough to force the map to grow several times
+        let value = (0..10_000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let expected = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let mut writer = rkyv::ser::Composite::new(
+            MmapWriter::new(file),
+            rkyv::ser::allocator::GlobalAllocator::new(),
+            rkyv::ser::sharing::Unify::new(),
+        );
+        serialize::<_, _, Failure>(&value, &mut writer).unwrap();
+        let map = writer.into_writer().into_mmap::<Failure>().unwrap();
+
+        assert_eq!(map.as_ptr() as usize % 16, 0);
+        assert_eq!(&map[..], expected.as_slice());
+        let archived = unsafe { access_unchecked::<Vec<String>>(&map) };
+        assert_eq!(archived.len(), value.len());
+
+        drop(map);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn vectored_writer() {
+        use rkyv::{ser::writer::VectoredWriter, with::CopyOptimize};
+
+        #[derive(Archive, Serialize)]
+        struct Blob {
+            name: String,
+            #[with(CopyOptimize)]
+            bytes: Vec<u8>,
+        }
+
+        let value = Blob {
+            name: "blob".to_string(),
+            bytes: (0..100_000).map(|i| i as u8).collect(),
+        };
+        let expected = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let mut serializer = rkyv::ser::Composite::new(
+            VectoredWriter::with_threshold(Vec::new(), 1024),
+            rkyv::ser::allocator::GlobalAllocator::new(),
+            rkyv::ser::sharing::Unify::new(),
+        );
+        serialize::<_, _, Failure>(&value, &mut serializer).unwrap();
+        let writer = serializer.into_writer();
+        assert!(writer.buffered() < 1024);
+        let buf = writer.into_inner::<Failure>().unwrap();
+
+        assert_eq!(buf.as_slice(), expected.as_slice());
+
+        // Archives made only of small writes are flushed as they're written
+        let value = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let expected = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let mut writer = VectoredWriter::new(Vec::new());
+        writer.set_max_buffered(256);
+        let mut serializer = rkyv::ser::Composite::new(
+            writer,
+            rkyv::ser::allocator::GlobalAllocator::new(),
+            rkyv::ser::sharing::Unify::new(),
+        );
+        serialize::<_, _, Failure>(&value, &mut serializer).unwrap();
+        let writer = serializer.into_writer();
+        assert!(writer.buffered() < 256);
+        let buf = writer.into_inner::<Failure>().unwrap();
+
+        assert_eq!(buf.as_slice(), expected.as_slice());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_io_writer() {
+        use rkyv::{
+            ser::{
+                allocator::GlobalAllocator, sharing::Unify,
+                writer::AsyncIoWriter, Composite, Positional as _,
+            },
+            util::serialize_async,
+        };
+
+        let first = vec!["hello".to_string(), "world".to_string()];
+        let second =
+            HashMap::from([("one".to_string(), "uno".to_string())]);
+
+        let mut writer = AsyncIoWriter::new(Vec::new());
+        writer.set_chunk_size(16);
+        let serializer =
+            Composite::new(writer, GlobalAllocator::new(), Unify::new());
+        let serializer =
+            serialize_async::<_, _, _, _, Failure>(first.clone(), serializer)
+                .await
+                .unwrap();
+        assert_eq!(serializer.writer.buffered(), 0);
+        let first_len = serializer.writer.pos();
+        let serializer =
+            serialize_async::<_, _, _, _, Failure>(second, serializer)
+                .await
+                .unwrap();
+        let buf = serializer
+            .into_writer()
+            .into_inner::<Failure>()
+            .await
+            .unwrap();
+
+        let expected = to_bytes::<_, 256, Failure>(&first).unwrap();
+        assert_eq!(&buf[..first_len], expected.as_slice());
+
+        let mut bytes = rkyv::util::AlignedVec::new();
+        bytes.extend_from_slice(&buf);
+        let archived = unsafe {
+            access_unchecked::<HashMap<String, String>>(bytes.as_slice())
+        };
+        assert_eq!(&archived["one"], "uno");
+    }
+
+    #[test]
+    fn serializer_pool() {
+        use std::thread;
+
+        use rkyv::{ser::SerializerPool, util::to_bytes_in};
+
+        let pool = SerializerPool::<256>::with_shards(2);
+
+        thread::scope(|s| {
+            for i in 0..8 {
+                let pool = &pool;
+                s.spawn(move || {
+                    for j in 0..16 {
+                        let value = vec![i.to_string(), j.to_string()];
+                        let expected =
+                            to_bytes::<_, 256, Failure>(&value).unwrap();
+                        let mut arena = pool.get();
+                        let bytes =
+                            to_bytes_in::<_, 256, Failure>(&value, &mut arena)
+                                .unwrap();
+                        assert_eq!(bytes, expected.as_slice());
+                    }
+                });
+            }
+        });
+
+        // At most one serializer is created for each concurrent user
+        assert!(pool.idle() >= 1 && pool.idle() <= 8);
+        let arena = pool.get();
+        assert!(arena.bytes().is_empty());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_hash_map() {
@@ -53,6 +219,231 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_perfect_hash_map() {
+        use rkyv::{deserialize, with::AsPerfectHash};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(AsPerfectHash)]
+            map: HashMap<String, u32>,
+            #[with(AsPerfectHash)]
+            empty: HashMap<String, u32>,
+        }
+
+        let value = Test {
+            map: (0..1000).map(|i| (format!("key {}", i), i)).collect(),
+            empty: HashMap::new(),
+        };
+
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.map.len(), value.map.len());
+        for (key, native_value) in value.map.iter() {
+            assert!(archived.map.contains_key(key.as_str()));
+            assert_eq!(archived.map[key.as_str()].to_native(), *native_value);
+        }
+        for (key, archived_value) in archived.map.iter() {
+            assert_eq!(value.map[key.as_str()], archived_value.to_native());
+            assert_eq!(archived.map.get(key.as_str()), Some(archived_value));
+        }
+        assert_eq!(archived.map.get("key 1000"), None);
+        assert!(!archived.map.contains_key("missing"));
+
+        assert!(archived.empty.is_empty());
+        assert_eq!(archived.empty.get("key 0"), None);
+        assert_eq!(archived.empty.iter().count(), 0);
+
+        let deserialized =
+            deserialize::<Test, _, Failure>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Large maps are built with a bounded search for each bucket
+        #[derive(Archive, Serialize)]
+        struct Large {
+            #[with(AsPerfectHash)]
+            map: HashMap<u32, u32>,
+        }
+
+        let value = Large {
+            map: (0..100_000).map(|i| (i, i * 2)).collect(),
+        };
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Large>(buf.as_ref()) };
+        assert_eq!(archived.map.len(), value.map.len());
+        for (key, native_value) in value.map.iter() {
+            let key = rkyv::Archived::<u32>::from_native(*key);
+            assert_eq!(archived.map[&key].to_native(), *native_value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_with() {
+        use rkyv::{
+            collections::swiss_table::ArchivedHashMap,
+            deserialize,
+            hash::{
+                ArchivedHasher, HashSeed, RandomSeed, SeededFxHasher64,
+                SipHasher13,
+            },
+            string::ArchivedString,
+            with::HashWith,
+            Archived,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(HashWith<SipHasher13>)]
+            map: HashMap<String, u32>,
+            #[with(HashWith<SeededFxHasher64>)]
+            set: HashSet<String>,
+        }
+
+        let value = Test {
+            map: (0..100).map(|i| (format!("key {}", i), i)).collect(),
+            set: (0..100).map(|i| format!("item {}", i)).collect(),
+        };
+
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.map.len(), value.map.len());
+        for (key, native_value) in value.map.iter() {
+            assert_eq!(archived.map[key.as_str()].to_native(), *native_value);
+        }
+        assert!(!archived.map.contains_key("key 100"));
+        assert_eq!(archived.set.len(), value.set.len());
+        for item in value.set.iter() {
+            assert!(archived.set.contains(item.as_str()));
+        }
+        assert!(!archived.set.contains("item 100"));
+
+        let deserialized =
+            deserialize::<Test, _, Failure>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Every hash map and set without a wrapper uses the hasher of the type
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(hasher = SipHasher13)]
+        struct Defaulted {
+            map: HashMap<String, u32>,
+            set: std::collections::HashSet<u32>,
+            list: Vec<String>,
+        }
+
+        let value = Defaulted {
+            map: (0..10).map(|i| (format!("key {}", i), i)).collect(),
+            set: (0..10).collect(),
+            list: vec!["hello".to_string()],
+        };
+
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Defaulted>(buf.as_ref()) };
+        let _: &ArchivedHashMap<ArchivedString, Archived<u32>, SipHasher13> =
+            &archived.map;
+        assert_eq!(archived.map["key 3"].to_native(), 3);
+        assert!(archived.set.contains(&Archived::<u32>::from_native(7)));
+
+        let deserialized =
+            deserialize::<Defaulted, _, Failure>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Seeds are deterministic unless a random seed is requested
+        let value = Test {
+            map: (0..10).map(|i| (format!("key {}", i), i)).collect(),
+            set: (0..10).map(|i| format!("item {}", i)).collect(),
+        };
+        let first = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let second = to_bytes::<_, 256, Failure>(&value).unwrap();
+        assert_eq!(first.as_ref(), second.as_ref());
+
+        struct FixedSeed;
+
+        impl HashSeed<SipHasher13> for FixedSeed {
+            fn seed() -> <SipHasher13 as ArchivedHasher>::Seed {
+                [1, 2].map(Archived::<u64>::from_native)
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Seeded {
+            #[with(HashWith<SipHasher13, FixedSeed>)]
+            fixed: HashMap<String, u32>,
+            #[with(HashWith<SipHasher13, RandomSeed>)]
+            random: HashMap<String, u32>,
+        }
+
+        let value = Seeded {
+            fixed: (0..10).map(|i| (format!("key {}", i), i)).collect(),
+            random: (0..10).map(|i| (format!("key {}", i), i)).collect(),
+        };
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Seeded>(buf.as_ref()) };
+        for i in 0..10 {
+            let key = format!("key {}", i);
+            assert_eq!(archived.fixed[key.as_str()].to_native(), i);
+            assert_eq!(archived.random[key.as_str()].to_native(), i);
+        }
+
+        // The seed of the type's hasher can be chosen too
+        #[derive(Archive, Serialize)]
+        #[archive(hasher = SipHasher13, hash_seed = FixedSeed)]
+        struct DefaultSeeded {
+            map: HashMap<String, u32>,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct ExplicitSeeded {
+            #[with(HashWith<SipHasher13, FixedSeed>)]
+            map: HashMap<String, u32>,
+        }
+
+        let map = (0..10)
+            .map(|i| (format!("key {}", i), i))
+            .collect::<HashMap<_, _>>();
+        let default_seeded =
+            to_bytes::<_, 256, Failure>(&DefaultSeeded { map: map.clone() })
+                .unwrap();
+        let explicit_seeded =
+            to_bytes::<_, 256, Failure>(&ExplicitSeeded { map }).unwrap();
+        assert_eq!(default_seeded.as_ref(), explicit_seeded.as_ref());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_as_sorted_vec() {
+        use rkyv::{deserialize, with::AsSortedVec};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(AsSortedVec)]
+            map: HashMap<String, u32>,
+        }
+
+        let value = Test {
+            map: (0..100).map(|i| (format!("key {:03}", i), i)).collect(),
+        };
+
+        let buf = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.map.len(), value.map.len());
+        assert!(archived.map.keys().windows(2).all(|w| w[0] < w[1]));
+        for (i, (key, archived_value)) in archived.map.iter().enumerate() {
+            assert_eq!(key.as_str(), format!("key {:03}", i));
+            assert_eq!(archived_value.to_native(), i as u32);
+        }
+        assert_eq!(archived.map["key 042"].to_native(), 42);
+        assert!(!archived.map.contains_key("key 100"));
+
+        let deserialized =
+            deserialize::<Test, _, Failure>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_hash_map_tuple_retrieved_by_get_with() {
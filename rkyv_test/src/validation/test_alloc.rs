@@ -484,4 +484,531 @@ mod tests {
         let data = AlignedBytes([0x10; 16]);
         rkyv::from_bytes::<String, Failure>(&data.0).unwrap_err();
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_digest() {
+        use core::hash::Hasher as _;
+
+        use rkyv::{
+            hash::Crc32c,
+            ser::{
+                allocator::GlobalAllocator, sharing::Unify,
+                writer::HashingWriter, Composite,
+            },
+            util::AlignedVec,
+            validation::util::access_checked_with_digest,
+        };
+
+        let mut hasher = Crc32c::default();
+        hasher.write(b"123456789");
+        assert_eq!(hasher.checksum(), 0xe306_9283);
+
+        let value = vec![Some("hello".to_string()), None];
+        let mut serializer = Composite::new(
+            HashingWriter::<_, Crc32c>::new(AlignedVec::new()),
+            GlobalAllocator::new(),
+            Unify::new(),
+        );
+        rkyv::util::serialize::<_, _, Failure>(&value, &mut serializer)
+            .unwrap();
+        let writer = serializer.into_writer();
+        let digest = writer.digest();
+        let mut bytes = writer.into_inner();
+
+        access_checked_with_digest::<Vec<Option<String>>, Crc32c, Failure>(
+            &bytes, digest,
+        )
+        .unwrap();
+
+        bytes[0] ^= 0xff;
+        access_checked_with_digest::<Vec<Option<String>>, Crc32c, Failure>(
+            &bytes, digest,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn dedup_by_content() {
+        use rkyv::{
+            ser::{
+                allocator::GlobalAllocator,
+                sharing::{DedupByContent, Unify},
+                Composite,
+            },
+            util::{serialize, AlignedVec},
+        };
+
+        let mut value = Vec::new();
+        for _ in 0..4 {
+            value.push(Rc::<str>::from("hello world"));
+            value.push(Rc::<str>::from("goodbye world"));
+        }
+        value.push(value[0].clone());
+
+        let mut serializer = DedupByContent::new(Composite::new(
+            AlignedVec::new(),
+            GlobalAllocator::new(),
+            (),
+        ));
+        serialize::<_, _, Failure>(&value, &mut serializer).unwrap();
+        assert_eq!(serializer.unique_values(), 2);
+        let bytes = serializer.into_inner().into_writer();
+
+        let mut unified = Composite::new(
+            AlignedVec::new(),
+            GlobalAllocator::new(),
+            Unify::new(),
+        );
+        serialize::<_, _, Failure>(&value, &mut unified).unwrap();
+        assert!(bytes.len() < unified.into_writer().len());
+
+        let archived = access::<Vec<Rc<str>>, Failure>(&bytes).unwrap();
+        assert_eq!(archived.len(), value.len());
+        for (string, expected) in archived.iter().zip(&value) {
+            assert_eq!(&**string, &**expected);
+        }
+        for (i, string) in archived.iter().enumerate() {
+            assert_eq!(string.as_ptr(), archived[i % 2].as_ptr());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn validation_limits() {
+        use rkyv::validation::{
+            util::access_with_limits, validators::ValidationLimits,
+        };
+
+        let value = vec![
+            "a string which is stored out of line".to_string(),
+            "short".to_string(),
+        ];
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        access_with_limits::<Vec<String>, Failure>(
+            &bytes,
+            ValidationLimits::default(),
+        )
+        .unwrap();
+        access_with_limits::<Vec<String>, Failure>(
+            &bytes,
+            ValidationLimits {
+                max_collection_len: Some(36),
+                max_total_elements: Some(43),
+                max_archived_bytes: Some(100),
+                max_alloc_bytes: Some(400),
+                ..ValidationLimits::default()
+            },
+        )
+        .unwrap();
+
+        access_with_limits::<Vec<String>, Failure>(
+            &bytes,
+            ValidationLimits {
+                max_collection_len: Some(35),
+                ..ValidationLimits::default()
+            },
+        )
+        .unwrap_err();
+        access_with_limits::<Vec<String>, Failure>(
+            &bytes,
+            ValidationLimits {
+                max_total_elements: Some(42),
+                ..ValidationLimits::default()
+            },
+        )
+        .unwrap_err();
+        access_with_limits::<Vec<String>, Failure>(
+            &bytes,
+            ValidationLimits {
+                max_archived_bytes: Some(40),
+                ..ValidationLimits::default()
+            },
+        )
+        .unwrap_err();
+
+        // The allocation estimate must cover the native strings, which are
+        // larger than the archived ones.
+        let native_bytes = value.len() * core::mem::size_of::<String>()
+            + value.iter().map(String::len).sum::<usize>();
+        access_with_limits::<Vec<String>, Failure>(
+            &bytes,
+            ValidationLimits {
+                max_alloc_bytes: Some(native_bytes - 1),
+                ..ValidationLimits::default()
+            },
+        )
+        .unwrap_err();
+
+        // Zero-sized elements don't take up any space in the archive, but
+        // still count towards the element limits.
+        let value = vec![(); 1000];
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        access_with_limits::<Vec<()>, Failure>(
+            &bytes,
+            ValidationLimits {
+                max_collection_len: Some(999),
+                ..ValidationLimits::default()
+            },
+        )
+        .unwrap_err();
+
+        // Boxed and shared slices are collections too.
+        let value = vec![0u32; 100].into_boxed_slice();
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let limits = ValidationLimits {
+            max_collection_len: Some(99),
+            ..ValidationLimits::default()
+        };
+        access_with_limits::<Box<[u32]>, Failure>(&bytes, limits).unwrap_err();
+
+        let value: Rc<[u32]> = vec![0u32; 100].into();
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        access_with_limits::<Rc<[u32]>, Failure>(&bytes, limits).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn validated() {
+        use rkyv::validation::Validated;
+
+        fn total_len(strings: Validated<Vec<String>, &[u8]>) -> usize {
+            strings.iter().map(|s| s.len()).sum()
+        }
+
+        let value = vec!["hello".to_string(), "world".to_string()];
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let validated =
+            Validated::<Vec<String>, _>::new::<Failure>(bytes).unwrap();
+        assert_eq!(validated.len(), 2);
+        assert_eq!(validated[0].as_str(), value[0]);
+
+        let borrowed = validated.as_slice();
+        let copied = borrowed;
+        assert_eq!(total_len(borrowed), 10);
+        assert_eq!(total_len(copied), 10);
+
+        let shared: Rc<[u8]> = validated.buffer().as_slice().into();
+        let shared =
+            Validated::<Vec<String>, _>::new::<Failure>(shared).unwrap();
+        let cloned = shared.clone();
+        assert_eq!(cloned.as_ptr(), shared.as_ptr());
+
+        let mut bytes = validated.into_inner();
+        let len = bytes.len();
+        bytes[len - 4..].copy_from_slice(&[0xff; 4]);
+        Validated::<Vec<String>, _>::new::<Failure>(bytes).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn lazy() {
+        use rkyv::{lazy::LazyAccess, with::Lazy};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Example {
+            #[with(Lazy)]
+            name: String,
+            #[with(Lazy)]
+            flag: bool,
+        }
+
+        let value = Example {
+            name: "hello world".to_string(),
+            flag: true,
+        };
+        let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let archived = unsafe { rkyv::access_unchecked::<Example>(&bytes) };
+        let flag = unsafe { archived.flag.get_unchecked() };
+        let flag_offset =
+            flag as *const bool as usize - bytes.as_ptr() as usize;
+        bytes[flag_offset] = 2;
+
+        // Eager validation does not check lazy values
+        access::<Example, Failure>(&bytes).unwrap();
+
+        let access = LazyAccess::new(&bytes);
+        let archived = access.root::<Example, Failure>().unwrap();
+        assert_eq!(access.checked_len(), 0);
+
+        let name = access.get::<_, Failure>(&archived.name).unwrap();
+        assert_eq!(name.as_str(), "hello world");
+        assert_eq!(access.checked_len(), 1);
+        access.get::<_, Failure>(&archived.name).unwrap();
+        assert_eq!(access.checked_len(), 1);
+
+        access.get::<_, Failure>(&archived.flag).unwrap_err();
+        assert_eq!(access.checked_len(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn lazy_limits() {
+        use rkyv::{
+            lazy::LazyAccess, validation::validators::ValidationLimits,
+            with::Lazy,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Example {
+            #[with(Lazy)]
+            first: Vec<u8>,
+            #[with(Lazy)]
+            second: Vec<u8>,
+        }
+
+        let value = Example {
+            first: vec![1; 10],
+            second: vec![2; 10],
+        };
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        // Limits apply to all of the lazy values together
+        let access = LazyAccess::with_limits(
+            &bytes,
+            ValidationLimits {
+                max_total_elements: Some(15),
+                ..ValidationLimits::default()
+            },
+        );
+        let archived = access.root::<Example, Failure>().unwrap();
+        access.get::<_, Failure>(&archived.first).unwrap();
+        assert_eq!(access.total_elements(), 10);
+        access.get::<_, Failure>(&archived.second).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn access_range() {
+        use core::mem::size_of;
+
+        use rkyv::{
+            util::AlignedVec,
+            validation::util::{access_pos_in_range, access_range},
+        };
+
+        let first = vec![1u8, 2, 3, 4];
+        let second = vec![5u8, 6, 7];
+        let first_bytes = to_bytes::<_, 256, Failure>(&first).unwrap();
+        let second_bytes = to_bytes::<_, 256, Failure>(&second).unwrap();
+
+        // A header followed by two aligned archives
+        let mut buffer = AlignedVec::new();
+        buffer.extend_from_slice(&[0xff; AlignedVec::ALIGNMENT]);
+        let first_start = buffer.len();
+        buffer.extend_from_slice(&first_bytes);
+        let first_end = buffer.len();
+        buffer.resize(first_end.next_multiple_of(AlignedVec::ALIGNMENT), 0);
+        let second_start = buffer.len();
+        buffer.extend_from_slice(&second_bytes);
+        let second_end = buffer.len();
+
+        let archived =
+            access_range::<Vec<u8>, Failure>(&buffer, first_start..first_end)
+                .unwrap();
+        assert_eq!(archived.as_slice(), &[1, 2, 3, 4]);
+        let archived =
+            access_range::<Vec<u8>, Failure>(&buffer, second_start..second_end)
+                .unwrap();
+        assert_eq!(archived.as_slice(), &[5, 6, 7]);
+
+        let root_size = size_of::<rkyv::Archived<Vec<u8>>>();
+        let root_pos = second_bytes.len() - root_size;
+        let archived = access_pos_in_range::<Vec<u8>, Failure>(
+            &buffer,
+            second_start..second_end,
+            root_pos,
+        )
+        .unwrap();
+        assert_eq!(archived.len(), 3);
+
+        // The range must be inside the buffer
+        access_range::<Vec<u8>, Failure>(&buffer, second_start..second_end + 1)
+            .unwrap_err();
+
+        // Pointers must stay inside the range
+        access_range::<Vec<u8>, Failure>(
+            &buffer,
+            second_end - root_size..second_end,
+        )
+        .unwrap_err();
+
+        // Alignment is checked against absolute addresses
+        let mut unaligned = AlignedVec::new();
+        unaligned.push(0);
+        unaligned.extend_from_slice(&first_bytes);
+        access_range::<Vec<u8>, Failure>(&unaligned, 1..unaligned.len())
+            .unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn raw_vec() {
+        use core::mem::size_of;
+
+        use rkyv::{
+            from_bytes, primitive::ArchivedUsize, with::Raw, Deserialize,
+            RawRelPtr,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(Raw)]
+            bytes: Vec<u8>,
+            #[with(Raw)]
+            pixels: Vec<[u8; 3]>,
+        }
+
+        let value = Test {
+            bytes: (0..=255).collect(),
+            pixels: vec![[1, 2, 3], [4, 5, 6]],
+        };
+        let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let archived = access::<Test, Failure>(&bytes).unwrap();
+        assert_eq!(archived.bytes.len(), 256);
+        assert_eq!(archived.bytes[200], 200);
+        assert_eq!(archived.pixels.as_slice(), &[[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(from_bytes::<Test, Failure>(&bytes).unwrap(), value);
+
+        // The length is still checked. It follows the relative pointer to the
+        // elements.
+        let len_offset = &archived.pixels as *const _ as usize
+            - bytes.as_ptr() as usize
+            + size_of::<RawRelPtr>();
+        let len_end = len_offset + size_of::<ArchivedUsize>();
+        bytes[len_offset..len_end].fill(0xff);
+        access::<Test, Failure>(&bytes).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn sorted_vec_map() {
+        #[cfg(feature = "std")]
+        use std::collections::BTreeMap;
+
+        use rkyv::{from_bytes, with::AsSortedVec, Archived, Deserialize};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(AsSortedVec)]
+            map: BTreeMap<u32, String>,
+        }
+
+        let value = Test {
+            map: (1..=3).map(|i| (i, i.to_string())).collect(),
+        };
+        let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let archived = access::<Test, Failure>(&bytes).unwrap();
+        let key = Archived::<u32>::from_native(2);
+        assert_eq!(archived.map.get(&key).unwrap(), "2");
+        assert_eq!(from_bytes::<Test, Failure>(&bytes).unwrap(), value);
+
+        // The keys must be sorted
+        let keys_offset =
+            archived.map.keys().as_ptr() as usize - bytes.as_ptr() as usize;
+        bytes[keys_offset..keys_offset + 4]
+            .copy_from_slice(&5u32.to_le_bytes());
+        access::<Test, Failure>(&bytes).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn interned_strings() {
+        use core::{mem::size_of, slice};
+
+        use rkyv::{
+            from_bytes,
+            primitive::ArchivedUsize,
+            ser::{
+                allocator::GlobalAllocator, sharing::DedupByContent, Composite,
+            },
+            string::symbol::{ArchivedStringTable, Interned},
+            util::{serialize, AlignedVec},
+            validation::{
+                util::access_with_context, validators::DefaultValidator,
+            },
+            with::{Intern, Map},
+            Deserialize,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Clone, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(Intern)]
+            name: String,
+            #[with(Map<Intern>)]
+            idents: Vec<String>,
+        }
+
+        let value = Interned(Test {
+            name: "a long identifier".to_string(),
+            idents: vec![
+                "x".to_string(),
+                "a long identifier".to_string(),
+                "x".to_string(),
+            ],
+        });
+
+        let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = access::<Interned<Test>, Failure>(&bytes).unwrap();
+        assert_eq!(archived.strings().len(), 2);
+        assert_eq!(archived.value().idents[1], archived.value().name);
+        assert_eq!(
+            from_bytes::<Interned<Test>, Failure>(&bytes).unwrap(),
+            value
+        );
+
+        // Each entry of the string table is checked once, as a shared value
+        let mut validator = DefaultValidator::new(&bytes);
+        access_with_context::<Interned<Test>, _, Failure>(
+            &bytes,
+            &mut validator,
+        )
+        .unwrap();
+        assert_eq!(validator.shared().nodes().count(), 2);
+
+        // Content deduplication also interns strings
+        let mut serializer = DedupByContent::new(Composite::new(
+            AlignedVec::new(),
+            GlobalAllocator::new(),
+            (),
+        ));
+        serialize::<_, _, Failure>(&value, &mut serializer).unwrap();
+        let deduped = serializer.into_inner().into_writer();
+        let archived = access::<Interned<Test>, Failure>(&deduped).unwrap();
+        assert_eq!(archived.strings().len(), 2);
+        assert_eq!(
+            from_bytes::<Interned<Test>, Failure>(&deduped).unwrap(),
+            value
+        );
+
+        // Symbols must be enclosed by exactly one string table
+        to_bytes::<_, 256, Failure>(&value.0).unwrap_err();
+        to_bytes::<_, 256, Failure>(&Interned(value.clone())).unwrap_err();
+
+        // Symbols must point to an entry of the string table
+        let archived = access::<Interned<Test>, Failure>(&bytes).unwrap();
+        let base = bytes.as_ptr() as usize;
+        let strings = archived.strings() as *const ArchivedStringTable as usize;
+        let len = ArchivedUsize::from_native(1);
+        let len_bytes = unsafe {
+            slice::from_raw_parts(
+                (&len as *const ArchivedUsize).cast::<u8>(),
+                size_of::<ArchivedUsize>(),
+            )
+        };
+        let end = strings - base + size_of::<ArchivedStringTable>();
+        bytes[end - size_of::<ArchivedUsize>()..end].copy_from_slice(len_bytes);
+        access::<Interned<Test>, Failure>(&bytes).unwrap_err();
+    }
 }
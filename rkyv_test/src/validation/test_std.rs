@@ -2,7 +2,13 @@
 mod tests {
     use std::collections::{HashMap, HashSet};
 
-    use rkyv::rancor::Failure;
+    use rkyv::{
+        rancor::{Failure, Strategy},
+        ser::writer::SeekWriter,
+        util::AlignedVec,
+        validation::util::access_with_header,
+        Serialize,
+    };
     #[cfg(feature = "wasm")]
     use wasm_bindgen_test::*;
 
@@ -27,4 +33,687 @@ mod tests {
         set.insert("baz".to_string());
         serialize_and_check::<_, Failure>(&set);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_with() {
+        use rkyv::{
+            from_bytes, hash::SipHasher13, to_bytes, with::HashWith, Archive,
+            Deserialize,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(HashWith<SipHasher13>)]
+            map: HashMap<String, u32>,
+            #[with(HashWith<SipHasher13>)]
+            set: HashSet<u32>,
+        }
+
+        let value = Test {
+            map: (0..100).map(|i| (format!("key {}", i), i)).collect(),
+            set: (0..100).collect(),
+        };
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        assert_eq!(from_bytes::<Test, Failure>(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn perfect_hash_map() {
+        use rkyv::{
+            access, from_bytes, to_bytes, with::AsPerfectHash, Archive,
+            Deserialize,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(AsPerfectHash)]
+            map: HashMap<String, u32>,
+        }
+
+        let value = Test {
+            map: (0..100).map(|i| (format!("key {}", i), i)).collect(),
+        };
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        let archived = access::<Test, Failure>(&bytes).unwrap();
+        assert_eq!(archived.map.get("key 42").map(|v| v.to_native()), Some(42));
+        assert_eq!(from_bytes::<Test, Failure>(&bytes).unwrap(), value);
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(check_bytes)]
+        struct Single {
+            #[with(AsPerfectHash)]
+            map: HashMap<u32, u32>,
+        }
+
+        // The displacements of a single entry are both zero, and they are
+        // written first.
+        let value = Single {
+            map: HashMap::from([(1, 2)]),
+        };
+        let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        assert_eq!(&bytes[0..8], &[0; 8]);
+        access::<Single, Failure>(&bytes).unwrap();
+
+        // Displacements must be less than the number of entries
+        bytes[0..4].copy_from_slice(&1u32.to_le_bytes());
+        access::<Single, Failure>(&bytes).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn seek_writer_header() {
+        use std::io::Cursor;
+
+        const TYPE_TAG: u64 = 0xabcd;
+
+        let value = vec!["hello".to_string(), "world".to_string()];
+
+        let mut serializer = rkyv::ser::Composite::new(
+            SeekWriter::new::<Failure>(Cursor::new(Vec::new())).unwrap(),
+            rkyv::ser::allocator::GlobalAllocator::new(),
+            rkyv::ser::sharing::Unify::new(),
+        );
+        let root_pos = value
+            .serialize_and_resolve(Strategy::<_, Failure>::wrap(
+                &mut serializer,
+            ))
+            .unwrap();
+        // Write some trailing bytes so the root is not at the end
+        rkyv::ser::Writer::<Failure>::write(&mut serializer, &[0xff; 7])
+            .unwrap();
+        let buf = serializer
+            .into_writer()
+            .finish::<Failure>(root_pos, TYPE_TAG)
+            .unwrap()
+            .into_inner();
+
+        let mut bytes = AlignedVec::new();
+        bytes.extend_from_slice(&buf);
+
+        let archived =
+            access_with_header::<Vec<String>, Failure>(&bytes, TYPE_TAG)
+                .unwrap();
+        assert_eq!(archived, &value);
+
+        access_with_header::<Vec<String>, Failure>(&bytes, TYPE_TAG + 1)
+            .expect_err("mismatched type tags must fail");
+        access_with_header::<Vec<String>, Failure>(&bytes[..40], TYPE_TAG)
+            .expect_err("truncated archives must fail");
+
+        // The archive may not point into the header
+        bytes[8..16].copy_from_slice(&0u64.to_le_bytes());
+        access_with_header::<Vec<String>, Failure>(&bytes, TYPE_TAG)
+            .expect_err("roots inside the header must fail");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn validation_error_path() {
+        use rkyv::{
+            access, access_unchecked, to_bytes,
+            validation::path::{PathSegment, ValidationError},
+            Archive,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Inventory {
+            name: String,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Player {
+            inventory: Inventory,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Game {
+            players: Vec<Player>,
+        }
+
+        let player = |name: &str| Player {
+            inventory: Inventory {
+                name: name.to_string(),
+            },
+        };
+        let game = Game {
+            players: vec![
+                player("a string which is stored out of line"),
+                player("another string which is stored out of line"),
+            ],
+        };
+
+        let mut bytes = to_bytes::<_, 256, Failure>(&game).unwrap();
+        let offset = {
+            let archived = unsafe { access_unchecked::<Game>(&bytes) };
+            archived.players[1].inventory.name.as_ptr() as usize
+                - bytes.as_ptr() as usize
+        };
+        // Make the second player's name invalid UTF-8
+        bytes[offset] = 0xff;
+
+        let error = access::<Game, ValidationError>(&bytes).unwrap_err();
+        assert_eq!(error.path().to_string(), "root.players[1].inventory.name");
+        assert_eq!(
+            error.path().iter().copied().collect::<Vec<_>>(),
+            vec![
+                PathSegment::Field("players"),
+                PathSegment::Index(1),
+                PathSegment::Field("inventory"),
+                PathSegment::Field("name"),
+            ],
+        );
+        assert_eq!(error.offset_in(&bytes), Some(offset));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn validation_report() {
+        use std::collections::HashMap;
+
+        use rkyv::{
+            access_unchecked, to_bytes,
+            validation::util::{check_fields_with_report, check_with_report},
+            Archive,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Player {
+            name: String,
+            alive: bool,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Game {
+            players: Vec<Player>,
+            scores: Vec<Vec<bool>>,
+            round: u32,
+        }
+
+        let game = Game {
+            players: (0..4)
+                .map(|i| Player {
+                    name: format!("player number {} has a long name", i),
+                    alive: true,
+                })
+                .collect(),
+            scores: vec![vec![true, false], vec![false, true]],
+            round: 3,
+        };
+
+        let mut bytes = to_bytes::<_, 256, Failure>(&game).unwrap();
+        let report = check_with_report::<Game>(&bytes);
+        assert!(report.is_valid());
+        assert_eq!(report.errors().len(), 0);
+
+        let (name, alive, score) = {
+            let archived = unsafe { access_unchecked::<Game>(&bytes) };
+            let base = bytes.as_ptr() as usize;
+            (
+                archived.players[0].name.as_ptr() as usize - base,
+                &archived.players[2].alive as *const bool as usize - base,
+                &archived.scores[1][0] as *const bool as usize - base,
+            )
+        };
+        // Make the first player's name invalid UTF-8
+        bytes[name] = 0xff;
+        bytes[alive] = 2;
+        bytes[score] = 2;
+
+        let report = check_with_report::<Game>(&bytes);
+        assert!(!report.is_valid());
+        let paths = report
+            .errors()
+            .iter()
+            .map(|error| error.path().to_string())
+            .collect::<Vec<_>>();
+        // Checking the fields of `Game` stops at the first invalid field
+        assert_eq!(
+            paths,
+            vec!["root.players[0].name", "root.players[2].alive"]
+        );
+        assert_eq!(report.errors()[0].offset_in(&bytes), Some(name));
+
+        let intact = report
+            .intact_elements()
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(intact, vec!["root.players[1]", "root.players[3]"]);
+
+        // Checking the root fields individually continues after `players`
+        let report = check_fields_with_report::<Game>(&bytes);
+        let paths = report
+            .errors()
+            .iter()
+            .map(|error| error.path().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "root.players[0].name",
+                "root.players[2].alive",
+                "root.scores[1][0]",
+            ]
+        );
+        let intact = report
+            .intact_elements()
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            intact,
+            vec![
+                "root.players[1]",
+                "root.players[3]",
+                "root.scores[0]",
+                "root.scores[1][1]",
+                "root.round",
+            ]
+        );
+
+        // Elements of nested collections are checked after invalid siblings
+        bytes[name] = b'p';
+        bytes[alive] = 1;
+        let report = check_with_report::<Game>(&bytes);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].path().to_string(), "root.scores[1][0]");
+        let intact = report
+            .intact_elements()
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(intact, vec!["root.scores[0]", "root.scores[1][1]"]);
+
+        // Every invalid bucket of a hash table is reported
+        let map = (0..32).map(|i| (i, true)).collect::<HashMap<u32, bool>>();
+        let mut bytes = to_bytes::<_, 256, Failure>(&map).unwrap();
+        let (first, second) = {
+            let archived =
+                unsafe { access_unchecked::<HashMap<u32, bool>>(&bytes) };
+            let base = bytes.as_ptr() as usize;
+            (
+                archived.get(&3).unwrap() as *const bool as usize - base,
+                archived.get(&29).unwrap() as *const bool as usize - base,
+            )
+        };
+        bytes[first] = 2;
+        bytes[second] = 2;
+
+        let report = check_with_report::<HashMap<u32, bool>>(&bytes);
+        assert_eq!(report.errors().len(), 2);
+        for error in report.errors() {
+            assert!(error.path().to_string().ends_with(".value"));
+        }
+        assert_eq!(report.intact_elements().len(), 0);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_validation() {
+        use std::rc::Rc;
+
+        use rkyv::{
+            access, access_unchecked, to_bytes,
+            validation::{
+                path::ValidationError,
+                util::{access_parallel, access_with_context},
+                validators::ParallelValidator,
+            },
+        };
+
+        let strings = (0..2000)
+            .map(|i| format!("string number {} is stored out of line", i))
+            .collect::<Vec<_>>();
+        let bytes = to_bytes::<_, 256, Failure>(&strings).unwrap();
+        let archived = access_parallel::<Vec<String>, Failure>(&bytes).unwrap();
+        assert_eq!(archived[1234], strings[1234]);
+
+        let mut validator = ParallelValidator::new(&bytes);
+        validator.set_min_parallel_len(2);
+        access_with_context::<Vec<String>, _, Failure>(&bytes, &mut validator)
+            .unwrap();
+
+        // Collections with shared pointers are checked again in order
+        let shared = Rc::new("a shared string".to_string());
+        let rcs = (0..2000).map(|_| shared.clone()).collect::<Vec<_>>();
+        let bytes = to_bytes::<_, 256, Failure>(&rcs).unwrap();
+        access_parallel::<Vec<Rc<String>>, Failure>(&bytes).unwrap();
+
+        let mut validator = ParallelValidator::new(&bytes);
+        validator.set_min_parallel_len(2);
+        access_with_context::<Vec<Rc<String>>, _, Failure>(
+            &bytes,
+            &mut validator,
+        )
+        .unwrap();
+        let mut nodes = validator.shared().nodes();
+        assert_eq!(nodes.next().unwrap().strong_count(), 2000);
+        assert!(nodes.next().is_none());
+
+        let map = (0..2000)
+            .map(|i| (i, strings[i as usize].clone()))
+            .collect::<HashMap<u32, String>>();
+        let bytes = to_bytes::<_, 256, Failure>(&map).unwrap();
+        let archived =
+            access_parallel::<HashMap<u32, String>, Failure>(&bytes).unwrap();
+        assert_eq!(archived.len(), 2000);
+
+        // Errors are the same as sequential validation
+        let mut bytes = to_bytes::<_, 256, Failure>(&strings).unwrap();
+        let offset = {
+            let archived = unsafe { access_unchecked::<Vec<String>>(&bytes) };
+            archived[1500].as_ptr() as usize - bytes.as_ptr() as usize
+        };
+        bytes[offset] = 0xff;
+
+        let sequential =
+            access::<Vec<String>, ValidationError>(&bytes).unwrap_err();
+        let parallel = access_parallel::<Vec<String>, ValidationError>(&bytes)
+            .unwrap_err();
+        assert_eq!(parallel.path().to_string(), "root[1500]");
+        assert_eq!(parallel.path().to_string(), sequential.path().to_string(),);
+        assert_eq!(parallel.offset_in(&bytes), Some(offset));
+    }
+
+    #[cfg(feature = "pointer_width_32")]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn shared_graph() {
+        use std::{
+            ptr::addr_of,
+            rc::{Rc, Weak},
+        };
+
+        use rkyv::{
+            bytecheck::CheckBytes,
+            rancor::{Error, Fallible},
+            to_bytes,
+            util::AlignedBytes,
+            validation::{
+                path::ValidationError,
+                util::{access_pos_with_context, access_with_context},
+                validators::{DefaultValidator, SharedError},
+                ArchiveContext, SharedContext, SharedPointerKind,
+            },
+            Archive, Archived,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            a: Rc<u32>,
+            b: Rc<u32>,
+            c: Weak<u32>,
+            d: Rc<u32>,
+        }
+
+        let shared = Rc::new(10);
+        let value = Test {
+            a: shared.clone(),
+            b: shared.clone(),
+            c: Rc::downgrade(&shared),
+            d: Rc::new(20),
+        };
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        let mut validator = DefaultValidator::new(&bytes);
+        validator.shared_mut().set_record_edges(true);
+        let archived =
+            access_with_context::<Test, _, Failure>(&bytes, &mut validator)
+                .unwrap();
+
+        let shared = validator.shared();
+        assert_eq!(shared.nodes().count(), 2);
+        let a = shared.node(&*archived.a as *const _ as usize).unwrap();
+        assert_eq!(a.size(), 4);
+        assert_eq!(a.strong_count(), 2);
+        assert_eq!(a.weak_count(), 1);
+        let d = shared.node(&*archived.d as *const _ as usize).unwrap();
+        assert_eq!(d.strong_count(), 1);
+        assert_eq!(d.weak_count(), 0);
+
+        let edges = shared.edges();
+        assert_eq!(edges.len(), 4);
+        assert!(edges.iter().all(|edge| edge.from().is_none()));
+        assert_eq!(edges[2].to(), a.address());
+        assert_eq!(edges[2].kind(), SharedPointerKind::Weak);
+
+        // Pointers to the same value which disagree on its size
+        let synthetic_buf = AlignedBytes([
+            // "hello world!"
+            0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x20, 0x77, 0x6f, 0x72, 0x6c, 0x64,
+            0x21, //
+            // First shared slice
+            244u8, 255u8, 255u8, 255u8, // points 12 bytes back
+            5u8, 0u8, 0u8, 0u8, // slice is 5 bytes long
+            // Second shared slice
+            236u8, 255u8, 255u8, 255u8, // points 20 bytes back
+            0u8, 1u8, 0u8, 0u8, // slice is 256 bytes long
+        ]);
+        let mut validator = DefaultValidator::new(synthetic_buf.as_ref());
+        access_pos_with_context::<[Rc<[u8]>; 2], _, Failure>(
+            synthetic_buf.as_ref(),
+            12,
+            &mut validator,
+        )
+        .unwrap_err();
+
+        #[derive(Archive)]
+        struct Node {
+            #[omit_bounds]
+            weak: Weak<Node>,
+            #[omit_bounds]
+            strong: Option<Rc<Node>>,
+        }
+
+        unsafe impl<C> CheckBytes<C> for ArchivedNode
+        where
+            C: Fallible + ArchiveContext + SharedContext + ?Sized,
+            C::Error: Error,
+        {
+            unsafe fn check_bytes(
+                value: *const Self,
+                context: &mut C,
+            ) -> Result<(), C::Error> {
+                <Archived<Weak<Node>> as CheckBytes<C>>::check_bytes(
+                    addr_of!((*value).weak),
+                    context,
+                )?;
+                <Archived<Option<Rc<Node>>> as CheckBytes<C>>::check_bytes(
+                    addr_of!((*value).strong),
+                    context,
+                )?;
+                Ok(())
+            }
+        }
+
+        let check = |bytes: &[u8], allow_weak_cycles: bool| {
+            let mut validator = DefaultValidator::new(bytes);
+            validator
+                .shared_mut()
+                .set_allow_weak_cycles(allow_weak_cycles);
+            access_pos_with_context::<Node, _, ValidationError>(
+                bytes,
+                16,
+                &mut validator,
+            )
+            .map(|_| ())
+            .map_err(|e| {
+                match e.inner().downcast_ref::<SharedError>() {
+                    Some(SharedError::Cycle { weak, .. }) => Some(*weak),
+                    _ => None,
+                }
+            })
+        };
+
+        // A node which is its own strong pointer
+        let strong_cycle = AlignedBytes([
+            // Shared node
+            0u8, 0u8, 0u8, 0u8, // weak: None
+            0u8, 0u8, 0u8, 0u8, //
+            1u8, 0u8, 0u8, 0u8, // strong: Some
+            244u8, 255u8, 255u8, 255u8, // points 12 bytes back
+            // Root node
+            0u8, 0u8, 0u8, 0u8, // weak: None
+            0u8, 0u8, 0u8, 0u8, //
+            1u8, 0u8, 0u8, 0u8, // strong: Some
+            228u8, 255u8, 255u8, 255u8, // points 28 bytes back
+        ]);
+        assert_eq!(check(strong_cycle.as_ref(), true), Err(Some(false)));
+
+        // Parallel validation rejects the same cycles
+        #[cfg(feature = "parallel")]
+        {
+            use rkyv::validation::validators::ParallelValidator;
+
+            let mut validator = ParallelValidator::new(strong_cycle.as_ref());
+            validator.set_min_parallel_len(1);
+            access_pos_with_context::<Node, _, ValidationError>(
+                strong_cycle.as_ref(),
+                16,
+                &mut validator,
+            )
+            .unwrap_err();
+        }
+
+        // A node which is its own weak pointer
+        let weak_cycle = AlignedBytes([
+            // Shared node
+            1u8, 0u8, 0u8, 0u8, // weak: Some
+            252u8, 255u8, 255u8, 255u8, // points 4 bytes back
+            0u8, 0u8, 0u8, 0u8, // strong: None
+            0u8, 0u8, 0u8, 0u8, //
+            // Root node
+            0u8, 0u8, 0u8, 0u8, // weak: None
+            0u8, 0u8, 0u8, 0u8, //
+            1u8, 0u8, 0u8, 0u8, // strong: Some
+            228u8, 255u8, 255u8, 255u8, // points 28 bytes back
+        ]);
+        check(weak_cycle.as_ref(), true).unwrap();
+        assert_eq!(check(weak_cycle.as_ref(), false), Err(Some(true)));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn corrupted_archives() {
+        use std::{num::NonZeroU32, rc::Rc};
+
+        use rkyv::{
+            from_bytes, to_bytes,
+            with::{AsPerfectHash, Niche},
+            Archive, Deserialize,
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            strings: Vec<String>,
+            map: HashMap<String, Vec<u32>>,
+            #[with(AsPerfectHash)]
+            lookup: HashMap<String, u32>,
+            shared: Vec<Rc<String>>,
+            #[with(Niche)]
+            boxed: Option<Box<String>>,
+            #[with(Niche)]
+            nonzero: Option<NonZeroU32>,
+        }
+
+        let shared = Rc::new("a shared string".to_string());
+        let value = Test {
+            strings: vec!["short".to_string(), "a longer string".to_string()],
+            map: (0..20)
+                .map(|i| (format!("key {}", i), vec![i; i as usize % 3]))
+                .collect(),
+            lookup: (0..20).map(|i| (format!("key {}", i), i)).collect(),
+            shared: vec![shared.clone(), shared],
+            boxed: Some(Box::new("boxed".to_string())),
+            nonzero: NonZeroU32::new(42),
+        };
+        let bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+        from_bytes::<Test, Failure>(&bytes).unwrap();
+
+        // Overwrite each word with offsets and lengths, and each byte with
+        // swiss table control bytes. Validation may succeed or fail, but
+        // neither validating nor deserializing may panic.
+        let mut corrupted = AlignedVec::new();
+        for pos in (0..bytes.len() - 3).step_by(4) {
+            for word in [
+                0,
+                1,
+                4,
+                (pos as u32).wrapping_neg(),
+                bytes.len() as u32,
+                i32::MAX as u32,
+                1 << 31,
+                u32::MAX,
+            ] {
+                corrupted.clear();
+                corrupted.extend_from_slice(&bytes);
+                corrupted[pos..pos + 4].copy_from_slice(&word.to_le_bytes());
+                let _ = from_bytes::<Test, Failure>(&corrupted);
+            }
+        }
+        for pos in 0..bytes.len() {
+            for byte in [0x00, 0x7f, 0x80, 0xff] {
+                corrupted.clear();
+                corrupted.extend_from_slice(&bytes);
+                corrupted[pos] = byte;
+                let _ = from_bytes::<Test, Failure>(&corrupted);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn lazy_checked() {
+        use rkyv::{lazy::LazyAccess, to_bytes, with::Lazy, Archive};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Index {
+            #[with(Lazy)]
+            entries: HashMap<String, Vec<Box<u32>>>,
+        }
+
+        let value = Index {
+            entries: HashMap::from([
+                ("a".to_string(), vec![Box::new(1), Box::new(2)]),
+                ("b".to_string(), vec![Box::new(3), Box::new(4)]),
+            ]),
+        };
+        let mut bytes = to_bytes::<_, 256, Failure>(&value).unwrap();
+
+        // Corrupt the entries of "a" so that they point out of bounds
+        let archived = unsafe { rkyv::access_unchecked::<Index>(&bytes) };
+        let entries = unsafe { archived.entries.get_unchecked() };
+        let a = entries["a"].as_ptr() as usize - bytes.as_ptr() as usize;
+        bytes[a..a + 4].copy_from_slice(&i32::MAX.to_le_bytes());
+
+        let access = LazyAccess::new(&bytes);
+        let archived = access.root::<Index, Failure>().unwrap();
+        let entries = access.checked::<_, Failure>(&archived.entries).unwrap();
+        assert!(entries.get::<_, Failure>("c").unwrap().is_none());
+
+        let b = entries.get::<_, Failure>("b").unwrap().unwrap();
+        assert_eq!(b.len(), 2);
+        assert!(b.get::<Failure>(2).unwrap().is_none());
+        let four = b.get::<Failure>(1).unwrap().unwrap();
+        let four = four.get::<Failure>().unwrap().check::<Failure>().unwrap();
+        assert_eq!(four.to_native(), 4);
+
+        let a = entries.get::<_, Failure>("a").unwrap().unwrap();
+        a.get::<Failure>(0)
+            .unwrap()
+            .unwrap()
+            .get::<Failure>()
+            .unwrap_err();
+        a.check::<Failure>().unwrap_err();
+        entries.check::<Failure>().unwrap_err();
+    }
 }